@@ -0,0 +1,119 @@
+//! Compares the functions in `inventory_management` with `IndexedInventory` on a big storage chest.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use game_inventory::helpers::{
+    add_to_inventory, can_add_item_to_inventory, inventory_contains_item_type, quant_in_inventory,
+    remove_from_inventory,
+};
+use game_inventory::indexed_inventory::IndexedInventory;
+use game_inventory::samples::{DefaultItem, DefaultItemInstance, DefaultSlot, JUNK, SWORD, TORCH};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type ChestSlot = DefaultSlot<'static, DefaultItem<'static>, Inst>;
+
+const CHEST_SIZE: usize = 1000;
+
+/// A chest that is mostly full of junk, with a few torches at the end and some empty slots.
+fn chest() -> Vec<ChestSlot> {
+    let junk = Arc::new(JUNK.clone());
+    let torch = Arc::new(TORCH.clone());
+    (0..CHEST_SIZE)
+        .map(|i| match i {
+            i if i < CHEST_SIZE - 20 => DefaultSlot::new(Some(Inst::new(junk.clone(), 100))),
+            i if i < CHEST_SIZE - 10 => DefaultSlot::new(Some(Inst::new(torch.clone(), 50))),
+            _ => DefaultSlot::new(None),
+        })
+        .collect()
+}
+
+/// A chest that is almost empty, with a single stack of junk at the start.
+fn empty_chest() -> Vec<ChestSlot> {
+    let junk = Arc::new(JUNK.clone());
+    (0..CHEST_SIZE)
+        .map(|i| match i {
+            0 => DefaultSlot::new(Some(Inst::new(junk.clone(), 50))),
+            _ => DefaultSlot::new(None),
+        })
+        .collect()
+}
+
+fn queries(c: &mut Criterion) {
+    let plain = chest();
+    let indexed = IndexedInventory::new(chest());
+    let torches = Inst::new(Arc::new(TORCH.clone()), 60);
+
+    let mut group = c.benchmark_group("quant");
+    group.bench_function("quant_in_inventory", |b| {
+        b.iter(|| quant_in_inventory(black_box(&plain), TORCH.id()))
+    });
+    group.bench_function("IndexedInventory::quant", |b| {
+        b.iter(|| black_box(&indexed).quant(TORCH.id()))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("contains_item_type");
+    group.bench_function("inventory_contains_item_type", |b| {
+        b.iter(|| inventory_contains_item_type(black_box(&plain), SWORD.id()))
+    });
+    group.bench_function("IndexedInventory::contains_item_type", |b| {
+        b.iter(|| black_box(&indexed).contains_item_type(SWORD.id()))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("can_add");
+    group.bench_function("can_add_item_to_inventory", |b| {
+        b.iter(|| can_add_item_to_inventory(black_box(&plain), torches.clone()))
+    });
+    group.bench_function("IndexedInventory::can_add_item", |b| {
+        b.iter(|| black_box(&indexed).can_add_item(&torches))
+    });
+    group.finish();
+}
+
+fn add_and_remove(c: &mut Criterion) {
+    let mut plain = chest();
+    let mut indexed = IndexedInventory::new(chest());
+    let torches = Inst::new(Arc::new(TORCH.clone()), 60);
+
+    let mut group = c.benchmark_group("add_and_remove");
+    group.bench_function("add_to_inventory", |b| {
+        b.iter(|| {
+            add_to_inventory(&mut plain, torches.clone());
+            remove_from_inventory(&mut plain, torches.clone());
+        })
+    });
+    group.bench_function("IndexedInventory::add", |b| {
+        b.iter(|| {
+            indexed.add(torches.clone());
+            indexed.remove(torches.clone());
+        })
+    });
+    group.finish();
+}
+
+fn empty_add_and_remove(c: &mut Criterion) {
+    let mut plain = empty_chest();
+    let mut indexed = IndexedInventory::new(empty_chest());
+    let torches = Inst::new(Arc::new(TORCH.clone()), 60);
+
+    let mut group = c.benchmark_group("empty_add_and_remove");
+    group.bench_function("add_to_inventory", |b| {
+        b.iter(|| {
+            can_add_item_to_inventory(&plain, torches.clone());
+            add_to_inventory(&mut plain, torches.clone());
+            remove_from_inventory(&mut plain, torches.clone());
+        })
+    });
+    group.bench_function("IndexedInventory::add", |b| {
+        b.iter(|| {
+            indexed.can_add_item(&torches);
+            indexed.add(torches.clone());
+            indexed.remove(torches.clone());
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, queries, add_and_remove, empty_add_and_remove);
+criterion_main!(benches);
@@ -0,0 +1,433 @@
+//! Derive macros for the traits in `game_inventory`.
+//!
+//! Use them through the `derive` feature of `game_inventory`, which re-exports them
+//! next to the traits they implement:
+//! ```toml
+//! game_inventory = { version = "2.1.0", features = ["derive"] }
+//! ```
+//!
+//! Every macro is driven by attributes on the fields of a struct with named fields.
+//! Fields without an attribute are left alone, and are filled with `Default::default()`
+//! whenever the trait needs to construct the struct from scratch.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Error, Fields,
+    GenericArgument, Ident, PathArguments, Type,
+};
+
+/// Implements `Item` for a struct.
+///
+/// - `#[item(id)]` marks the field returned by `Item::id`. Its type becomes `Item::Id`, and it needs to be `Clone`.
+/// - `#[item(max_quant)]` marks the field returned by `Item::max_quant`. Its type becomes `Item::Quant`.
+/// - `#[item(stackable)]` optionally marks a `bool` field returned by `Item::stackable`.
+///   Without it, items are stackable when their max quantity is more than one.
+/// - `#[item(tags)]` optionally marks a field returned by `Item::tags`, like a `Vec<String>`
+///   or a `&'static [&'static str]`. Anything with an `iter` method over items that are `AsRef<str>` works.
+/// - `#[item(flags)]` optionally marks an `ItemFlags` field returned by `Item::flags`.
+///
+/// ```
+/// use game_inventory::traits::Item;
+///
+/// #[derive(Item)]
+/// struct Food {
+///     #[item(id)]
+///     name: &'static str,
+///     #[item(max_quant)]
+///     stack_size: u8,
+///     nutrition: u32,
+/// }
+///
+/// let apple = Food { name: "apple", stack_size: 16, nutrition: 4 };
+/// assert_eq!(apple.id(), "apple");
+/// assert_eq!(apple.max_quant(), 16);
+/// assert!(apple.stackable());
+/// ```
+#[proc_macro_derive(Item, attributes(item))]
+pub fn derive_item(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(item(input))
+}
+
+/// Implements `ItemInstance` for a struct.
+///
+/// - `#[instance(item)]` marks the field holding the item, like an `Arc<I>` or a `&'static I`.
+///   Its type becomes `ItemInstance::ItemRef`.
+/// - `#[instance(quant)]` marks the field holding the quantity. Its type needs to be the item's `Item::Quant`.
+///
+/// `ItemInstance::new` fills every other field with `Default::default()`. `with_quant` and
+/// `with_item` clone them instead, so data like durability survives splitting a stack, and
+/// `stacks_with` only merges instances whose other fields are equal. The other fields need to
+/// be `Clone` and `PartialEq`.
+///
+/// ```
+/// use game_inventory::samples::{DefaultItem, TORCH};
+/// use game_inventory::traits::{Item, ItemInstance};
+///
+/// #[derive(ItemInstance, Clone)]
+/// struct Stack {
+///     #[instance(item)]
+///     item: &'static DefaultItem<'static>,
+///     #[instance(quant)]
+///     quant: u16,
+///     durability: u32,
+/// }
+///
+/// let mut torches = Stack::new(&TORCH, 12);
+/// assert_eq!(torches.item().id(), "torch");
+/// assert_eq!(torches.quant(), 12);
+/// assert_eq!(torches.durability, 0);
+///
+/// torches.durability = 40;
+/// let half = torches.with_quant(6);
+/// assert_eq!(half.durability, 40);
+/// assert!(half.stacks_with(&torches));
+/// assert!(!half.stacks_with(&Stack::new(&TORCH, 6)));
+/// ```
+#[proc_macro_derive(ItemInstance, attributes(instance))]
+pub fn derive_item_instance(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(item_instance(input))
+}
+
+/// Implements `Slot` for a struct, for every item the stored item instance works with.
+///
+/// - `#[slot(instance)]` marks the `Option<II>` field holding the item instance. `II` needs to be `Clone`.
+/// - `#[slot(modified)]` marks the `bool` field holding whether the slot was modified.
+///
+/// `Slot::transfer` keeps its default behaviour, and `Slot::new` fills every other field
+/// with `Default::default()`.
+///
+/// ```
+/// use game_inventory::helpers::add_to_inventory;
+/// use game_inventory::samples::{DefaultItem, DefaultItemInstance, TORCH_INST};
+/// use game_inventory::traits::{ItemInstance, Slot};
+///
+/// #[derive(Slot)]
+/// struct HotbarSlot {
+///     #[slot(instance)]
+///     contents: Option<DefaultItemInstance<DefaultItem<'static>>>,
+///     #[slot(modified)]
+///     dirty: bool,
+///     key: char,
+/// }
+///
+/// let mut hotbar = vec![HotbarSlot::new(None), HotbarSlot::new(None)];
+/// add_to_inventory(&mut hotbar, TORCH_INST.clone().unwrap());
+/// assert_eq!(hotbar[0].item_instance().unwrap().quant(), 23);
+/// assert!(hotbar[0].dirty);
+/// assert!(!hotbar[1].dirty);
+/// ```
+#[proc_macro_derive(Slot, attributes(slot))]
+pub fn derive_slot(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(slot(input))
+}
+
+fn expand(res: Result<TokenStream2, Error>) -> TokenStream {
+    match res {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// A field marked with one of the derive attributes.
+struct Marked {
+    ident: Ident,
+    ty: Type,
+}
+
+/// The fields of a struct, split into the ones marked with each key of an attribute and the rest.
+struct MarkedFields {
+    marked: Vec<(String, Marked)>,
+    unmarked: Vec<Marked>,
+}
+
+impl MarkedFields {
+    fn parse(input: &DeriveInput, attr: &str, keys: &[&str]) -> Result<Self, Error> {
+        let fields = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                _ => {
+                    return Err(Error::new(
+                        input.ident.span(),
+                        "can only be derived for structs with named fields",
+                    ))
+                }
+            },
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "can only be derived for structs with named fields",
+                ))
+            }
+        };
+        let mut res = MarkedFields {
+            marked: Vec::new(),
+            unmarked: Vec::new(),
+        };
+        for field in fields {
+            let ident = match &field.ident {
+                Some(ident) => ident.clone(),
+                None => unreachable!("named fields always have an ident"),
+            };
+            let mut keys_on_field = Vec::new();
+            for a in field.attrs.iter().filter(|a| a.path().is_ident(attr)) {
+                a.parse_nested_meta(|meta| {
+                    match keys.iter().find(|key| meta.path.is_ident(key)) {
+                        Some(key) => {
+                            keys_on_field.push(key.to_string());
+                            Ok(())
+                        }
+                        None => Err(meta.error(format!(
+                            "unknown {} attribute, expected {}",
+                            attr,
+                            expected(keys)
+                        ))),
+                    }
+                })?;
+            }
+            if keys_on_field.is_empty() {
+                res.unmarked.push(Marked {
+                    ident,
+                    ty: field.ty.clone(),
+                });
+                continue;
+            }
+            for key in keys_on_field {
+                if res.marked.iter().any(|(other, _)| *other == key) {
+                    return Err(Error::new(
+                        ident.span(),
+                        format!("only one field can be marked #[{}({})]", attr, key),
+                    ));
+                }
+                res.marked.push((
+                    key,
+                    Marked {
+                        ident: ident.clone(),
+                        ty: field.ty.clone(),
+                    },
+                ));
+            }
+        }
+        Ok(res)
+    }
+
+    fn get(&self, key: &str) -> Option<&Marked> {
+        self.marked
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, field)| field)
+    }
+
+    fn require(&self, input: &DeriveInput, attr: &str, key: &str) -> Result<&Marked, Error> {
+        match self.get(key) {
+            Some(field) => Ok(field),
+            None => Err(Error::new(
+                input.ident.span(),
+                format!("missing a field marked #[{}({})]", attr, key),
+            )),
+        }
+    }
+}
+
+fn expected(keys: &[&str]) -> String {
+    let keys: Vec<String> = keys.iter().map(|key| format!("`{}`", key)).collect();
+    match keys.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+fn item(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = MarkedFields::parse(
+        &input,
+        "item",
+        &["id", "max_quant", "stackable", "tags", "flags"],
+    )?;
+    let id = fields.require(&input, "item", "id")?;
+    let max_quant = fields.require(&input, "item", "max_quant")?;
+    let (id_field, id_ty) = (&id.ident, &id.ty);
+    let (quant_field, quant_ty) = (&max_quant.ident, &max_quant.ty);
+    let stackable = match fields.get("stackable") {
+        Some(field) => {
+            let ident = &field.ident;
+            quote!(self.#ident)
+        }
+        None => quote! {
+            self.#quant_field > <#quant_ty as ::game_inventory::traits::Quantity>::ONE
+        },
+    };
+    let tags = fields.get("tags").map(|field| {
+        let ident = &field.ident;
+        quote! {
+            fn tags(&self) -> impl ::core::iter::Iterator<Item = &str> {
+                self.#ident.iter().map(|tag| ::core::convert::AsRef::<str>::as_ref(tag))
+            }
+        }
+    });
+    let flags = fields.get("flags").map(|field| {
+        let ident = &field.ident;
+        quote! {
+            fn flags(&self) -> ::game_inventory::restrictions::ItemFlags {
+                self.#ident
+            }
+        }
+    });
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::game_inventory::traits::Item for #name #ty_generics #where_clause {
+            type Id = #id_ty;
+            type Quant = #quant_ty;
+            fn stackable(&self) -> bool {
+                #stackable
+            }
+            fn max_quant(&self) -> #quant_ty {
+                self.#quant_field
+            }
+            fn id(&self) -> #id_ty {
+                ::core::clone::Clone::clone(&self.#id_field)
+            }
+            #tags
+            #flags
+        }
+    })
+}
+
+fn item_instance(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = MarkedFields::parse(&input, "instance", &["item", "quant"])?;
+    let item = fields.require(&input, "instance", "item")?;
+    let quant = fields.require(&input, "instance", "quant")?;
+    let (item_field, item_ty) = (&item.ident, &item.ty);
+    let quant_field = &quant.ident;
+    // Spanned to the field, so a quantity of the wrong type is reported there.
+    let get_quant = quote_spanned!(quant.ty.span()=> self.#quant_field);
+    let set_quant = quote_spanned!(quant.ty.span()=> #quant_field: quantity);
+    let others: Vec<&Ident> = fields.unmarked.iter().map(|field| &field.ident).collect();
+    let item_type = quote!(<#item_ty as ::core::ops::Deref>::Target);
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote!(#item_type: ::game_inventory::traits::Item));
+    for field in &fields.unmarked {
+        let ty = &field.ty;
+        where_clause.predicates.push(parse_quote!(
+            #ty: ::core::clone::Clone + ::core::cmp::PartialEq
+        ));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::game_inventory::traits::ItemInstance<#item_type> for #name #ty_generics #where_clause {
+            type ItemRef = #item_ty;
+            fn quant(&self) -> <#item_type as ::game_inventory::traits::Item>::Quant {
+                #get_quant
+            }
+            fn item(&self) -> #item_ty {
+                ::core::clone::Clone::clone(&self.#item_field)
+            }
+            fn new(item: #item_ty, quantity: <#item_type as ::game_inventory::traits::Item>::Quant) -> Self {
+                #name {
+                    #item_field: item,
+                    #set_quant,
+                    #(#others: ::core::default::Default::default(),)*
+                }
+            }
+            fn with_quant(&self, quantity: <#item_type as ::game_inventory::traits::Item>::Quant) -> Self {
+                #name {
+                    #item_field: ::core::clone::Clone::clone(&self.#item_field),
+                    #set_quant,
+                    #(#others: ::core::clone::Clone::clone(&self.#others),)*
+                }
+            }
+            fn with_item(&self, item: #item_ty) -> Self {
+                #name {
+                    #item_field: item,
+                    #quant_field: self.#quant_field,
+                    #(#others: ::core::clone::Clone::clone(&self.#others),)*
+                }
+            }
+            fn stacks_with(&self, other: &Self) -> bool {
+                true #(&& self.#others == other.#others)*
+            }
+        }
+    })
+}
+
+fn slot(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = MarkedFields::parse(&input, "slot", &["instance", "modified"])?;
+    let instance = fields.require(&input, "slot", "instance")?;
+    let modified = fields.require(&input, "slot", "modified")?;
+    let instance_field = &instance.ident;
+    let modified_field = &modified.ident;
+    let instance_ty = match option_inner(&instance.ty) {
+        Some(ty) => ty,
+        None => {
+            return Err(Error::new(
+                instance.ty.span(),
+                "the #[slot(instance)] field needs to be an `Option` of an item instance",
+            ))
+        }
+    };
+    let others: Vec<&Ident> = fields.unmarked.iter().map(|field| &field.ident).collect();
+
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    generics
+        .params
+        .push(parse_quote!(__I: ::game_inventory::traits::Item));
+    generics.make_where_clause().predicates.push(parse_quote!(
+        #instance_ty: ::game_inventory::traits::ItemInstance<__I> + ::core::clone::Clone
+    ));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::game_inventory::traits::Slot<__I, #instance_ty> for #name #ty_generics #where_clause {
+            fn item_instance(&self) -> ::core::option::Option<#instance_ty> {
+                ::core::clone::Clone::clone(&self.#instance_field)
+            }
+            fn set_item_instance(&mut self, item_instance: &::core::option::Option<#instance_ty>) {
+                self.#modified_field = true;
+                self.#instance_field = ::core::clone::Clone::clone(item_instance);
+            }
+            fn modified(&mut self) -> bool {
+                self.#modified_field
+            }
+            fn set_modified(&mut self, modified: bool) {
+                self.#modified_field = modified;
+            }
+            fn new(item_instance: ::core::option::Option<#instance_ty>) -> Self {
+                #name {
+                    #instance_field: item_instance,
+                    #modified_field: false,
+                    #(#others: ::core::default::Default::default(),)*
+                }
+            }
+        }
+    })
+}
+
+/// Gets `T` out of `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => args,
+        _ => return None,
+    };
+    match &args.args[0] {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
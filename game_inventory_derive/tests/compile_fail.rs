@@ -0,0 +1,5 @@
+#[test]
+fn misuse_is_rejected() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,14 @@
+use game_inventory::samples::DefaultItem;
+use game_inventory::traits::ItemInstance;
+
+#[derive(ItemInstance, Clone)]
+struct Stack {
+    #[instance(item)]
+    item: &'static DefaultItem<'static>,
+    #[instance(quant)]
+    quant: u16,
+    #[instance(quant)]
+    durability: u16,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use game_inventory::samples::DefaultItem;
+use game_inventory::traits::ItemInstance;
+
+#[derive(ItemInstance, Clone)]
+struct Stack {
+    #[instance(item)]
+    item: &'static DefaultItem<'static>,
+    #[instance(quant)]
+    quant: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use game_inventory::traits::Item;
+
+#[derive(Item)]
+struct Food {
+    name: String,
+    #[item(max_quant)]
+    stack_size: u8,
+}
+
+fn main() {}
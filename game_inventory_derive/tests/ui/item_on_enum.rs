@@ -0,0 +1,9 @@
+use game_inventory::traits::Item;
+
+#[derive(Item)]
+enum Food {
+    Apple,
+    Bread,
+}
+
+fn main() {}
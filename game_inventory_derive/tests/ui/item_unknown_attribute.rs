@@ -0,0 +1,11 @@
+use game_inventory::traits::Item;
+
+#[derive(Item)]
+struct Food {
+    #[item(id)]
+    name: String,
+    #[item(max_quantity)]
+    stack_size: u8,
+}
+
+fn main() {}
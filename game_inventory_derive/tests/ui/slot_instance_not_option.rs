@@ -0,0 +1,12 @@
+use game_inventory::samples::{DefaultItem, DefaultItemInstance};
+use game_inventory::traits::Slot;
+
+#[derive(Slot)]
+struct Cell {
+    #[slot(instance)]
+    contents: DefaultItemInstance<DefaultItem<'static>>,
+    #[slot(modified)]
+    modified: bool,
+}
+
+fn main() {}
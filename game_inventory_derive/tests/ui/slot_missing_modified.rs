@@ -0,0 +1,10 @@
+use game_inventory::samples::{DefaultItem, DefaultItemInstance};
+use game_inventory::traits::Slot;
+
+#[derive(Slot)]
+struct Cell {
+    #[slot(instance)]
+    contents: Option<DefaultItemInstance<DefaultItem<'static>>>,
+}
+
+fn main() {}
@@ -0,0 +1,398 @@
+//! Equipment slots for a character, and the stats the equipped items add up to.
+//!
+//! `Equipment` holds one `Slot` per `EquipSlot`. Items say where they go and what they do
+//! by implementing `Equippable`. Equipping takes an item out of a backpack inventory and
+//! puts whatever it replaces back in, so nothing is ever lost.
+//!
+//! Two-handed items live in the main hand, and keep the off hand empty while equipped.
+use crate::inventory_management::{add_to_inventory, can_add_item_to_inventory, take_from_slot};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Debug, Display};
+use core::marker::PhantomData;
+
+/// A place on a character where an item can be equipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EquipSlot {
+    Head,
+    Chest,
+    Legs,
+    Feet,
+    MainHand,
+    OffHand,
+}
+
+impl EquipSlot {
+    /// Every equipment slot, in the order `Equipment` stores them.
+    pub const ALL: [EquipSlot; 6] = [
+        EquipSlot::Head,
+        EquipSlot::Chest,
+        EquipSlot::Legs,
+        EquipSlot::Feet,
+        EquipSlot::MainHand,
+        EquipSlot::OffHand,
+    ];
+}
+
+/// A change an item makes to one of a character's stats.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Modifier<S> {
+    /// Adds to the base value of the stat.
+    Add(S, f32),
+    /// Multiplies the stat after every `Add`, so `Multiply(stat, 1.1)` is 10% more.
+    Multiply(S, f32),
+}
+
+/// Modifiers that apply once enough items of a set are equipped.
+///
+/// Give a set several bonuses with different `pieces` for bonuses that build on each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetBonus<Set, S> {
+    pub set: Set,
+    pub pieces: usize,
+    pub modifiers: Vec<Modifier<S>>,
+}
+
+/// Items that can be equipped.
+pub trait Equippable: Item {
+    /// Identifies a stat, like an enum of every stat in the game or its name.
+    type Stat: PartialEq + Clone;
+    /// Identifies an item set.
+    type Set: PartialEq;
+
+    /// Where the item goes, or `None` if it can not be equipped.
+    fn equip_slot(&self) -> Option<EquipSlot>;
+
+    /// Whether the item takes up both hands. Two-handed items are always equipped in the main hand.
+    fn two_handed(&self) -> bool {
+        false
+    }
+
+    /// What the item does to the stats of whoever has it equipped.
+    fn modifiers(&self) -> &[Modifier<Self::Stat>] {
+        &[]
+    }
+
+    /// The set the item belongs to, if any.
+    fn set(&self) -> Option<Self::Set> {
+        None
+    }
+}
+
+/// Why an item could not be equipped or unequipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipError {
+    /// There is no item to equip or unequip.
+    EmptySlot,
+    /// The item has no `EquipSlot`.
+    NotEquippable,
+    /// The backpack has no room for the items that would be taken off.
+    BackpackFull,
+    /// The backpack slot is locked or its item is reserved, or the equipment slot is locked.
+    Locked,
+}
+
+impl Display for EquipError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            EquipError::EmptySlot => "There is no item in that slot.",
+            EquipError::NotEquippable => "That item can not be equipped.",
+            EquipError::BackpackFull => "There is no room in the backpack for the replaced items.",
+            EquipError::Locked => "That item is locked or reserved.",
+        })
+    }
+}
+
+impl Error for EquipError {}
+
+/// The sum of a number of modifiers, per stat.
+///
+/// ```
+/// # use game_inventory::equipment::{Modifier, StatTotals};
+/// let mut totals = StatTotals::new();
+/// totals.apply(&Modifier::Add("armor", 5.0));
+/// totals.apply(&Modifier::Multiply("armor", 2.0));
+/// totals.apply(&Modifier::Add("armor", 3.0));
+/// assert_eq!(totals.total(&"armor", 10.0), 36.0);
+/// assert_eq!(totals.total(&"speed", 10.0), 10.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatTotals<S> {
+    /// Every stat with a modifier, with its added value and multiplier.
+    stats: Vec<(S, f32, f32)>,
+}
+
+impl<S> Default for StatTotals<S> {
+    fn default() -> Self {
+        StatTotals { stats: Vec::new() }
+    }
+}
+
+impl<S: PartialEq + Clone> StatTotals<S> {
+    /// Totals with no modifiers applied.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a modifier to the totals. The order modifiers are applied in does not matter.
+    pub fn apply(&mut self, modifier: &Modifier<S>) {
+        let (stat, add, mul) = match modifier {
+            Modifier::Add(stat, value) => (stat, *value, 1.0),
+            Modifier::Multiply(stat, value) => (stat, 0.0, *value),
+        };
+        match self.stats.iter_mut().find(|entry| entry.0 == *stat) {
+            Some(entry) => {
+                entry.1 += add;
+                entry.2 *= mul;
+            }
+            None => self.stats.push((stat.clone(), add, mul)),
+        }
+    }
+
+    /// The sum of every `Add` modifier for a stat.
+    pub fn added(&self, stat: &S) -> f32 {
+        match self.stats.iter().find(|entry| entry.0 == *stat) {
+            Some(entry) => entry.1,
+            None => 0.0,
+        }
+    }
+
+    /// The product of every `Multiply` modifier for a stat.
+    pub fn multiplier(&self, stat: &S) -> f32 {
+        match self.stats.iter().find(|entry| entry.0 == *stat) {
+            Some(entry) => entry.2,
+            None => 1.0,
+        }
+    }
+
+    /// The value of a stat with a base value, once every modifier is applied.
+    pub fn total(&self, stat: &S, base: f32) -> f32 {
+        (base + self.added(stat)) * self.multiplier(stat)
+    }
+}
+
+/// One slot per `EquipSlot`.
+///
+/// ```
+/// # use game_inventory::equipment::{EquipSlot, Equipment, Modifier, SetBonus};
+/// # use game_inventory::samples::{DefaultItemInstance, DefaultSlot, Gear};
+/// # use game_inventory::traits::{ItemInstance, Slot};
+/// # use std::sync::Arc;
+/// let piece = |name, equip_slot, armor| {
+///     let gear = Gear {
+///         name,
+///         max_quantity: 1,
+///         equip_slot: Some(equip_slot),
+///         two_handed: false,
+///         set: Some("iron"),
+///         modifiers: vec![Modifier::Add("armor", armor)],
+///     };
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(gear), 1)))
+/// };
+/// let mut backpack = vec![
+///     piece("iron helmet", EquipSlot::Head, 2.0),
+///     piece("iron chestplate", EquipSlot::Chest, 5.0),
+/// ];
+/// let mut equipment: Equipment<_, _, DefaultSlot<_, _>> = Equipment::new();
+/// equipment.equip(&mut backpack, 0).unwrap();
+/// equipment.equip(&mut backpack, 1).unwrap();
+/// assert!(backpack.iter().all(|slot| slot.item_instance().is_none()));
+///
+/// let bonuses = [SetBonus { set: "iron", pieces: 2, modifiers: vec![Modifier::Multiply("armor", 1.5)] }];
+/// assert_eq!(equipment.stats(&bonuses).total(&"armor", 1.0), 12.0);
+/// ```
+pub struct Equipment<I: Equippable, II: ItemInstance<I>, S: Slot<I, II>> {
+    slots: [S; 6],
+    phantom: PhantomData<(I, II)>,
+}
+
+impl<I: Equippable, II: ItemInstance<I>, S: Slot<I, II> + Debug> Debug for Equipment<I, II, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Equipment")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
+
+impl<I: Equippable, II: ItemInstance<I>, S: Slot<I, II>> Default for Equipment<I, II, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Equippable, II: ItemInstance<I>, S: Slot<I, II>> Equipment<I, II, S> {
+    /// Creates equipment with nothing equipped.
+    pub fn new() -> Self {
+        Equipment {
+            slots: core::array::from_fn(|_| S::new(None)),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The slot backing an `EquipSlot`, for drawing it.
+    ///
+    /// The off hand slot stays empty while a two-handed item is equipped.
+    pub fn slot(&self, slot: EquipSlot) -> &S {
+        &self.slots[slot as usize]
+    }
+
+    /// The item taking up an `EquipSlot`, including a two-handed item in the off hand.
+    pub fn get(&self, slot: EquipSlot) -> Option<II> {
+        self.slots[self.resolve(slot) as usize].item_instance()
+    }
+
+    /// Every equipped item, once each, with the slot it is stored in.
+    pub fn equipped(&self) -> impl Iterator<Item = (EquipSlot, II)> + '_ {
+        EquipSlot::ALL
+            .into_iter()
+            .filter_map(|slot| Some((slot, self.slots[slot as usize].item_instance()?)))
+    }
+
+    /// How many equipped items belong to a set.
+    pub fn set_pieces(&self, set: &I::Set) -> usize {
+        self.equipped()
+            .filter(|(_, ii)| ii.item().set().as_ref() == Some(set))
+            .count()
+    }
+
+    /// Adds up the modifiers of every equipped item, and of every set bonus with enough pieces equipped.
+    pub fn stats(&self, set_bonuses: &[SetBonus<I::Set, I::Stat>]) -> StatTotals<I::Stat> {
+        let mut totals = StatTotals::new();
+        for (_, ii) in self.equipped() {
+            ii.item()
+                .modifiers()
+                .iter()
+                .for_each(|modifier| totals.apply(modifier));
+        }
+        for bonus in set_bonuses {
+            if self.set_pieces(&bonus.set) >= bonus.pieces {
+                bonus
+                    .modifiers
+                    .iter()
+                    .for_each(|modifier| totals.apply(modifier));
+            }
+        }
+        totals
+    }
+
+    fn two_handed_equipped(&self) -> bool {
+        match self.slots[EquipSlot::MainHand as usize].item_instance() {
+            Some(ii) => ii.item().two_handed(),
+            None => false,
+        }
+    }
+
+    fn resolve(&self, slot: EquipSlot) -> EquipSlot {
+        if slot == EquipSlot::OffHand && self.two_handed_equipped() {
+            return EquipSlot::MainHand;
+        }
+        slot
+    }
+}
+
+impl<I: Equippable, II: ItemInstance<I> + Clone, S: Slot<I, II>> Equipment<I, II, S> {
+    /// Equips the item in a backpack slot, putting the items it replaces back into the backpack.
+    ///
+    /// Only one item of a stack is equipped, and the rest stay in the backpack slot. Otherwise the
+    /// first replaced item takes the place of the equipped one. Any other replaced items, like
+    /// when equipping a two-handed item, go into the first empty backpack slots.
+    /// Nothing changes if there is no room for them.
+    ///
+    /// Returns `EquipError::EmptySlot` if `index` is out of bounds or the slot is empty,
+    /// and `EquipError::Locked` if the backpack slot is locked or any of its items are reserved,
+    /// or an equipment slot it would clear is locked.
+    pub fn equip<B: Slot<I, II>>(
+        &mut self,
+        backpack: &mut [B],
+        index: usize,
+    ) -> Result<(), EquipError> {
+        let ii = match backpack.get(index).and_then(|slot| slot.item_instance()) {
+            Some(ii) => ii,
+            None => return Err(EquipError::EmptySlot),
+        };
+        if backpack[index].locked() || backpack[index].reserved() > I::Quant::ZERO {
+            return Err(EquipError::Locked);
+        }
+        let item = ii.item();
+        let mut cleared = match item.equip_slot() {
+            Some(_) if item.two_handed() => Vec::from([EquipSlot::MainHand, EquipSlot::OffHand]),
+            Some(slot) => Vec::from([slot]),
+            None => return Err(EquipError::NotEquippable),
+        };
+        let target = cleared[0];
+        if target == EquipSlot::OffHand && self.two_handed_equipped() {
+            cleared.push(EquipSlot::MainHand);
+        }
+        if cleared
+            .iter()
+            .any(|&slot| self.slots[slot as usize].locked())
+        {
+            return Err(EquipError::Locked);
+        }
+
+        let replaced: Vec<II> = cleared
+            .iter()
+            .filter_map(|&slot| self.slots[slot as usize].item_instance())
+            .collect();
+        let rest_stays = item.stackable() && ii.quant() > I::Quant::ONE;
+        let needed = match rest_stays {
+            true => replaced.len(),
+            false => replaced.len().saturating_sub(1),
+        };
+        let free: Vec<usize> = backpack
+            .iter()
+            .enumerate()
+            .filter(|(i, slot)| *i != index && !slot.locked() && slot.item_instance().is_none())
+            .map(|(i, _)| i)
+            .take(needed)
+            .collect();
+        if free.len() < needed {
+            return Err(EquipError::BackpackFull);
+        }
+
+        for slot in cleared {
+            self.slots[slot as usize].set_item_instance(&None);
+        }
+        let mut replaced = replaced.into_iter();
+        match rest_stays {
+            true => {
+                let equipped = ii.with_quant(I::Quant::ONE);
+                take_from_slot(I::Quant::ONE, &mut backpack[index], ii);
+                self.slots[target as usize].set_item_instance(&Some(equipped));
+            }
+            false => {
+                self.slots[target as usize].set_item_instance(&Some(ii));
+                backpack[index].set_item_instance(&replaced.next());
+            }
+        }
+        for (i, ii) in free.into_iter().zip(replaced) {
+            backpack[i].set_item_instance(&Some(ii));
+        }
+        Ok(())
+    }
+
+    /// Takes off the item in an `EquipSlot`, adding it to the backpack like `add_to_inventory`.
+    ///
+    /// Nothing changes if it does not fit into the backpack, or the equipment slot is locked.
+    pub fn unequip<B: Slot<I, II>>(
+        &mut self,
+        slot: EquipSlot,
+        backpack: &mut [B],
+    ) -> Result<(), EquipError> {
+        let slot = self.resolve(slot);
+        let ii = match self.slots[slot as usize].item_instance() {
+            Some(ii) => ii,
+            None => return Err(EquipError::EmptySlot),
+        };
+        if self.slots[slot as usize].locked() {
+            return Err(EquipError::Locked);
+        }
+        if !can_add_item_to_inventory(backpack, ii.clone()) {
+            return Err(EquipError::BackpackFull);
+        }
+        add_to_inventory(backpack, ii);
+        self.slots[slot as usize].set_item_instance(&None);
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! An inventory with a fixed amount of slots, that never allocates.
+//!
+//! Useful for hotbars, equipment screens, and targets without an allocator.
+//! `FixedInventory` derefs to `[S]`, so every function in `inventory_management` works on it.
+use crate::traits::{Item, ItemInstance, Slot};
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+/// A `[Slot; N]` that starts out with every slot empty.
+///
+/// ```
+/// # use game_inventory::fixed_inventory::FixedInventory;
+/// # use game_inventory::samples::{DefaultItem, DefaultSlot, RefItemInstance};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use game_inventory::helpers::{add_to_inventory, quant_in_inventory};
+/// static ARROW: DefaultItem = DefaultItem { name: "arrow", max_quantity: 64 };
+///
+/// let mut quiver: FixedInventory<_, _, DefaultSlot<_, _>, 2> = FixedInventory::new();
+/// for _ in 0..2 {
+///     assert!(add_to_inventory(&mut quiver, RefItemInstance::new(&ARROW, 60)).is_none());
+/// }
+/// let left = add_to_inventory(&mut quiver, RefItemInstance::new(&ARROW, 40)).unwrap();
+/// assert_eq!(left.quant(), 32);
+/// assert_eq!(quant_in_inventory(&quiver, ARROW.id()), 128);
+/// ```
+pub struct FixedInventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>, const N: usize> {
+    slots: [S; N],
+    phantom: PhantomData<(I, II)>,
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II> + Debug, const N: usize> Debug
+    for FixedInventory<I, II, S, N>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FixedInventory")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>, const N: usize> Default
+    for FixedInventory<I, II, S, N>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>, const N: usize> Deref
+    for FixedInventory<I, II, S, N>
+{
+    type Target = [S];
+
+    fn deref(&self) -> &Self::Target {
+        &self.slots
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>, const N: usize> DerefMut
+    for FixedInventory<I, II, S, N>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.slots
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>, const N: usize> From<[S; N]>
+    for FixedInventory<I, II, S, N>
+{
+    fn from(slots: [S; N]) -> Self {
+        FixedInventory {
+            slots,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>, const N: usize> FixedInventory<I, II, S, N> {
+    /// Creates an inventory where every slot is empty.
+    pub fn new() -> Self {
+        core::array::from_fn(|_| S::new(None)).into()
+    }
+
+    /// Gives back the slots.
+    pub fn into_inner(self) -> [S; N] {
+        self.slots
+    }
+}
@@ -0,0 +1,290 @@
+//! Rolling items with a rarity and random affixes, like the loot of an action RPG.
+//!
+//! An `ItemGenerator` holds the `Rarity` tiers an item can roll and a pool of `AffixDef`s.
+//! Rolling first picks a rarity by weight, then up to the rarity's number of prefixes and
+//! suffixes by weight. Affixes in the same exclusion group never roll together, and each value
+//! is rolled from the range of the affix's tier for that rarity.
+//!
+//! All randomness comes from an `Rng`, so rolls can be replayed from a seed with `SeededRng`.
+//! Rolled instances are built through `Rollable`, and should not stack with each other,
+//! see `ItemInstance::stacks_with`.
+//!
+//! ```
+//! # use game_inventory::generation::{AffixDef, AffixKind, ItemGenerator, Rarity, SeededRng};
+//! let mut generator = ItemGenerator::new();
+//! generator.add_rarity(Rarity { id: "magic", weight: 1, prefixes: 1, suffixes: 1, tier: 0 });
+//! generator.add_affix(AffixDef {
+//!     id: "sharp",
+//!     kind: AffixKind::Prefix,
+//!     weight: 1,
+//!     group: None,
+//!     tiers: vec![(5, 10)],
+//! });
+//! let rolls = generator.roll(&mut SeededRng::new(7)).unwrap();
+//! assert_eq!(rolls.rarity, "magic");
+//! assert_eq!(rolls.affixes.len(), 1);
+//! assert!((5..=10).contains(&rolls.affixes[0].value));
+//! // The same seed always rolls the same item.
+//! assert_eq!(generator.roll(&mut SeededRng::new(7)), Some(rolls));
+//! ```
+use crate::traits::{Item, ItemInstance};
+use alloc::vec::Vec;
+
+/// A source of random numbers for rolling items.
+///
+/// Implement it for the random number generator your game already uses.
+pub trait Rng {
+    /// A random `u64`, with every value equally likely.
+    fn next_u64(&mut self) -> u64;
+    /// A random number below `bound`, which must not be 0.
+    fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/// A small random number generator that always produces the same numbers from the same seed.
+///
+/// Uses SplitMix64. It is fast and good enough for loot, but not for anything that needs to be secure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Whether an affix goes before or after the item's name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+/// A rarity an item can roll, like magic or rare.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rarity<K> {
+    pub id: K,
+    /// How likely the rarity is, compared to the weights of the other rarities.
+    pub weight: u32,
+    /// The most prefixes an item of this rarity rolls.
+    pub prefixes: usize,
+    /// The most suffixes an item of this rarity rolls.
+    pub suffixes: usize,
+    /// Which of each affix's `tiers` its value is rolled from.
+    pub tier: usize,
+}
+
+/// An affix that can roll on an item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AffixDef<K> {
+    pub id: K,
+    pub kind: AffixKind,
+    /// How likely the affix is, compared to the weights of the other affixes of its kind.
+    pub weight: u32,
+    /// Affixes in the same group never roll on the same item.
+    pub group: Option<K>,
+    /// The smallest and biggest value of the affix at each tier, both included.
+    ///
+    /// Rarities with a tier past the last one use the last one. Affixes without tiers never roll.
+    pub tiers: Vec<(i32, i32)>,
+}
+
+/// An affix rolled onto an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RolledAffix<K> {
+    pub id: K,
+    pub kind: AffixKind,
+    /// The tier the value was rolled from.
+    pub tier: usize,
+    pub value: i32,
+}
+
+/// The rarity and affixes an item rolled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rolls<K> {
+    pub rarity: K,
+    /// The prefixes first, then the suffixes, each in the order they were rolled.
+    pub affixes: Vec<RolledAffix<K>>,
+}
+
+impl<K: PartialEq> Rolls<K> {
+    /// The rolled affix with an id, if it rolled.
+    pub fn get(&self, id: &K) -> Option<&RolledAffix<K>> {
+        self.affixes.iter().find(|affix| affix.id == *id)
+    }
+
+    /// The rolled affixes of a kind.
+    pub fn of_kind(&self, kind: AffixKind) -> impl Iterator<Item = &RolledAffix<K>> {
+        self.affixes.iter().filter(move |affix| affix.kind == kind)
+    }
+}
+
+/// An item instance that can be created with rolls.
+pub trait Rollable<I: Item, K>: ItemInstance<I> {
+    /// Creates a single rolled item.
+    fn rolled(item: Self::ItemRef, rolls: Rolls<K>) -> Self;
+}
+
+/// The rarities and affix pool items are rolled from.
+///
+/// Use one generator per kind of item that rolls different affixes, like weapons and armor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemGenerator<K> {
+    rarities: Vec<Rarity<K>>,
+    affixes: Vec<AffixDef<K>>,
+}
+
+impl<K> Default for ItemGenerator<K> {
+    fn default() -> Self {
+        ItemGenerator {
+            rarities: Vec::new(),
+            affixes: Vec::new(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> ItemGenerator<K> {
+    /// Creates a generator without any rarities or affixes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rarity items can roll.
+    pub fn add_rarity(&mut self, rarity: Rarity<K>) {
+        self.rarities.push(rarity);
+    }
+
+    /// Adds an affix to the pool.
+    pub fn add_affix(&mut self, affix: AffixDef<K>) {
+        self.affixes.push(affix);
+    }
+
+    /// Every rarity, in the order they were added.
+    pub fn rarities(&self) -> &[Rarity<K>] {
+        &self.rarities
+    }
+
+    /// The affix pool, in the order the affixes were added.
+    pub fn affixes(&self) -> &[AffixDef<K>] {
+        &self.affixes
+    }
+
+    /// Rolls a rarity by weight, then the affixes for it.
+    ///
+    /// Returns `None` if no rarity has a weight above zero.
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Option<Rolls<K>> {
+        let index = pick(rng, &self.rarities, |rarity| rarity.weight)?;
+        Some(self.roll_affixes(&self.rarities[index], rng))
+    }
+
+    /// Rolls the affixes for a rarity, like for a drop that is always rare.
+    ///
+    /// Returns `None` if the generator has no rarity with that id.
+    pub fn roll_rarity<R: Rng>(&self, rarity: &K, rng: &mut R) -> Option<Rolls<K>> {
+        let rarity = self.rarities.iter().find(|other| other.id == *rarity)?;
+        Some(self.roll_affixes(rarity, rng))
+    }
+
+    /// Rolls an item and creates a single instance of it with the rolls.
+    pub fn generate<I: Item, II: Rollable<I, K>, R: Rng>(
+        &self,
+        item: II::ItemRef,
+        rng: &mut R,
+    ) -> Option<II> {
+        Some(II::rolled(item, self.roll(rng)?))
+    }
+
+    fn roll_affixes<R: Rng>(&self, rarity: &Rarity<K>, rng: &mut R) -> Rolls<K> {
+        let mut affixes = Vec::new();
+        for (kind, count) in [
+            (AffixKind::Prefix, rarity.prefixes),
+            (AffixKind::Suffix, rarity.suffixes),
+        ] {
+            for _ in 0..count {
+                let pool: Vec<&AffixDef<K>> = self
+                    .affixes
+                    .iter()
+                    .filter(|affix| affix.kind == kind && rollable(affix, &affixes, &self.affixes))
+                    .collect();
+                let affix = match pick(rng, &pool, |affix| affix.weight) {
+                    Some(index) => pool[index],
+                    None => break,
+                };
+                let tier = rarity.tier.min(affix.tiers.len() - 1);
+                affixes.push(RolledAffix {
+                    id: affix.id.clone(),
+                    kind,
+                    tier,
+                    value: roll_value(rng, affix.tiers[tier]),
+                });
+            }
+        }
+        Rolls {
+            rarity: rarity.id.clone(),
+            affixes,
+        }
+    }
+}
+
+/// Whether an affix can still roll next to the ones that already did.
+fn rollable<K: PartialEq>(
+    affix: &AffixDef<K>,
+    rolled: &[RolledAffix<K>],
+    defs: &[AffixDef<K>],
+) -> bool {
+    if affix.tiers.is_empty() {
+        return false;
+    }
+    rolled.iter().all(|other| {
+        if other.id == affix.id {
+            return false;
+        }
+        let group = match &affix.group {
+            Some(group) => group,
+            None => return true,
+        };
+        !defs
+            .iter()
+            .any(|def| def.id == other.id && def.group.as_ref() == Some(group))
+    })
+}
+
+/// Picks an index by weight, or `None` if every weight is zero.
+fn pick<T, R: Rng>(rng: &mut R, options: &[T], weight: impl Fn(&T) -> u32) -> Option<usize> {
+    let total: u64 = options.iter().map(|option| weight(option) as u64).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.below(total);
+    for (index, option) in options.iter().enumerate() {
+        let weight = weight(option) as u64;
+        if roll < weight {
+            return Some(index);
+        }
+        roll -= weight;
+    }
+    unreachable!("the roll is below the total weight")
+}
+
+fn roll_value<R: Rng>(rng: &mut R, (min, max): (i32, i32)) -> i32 {
+    let (low, high) = (min.min(max) as i64, min.max(max) as i64);
+    (low + rng.below((high - low + 1) as u64) as i64) as i32
+}
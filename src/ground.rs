@@ -0,0 +1,320 @@
+//! Items lying on the ground in the world.
+//!
+//! `GroundItems` stores dropped item instances with a position. Stackable items dropped close
+//! to a stack of the same item are merged into it with `combine_stack`, so a pile of drops
+//! stays a single world object. Drops are picked up with `add_to_inventory`, and whatever
+//! does not fit stays on the ground.
+//!
+//! `GroundItems::drop_from` drops what is in an inventory slot, unless the item is flagged to
+//! never be dropped. `GroundItems::drop_item` is for items that do not come from an inventory,
+//! like loot, and does not check the flags.
+//!
+//! Drops despawn once they have been on the ground for a number of ticks. Call
+//! `GroundItems::tick` from the game loop to advance their age.
+use crate::inventory_management::{add_to_inventory, available_quant_in_slot, take_from_slot};
+use crate::restrictions::{check, Action, ItemFlags};
+use crate::slot_management::combine_stack;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Debug, Display};
+use core::marker::PhantomData;
+
+/// Identifies a drop for as long as it is on the ground. Ids are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DropId(pub u64);
+
+/// An item instance lying on the ground.
+#[derive(Debug, Clone)]
+pub struct GroundItem<II> {
+    pub id: DropId,
+    pub item_instance: II,
+    /// Where the drop is. 2D games can leave the last coordinate at 0.
+    pub position: [f32; 3],
+    /// How many ticks the drop has been on the ground for.
+    pub age: u32,
+}
+
+/// Why the item in a slot could not be dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropError {
+    /// There is no item in the slot, or no slot at that index.
+    EmptySlot,
+    /// The slot is locked, or all of its items are reserved.
+    Locked,
+    /// The item can not be dropped, because of these flags.
+    Restricted(ItemFlags),
+}
+
+impl Display for DropError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            DropError::EmptySlot => "There is no item in that slot.",
+            DropError::Locked => "That item is locked or reserved.",
+            DropError::Restricted(_) => "That item can not be dropped.",
+        })
+    }
+}
+
+impl Error for DropError {}
+
+/// There is no drop with that id, because it was picked up or despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownDrop;
+
+impl Display for UnknownDrop {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("There is no drop with that id.")
+    }
+}
+
+impl Error for UnknownDrop {}
+
+/// Every item on the ground, in a level or a chunk of one.
+///
+/// ```
+/// # use game_inventory::ground::GroundItems;
+/// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_INST};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use game_inventory::helpers::quant_in_inventory;
+/// let mut ground = GroundItems::new(1.0, 600);
+/// let pile = ground.drop_item(TORCH_INST.clone().unwrap(), [0.0, 0.0, 0.0]);
+/// // Close enough to end up in the same pile.
+/// assert_eq!(ground.drop_item(TORCH_INST.clone().unwrap(), [0.5, 0.0, 0.0]), pile);
+/// assert_eq!(ground.get(pile).unwrap().item_instance.quant(), 46);
+///
+/// let mut inventory = vec![DefaultSlot::new(None)];
+/// assert!(ground.pick_up(pile, &mut inventory).unwrap().is_none());
+/// assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 46);
+/// assert!(ground.is_empty());
+/// ```
+pub struct GroundItems<I: Item, II: ItemInstance<I>> {
+    drops: Vec<GroundItem<II>>,
+    next_id: u64,
+    merge_radius: f32,
+    lifetime: u32,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Item, II: ItemInstance<I> + Debug> Debug for GroundItems<I, II> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GroundItems")
+            .field("drops", &self.drops)
+            .field("merge_radius", &self.merge_radius)
+            .field("lifetime", &self.lifetime)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone> GroundItems<I, II> {
+    /// Creates an empty ground.
+    ///
+    /// Drops within `merge_radius` of each other are merged, and drops despawn after `lifetime` ticks.
+    pub fn new(merge_radius: f32, lifetime: u32) -> Self {
+        GroundItems {
+            drops: Vec::new(),
+            next_id: 0,
+            merge_radius,
+            lifetime,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Changes how many ticks drops stay on the ground for, including the ones already there.
+    pub fn set_lifetime(&mut self, lifetime: u32) {
+        self.lifetime = lifetime;
+    }
+
+    /// Drops an item instance on the ground, and returns the drop it ended up in.
+    ///
+    /// Merges as much of it as possible into stacks of the same item within the merge radius,
+    /// which then start their lifetime over. The rest becomes a new drop at `position`.
+    pub fn drop_item(&mut self, item_instance: II, position: [f32; 3]) -> DropId {
+        let mut left = Some(item_instance);
+        let mut merged_into = None;
+        for drop in self.drops.iter_mut() {
+            if left.is_none() {
+                break;
+            }
+            if distance_squared(drop.position, position) > self.merge_radius * self.merge_radius {
+                continue;
+            }
+            let (rest, stack) =
+                match combine_stack((left.clone(), Some(drop.item_instance.clone()))) {
+                    Ok(items) => items,
+                    Err(_) => continue,
+                };
+            if let Some(stack) = stack {
+                drop.item_instance = stack;
+                drop.age = 0;
+                merged_into = Some(drop.id);
+            }
+            left = rest;
+        }
+        match (left, merged_into) {
+            (Some(rest), _) => self.insert(rest, position),
+            (None, Some(id)) => id,
+            (None, None) => unreachable!("the item was either merged or is left over"),
+        }
+    }
+
+    /// Takes the item out of an inventory slot and drops it like `drop_item`.
+    ///
+    /// Only the part of a stack that is not reserved is dropped. Nothing changes if the item
+    /// is flagged so it can not be dropped, see `restrictions`.
+    ///
+    /// ```
+    /// # use game_inventory::ground::{DropError, GroundItems};
+    /// # use game_inventory::restrictions::ItemFlags;
+    /// # use game_inventory::samples::{DefaultItemInstance, DefaultSlot, QUEST_MAP};
+    /// # use game_inventory::traits::{ItemInstance, Slot};
+    /// # use std::sync::Arc;
+    /// let map = DefaultItemInstance::new(Arc::new(QUEST_MAP.clone()), 0);
+    /// let mut inventory = vec![DefaultSlot::new(Some(map)), DefaultSlot::new(None)];
+    /// let mut ground = GroundItems::new(1.0, 600);
+    /// assert!(matches!(ground.drop_from(&mut inventory, 0, [0.0; 3]), Err(DropError::Restricted(_))));
+    /// assert_eq!(ground.drop_from(&mut inventory, 1, [0.0; 3]), Err(DropError::EmptySlot));
+    /// assert!(inventory[0].item_instance().is_some());
+    /// assert!(ground.is_empty());
+    /// ```
+    pub fn drop_from<S: Slot<I, II>>(
+        &mut self,
+        inventory: &mut [S],
+        index: usize,
+        position: [f32; 3],
+    ) -> Result<DropId, DropError> {
+        let slot = match inventory.get_mut(index) {
+            Some(slot) => slot,
+            None => return Err(DropError::EmptySlot),
+        };
+        let ii = match slot.item_instance() {
+            Some(ii) => ii,
+            None => return Err(DropError::EmptySlot),
+        };
+        if let Err(restricted) = check(&ii, Action::Drop) {
+            return Err(DropError::Restricted(restricted.flags));
+        }
+        let available = available_quant_in_slot(slot);
+        if available == I::Quant::ZERO {
+            return Err(DropError::Locked);
+        }
+        let dropped = if ii.item().stackable() {
+            ii.with_quant(available)
+        } else {
+            ii.clone()
+        };
+        take_from_slot(available, slot, ii);
+        Ok(self.drop_item(dropped, position))
+    }
+
+    /// The drop with the given id, if it is still on the ground.
+    pub fn get(&self, id: DropId) -> Option<&GroundItem<II>> {
+        self.drops.iter().find(|drop| drop.id == id)
+    }
+
+    /// Removes a drop from the ground without picking it up.
+    pub fn remove(&mut self, id: DropId) -> Option<GroundItem<II>> {
+        let index = self.drops.iter().position(|drop| drop.id == id)?;
+        Some(self.drops.remove(index))
+    }
+
+    /// Every drop on the ground, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &GroundItem<II>> {
+        self.drops.iter()
+    }
+
+    /// Every drop within `radius` of a position, like the ones a player is standing on.
+    pub fn near(&self, position: [f32; 3], radius: f32) -> impl Iterator<Item = &GroundItem<II>> {
+        self.drops
+            .iter()
+            .filter(move |drop| distance_squared(drop.position, position) <= radius * radius)
+    }
+
+    /// How many drops are on the ground.
+    pub fn len(&self) -> usize {
+        self.drops.len()
+    }
+
+    /// Whether there is nothing on the ground.
+    pub fn is_empty(&self) -> bool {
+        self.drops.is_empty()
+    }
+
+    /// Picks a drop up into an inventory with `add_to_inventory`.
+    ///
+    /// Whatever does not fit stays on the ground and is returned, or `None` if everything
+    /// was picked up. Returns `UnknownDrop` if there is no drop with that id.
+    ///
+    /// ```
+    /// # use game_inventory::ground::GroundItems;
+    /// # use game_inventory::samples::{DefaultSlot, TORCH_FULL_STACK_INST, TORCH_INST};
+    /// # use game_inventory::traits::{ItemInstance, Slot};
+    /// let mut ground = GroundItems::new(1.0, 600);
+    /// let id = ground.drop_item(TORCH_FULL_STACK_INST.clone().unwrap(), [0.0; 3]);
+    /// let mut inventory = vec![DefaultSlot::new(TORCH_INST.clone())];
+    /// assert_eq!(ground.pick_up(id, &mut inventory).unwrap().unwrap().quant(), 23);
+    /// assert_eq!(ground.get(id).unwrap().item_instance.quant(), 23);
+    /// ```
+    pub fn pick_up<S: Slot<I, II>>(
+        &mut self,
+        id: DropId,
+        inventory: &mut [S],
+    ) -> Result<Option<II>, UnknownDrop> {
+        let index = match self.drops.iter().position(|drop| drop.id == id) {
+            Some(index) => index,
+            None => return Err(UnknownDrop),
+        };
+        let left = add_to_inventory(inventory, self.drops[index].item_instance.clone());
+        match &left {
+            Some(left) => self.drops[index].item_instance = left.clone(),
+            None => {
+                self.drops.remove(index);
+            }
+        }
+        Ok(left)
+    }
+
+    /// Ages every drop by a number of ticks, and despawns the ones that reached the lifetime.
+    ///
+    /// Returns the despawned drops.
+    ///
+    /// ```
+    /// # use game_inventory::ground::GroundItems;
+    /// # use game_inventory::samples::SWORD_INST;
+    /// let mut ground = GroundItems::new(1.0, 3);
+    /// ground.drop_item(SWORD_INST.clone().unwrap(), [0.0; 3]);
+    /// assert!(ground.tick(2).is_empty());
+    /// assert_eq!(ground.tick(1).len(), 1);
+    /// assert!(ground.is_empty());
+    /// ```
+    pub fn tick(&mut self, ticks: u32) -> Vec<GroundItem<II>> {
+        let mut despawned = Vec::new();
+        let mut index = 0;
+        while index < self.drops.len() {
+            let drop = &mut self.drops[index];
+            drop.age = drop.age.saturating_add(ticks);
+            if drop.age >= self.lifetime {
+                despawned.push(self.drops.remove(index));
+            } else {
+                index += 1;
+            }
+        }
+        despawned
+    }
+
+    fn insert(&mut self, item_instance: II, position: [f32; 3]) -> DropId {
+        let id = DropId(self.next_id);
+        self.next_id += 1;
+        self.drops.push(GroundItem {
+            id,
+            item_instance,
+            position,
+            age: 0,
+        });
+        id
+    }
+}
+
+fn distance_squared(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
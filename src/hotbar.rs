@@ -0,0 +1,205 @@
+//! A hotbar that binds keys to items in an inventory, without owning them.
+//!
+//! Each key is bound either to a slot index, or to an item id. Keys bound to an item id
+//! follow the item around the inventory, so when one stack of potions runs out the key
+//! moves on to the next stack. Locked slots and reserved items are skipped, like the helpers
+//! in `inventory_management` do.
+use crate::inventory_management::{available_quant_in_slot, remove_from_inventory};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use core::error::Error;
+use core::fmt::Display;
+
+/// What a hotbar key points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Binding<Id> {
+    /// Always the slot at this index, whatever it holds.
+    Slot(usize),
+    /// The first slot holding an item with this id.
+    Item(Id),
+}
+
+/// The hotbar has no key at that index, because it is not below `N`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoSuchKey;
+
+impl Display for NoSuchKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("The hotbar has no key at that index.")
+    }
+}
+
+impl Error for NoSuchKey {}
+
+/// `N` keys, each optionally bound to something in an inventory, one of which is selected.
+///
+/// ```
+/// # use game_inventory::hotbar::{Binding, Hotbar};
+/// # use game_inventory::samples::{DefaultItemInstance, DefaultSlot, TORCH, TORCH_INST, SWORD_INST};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use std::sync::Arc;
+/// let mut inventory = vec![
+///     DefaultSlot::new(SWORD_INST.clone()),
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), 1))),
+///     DefaultSlot::new(TORCH_INST.clone()),
+/// ];
+/// let mut hotbar: Hotbar<_, 4> = Hotbar::new();
+/// hotbar.bind(0, Binding::Slot(0)).unwrap();
+/// hotbar.bind(1, Binding::Item(TORCH.id())).unwrap();
+///
+/// hotbar.select(1).unwrap();
+/// assert_eq!(hotbar.resolve_selected(&inventory), Some(1));
+/// assert_eq!(hotbar.use_selected(&mut inventory).unwrap().quant(), 1);
+/// // The first stack ran out, so the key moved on to the next one.
+/// assert_eq!(hotbar.resolve_selected(&inventory), Some(2));
+/// assert_eq!(hotbar.use_selected(&mut inventory).unwrap().quant(), 1);
+/// assert_eq!(inventory[2].item_instance().unwrap().quant(), 22);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hotbar<Id, const N: usize> {
+    bindings: [Option<Binding<Id>>; N],
+    selected: usize,
+}
+
+impl<Id, const N: usize> Default for Hotbar<Id, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id, const N: usize> Hotbar<Id, N> {
+    /// Creates a hotbar with no bound keys, and the first key selected.
+    pub fn new() -> Self {
+        Hotbar {
+            bindings: core::array::from_fn(|_| None),
+            selected: 0,
+        }
+    }
+
+    /// Binds a key, returning what it was bound to before.
+    ///
+    /// Returns `NoSuchKey` if `key` is not below `N`.
+    pub fn bind(
+        &mut self,
+        key: usize,
+        binding: Binding<Id>,
+    ) -> Result<Option<Binding<Id>>, NoSuchKey> {
+        match self.bindings.get_mut(key) {
+            Some(bound) => Ok(bound.replace(binding)),
+            None => Err(NoSuchKey),
+        }
+    }
+
+    /// Unbinds a key, returning what it was bound to.
+    ///
+    /// Returns `NoSuchKey` if `key` is not below `N`.
+    pub fn unbind(&mut self, key: usize) -> Result<Option<Binding<Id>>, NoSuchKey> {
+        match self.bindings.get_mut(key) {
+            Some(bound) => Ok(bound.take()),
+            None => Err(NoSuchKey),
+        }
+    }
+
+    /// What a key is bound to, or `None` if it is unbound or not below `N`.
+    pub fn binding(&self, key: usize) -> Option<&Binding<Id>> {
+        self.bindings.get(key).and_then(|binding| binding.as_ref())
+    }
+
+    /// The selected key.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects a key.
+    ///
+    /// Returns `NoSuchKey` if `key` is not below `N`, and keeps the current selection.
+    pub fn select(&mut self, key: usize) -> Result<(), NoSuchKey> {
+        if key >= N {
+            return Err(NoSuchKey);
+        }
+        self.selected = key;
+        Ok(())
+    }
+
+    /// Selects the next key, wrapping around to the first one, like scrolling down.
+    pub fn select_next(&mut self) {
+        if N > 0 {
+            self.selected = (self.selected + 1) % N;
+        }
+    }
+
+    /// Selects the previous key, wrapping around to the last one, like scrolling up.
+    pub fn select_prev(&mut self) {
+        if N > 0 {
+            self.selected = (self.selected + N - 1) % N;
+        }
+    }
+}
+
+impl<Id: Eq, const N: usize> Hotbar<Id, N> {
+    /// Works out the index of the inventory slot a key currently points at.
+    ///
+    /// Returns `None` if the key is unbound, bound to a slot the inventory does not have,
+    /// or bound to an item the inventory does not hold. Slots that are locked, or that hold
+    /// nothing but reserved items, are skipped.
+    pub fn resolve<I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
+        &self,
+        key: usize,
+        inventory: &[S],
+    ) -> Option<usize> {
+        match self.binding(key)? {
+            Binding::Slot(index) => {
+                let slot = inventory.get(*index)?;
+                let usable = match slot.item_instance() {
+                    Some(_) => available_quant_in_slot(slot) > I::Quant::ZERO,
+                    None => !slot.locked(),
+                };
+                match usable {
+                    true => Some(*index),
+                    false => None,
+                }
+            }
+            Binding::Item(id) => inventory
+                .iter()
+                .position(|slot| match slot.item_instance() {
+                    Some(ii) => {
+                        ii.item().id() == *id && available_quant_in_slot(slot) > I::Quant::ZERO
+                    }
+                    None => false,
+                }),
+        }
+    }
+
+    /// Same as `resolve`, for the selected key.
+    pub fn resolve_selected<I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
+        &self,
+        inventory: &[S],
+    ) -> Option<usize> {
+        self.resolve(self.selected, inventory)
+    }
+
+    /// Uses up a single item from the slot a key points at, and returns it.
+    ///
+    /// This is meant for consumables, unstackable items are removed from the slot entirely.
+    /// Returns `None` if the key does not point at an item.
+    pub fn use_key<I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
+        &self,
+        key: usize,
+        inventory: &mut [S],
+    ) -> Option<II> {
+        let index = self.resolve(key, inventory)?;
+        let ii = inventory[index].item_instance()?;
+        let one = || ii.with_quant(I::Quant::ONE);
+        match remove_from_inventory(&mut inventory[index..=index], one()) {
+            Some(_) => None,
+            None => Some(one()),
+        }
+    }
+
+    /// Same as `use_key`, for the selected key.
+    pub fn use_selected<I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
+        &self,
+        inventory: &mut [S],
+    ) -> Option<II> {
+        self.use_key(self.selected, inventory)
+    }
+}
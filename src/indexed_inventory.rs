@@ -0,0 +1,400 @@
+//! An inventory that keeps an index of its contents, for big inventories that are queried often.
+//!
+//! The functions in `inventory_management` look at every slot each time they are called.
+//! That is fine for a player inventory, but adds up for storage chests with hundreds of slots
+//! that are queried every frame. `IndexedInventory` keeps track of which slots hold which item,
+//! the total quantity of every item and which slots are empty, so that queries don't have
+//! to look at the slots at all. Adding items only visits the slots they go into, in order.
+//!
+//! To keep the index correct, slots can only be modified through the inventory itself.
+use crate::inventory_management::available_quant_in_slot;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+#[derive(Debug, Default)]
+struct ItemIndex {
+    /// Every slot holding the item.
+    slots: BTreeSet<usize>,
+    /// Every unlocked slot holding a stack of the item that is not full.
+    partial: BTreeSet<usize>,
+    /// The total quantity of the item, counting unstackable items as one.
+    ///
+    /// Kept as a `u128` so that it can not overflow, even if it does not fit the item's quantity type.
+    total: u128,
+}
+
+/// A `Vec<Slot>` with an index from item id to the slots holding that item.
+///
+/// Derefs to `[S]`, so the slots can be read like any other inventory.
+///
+/// ```
+/// # use game_inventory::indexed_inventory::IndexedInventory;
+/// # use game_inventory::samples::{DefaultSlot, TORCH, SWORD, JUNK, TORCH_INST, TORCH_FULL_STACK_INST, SWORD_INST};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// let mut inventory = IndexedInventory::new(vec![
+///     DefaultSlot::new(TORCH_FULL_STACK_INST.clone()),
+///     DefaultSlot::new(SWORD_INST.clone()),
+///     DefaultSlot::new(TORCH_INST.clone()),
+///     DefaultSlot::new(None),
+/// ]);
+/// assert_eq!(inventory.quant(TORCH.id()), 123);
+/// assert!(inventory.contains_item_type(SWORD.id()));
+/// assert!(!inventory.contains_item_type(JUNK.id()));
+/// assert_eq!(inventory.empty_quant(), 1);
+///
+/// assert!(inventory.add(TORCH_INST.clone().unwrap()).is_none());
+/// assert_eq!(inventory.quant(TORCH.id()), 146);
+/// assert_eq!(inventory[2].item_instance().unwrap().quant(), 46);
+/// ```
+pub struct IndexedInventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>>
+where
+    I::Id: Hash,
+{
+    slots: Vec<S>,
+    items: HashMap<I::Id, ItemIndex>,
+    /// Every empty slot that is not locked.
+    free: BTreeSet<usize>,
+    /// How many empty slots are locked.
+    locked_free: usize,
+    phantom: PhantomData<(I, II)>,
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II> + Debug> Debug for IndexedInventory<I, II, S>
+where
+    I::Id: Hash,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexedInventory")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>> Deref for IndexedInventory<I, II, S>
+where
+    I::Id: Hash,
+{
+    type Target = [S];
+
+    fn deref(&self) -> &Self::Target {
+        &self.slots
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>> IndexedInventory<I, II, S>
+where
+    I::Id: Hash,
+{
+    /// Builds the index for the given slots.
+    pub fn new(slots: Vec<S>) -> Self {
+        let mut inventory = IndexedInventory {
+            slots,
+            items: HashMap::new(),
+            free: BTreeSet::new(),
+            locked_free: 0,
+            phantom: PhantomData,
+        };
+        for index in 0..inventory.slots.len() {
+            inventory.index_slot(index);
+        }
+        inventory
+    }
+
+    /// Gives back the slots, dropping the index.
+    pub fn into_inner(self) -> Vec<S> {
+        self.slots
+    }
+
+    /// Sets the item instance stored in a slot.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn set_item_instance(&mut self, index: usize, item_instance: &Option<II>) {
+        self.update(index, |slot| slot.set_item_instance(item_instance))
+    }
+
+    /// Calls `Slot::transfer` on a slot.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn transfer(
+        &mut self,
+        index: usize,
+        item_instance: Option<II>,
+        button: &str,
+    ) -> Option<II> {
+        self.update(index, |slot| slot.transfer(item_instance, button))
+    }
+
+    /// Modifies a slot in any way, updating the index afterwards.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn update<R, F: FnOnce(&mut S) -> R>(&mut self, index: usize, f: F) -> R {
+        self.unindex_slot(index);
+        let res = f(&mut self.slots[index]);
+        self.index_slot(index);
+        res
+    }
+
+    /// Same as `inventory_contains_item`, but only looks at the slots holding the item.
+    pub fn contains_item(&self, other: &II) -> bool {
+        match self.items.get(&other.item().id()) {
+            Some(entry) => {
+                entry
+                    .slots
+                    .iter()
+                    .any(|&index| match self.slots[index].item_instance() {
+                        Some(ii) => ii.quant() == other.quant(),
+                        None => false,
+                    })
+            }
+            None => false,
+        }
+    }
+
+    /// Same as `inventory_contains_item_type`, without looking at any slots.
+    pub fn contains_item_type(&self, id: I::Id) -> bool {
+        self.items.contains_key(&id)
+    }
+
+    /// Same as `quant_in_inventory`, without looking at any slots.
+    pub fn quant(&self, id: I::Id) -> u128 {
+        match self.items.get(&id) {
+            Some(entry) => entry.total,
+            None => 0,
+        }
+    }
+
+    /// Same as `empty_quant_in_inventory`, without looking at any slots.
+    pub fn empty_quant(&self) -> usize {
+        self.free.len() + self.locked_free
+    }
+
+    /// Checks if all of an item instance fits into the inventory, only looking at the empty slots
+    /// and the stacks of the item that are not full. Stops as soon as enough room is found.
+    ///
+    /// ```
+    /// # use game_inventory::indexed_inventory::IndexedInventory;
+    /// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH, SWORD, SWORD_INST};
+    /// # use game_inventory::traits::{ItemInstance, Slot};
+    /// # use std::sync::Arc;
+    /// let torches = |quant| Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant));
+    /// let inventory = IndexedInventory::new(vec![
+    ///     DefaultSlot::new(torches(90)),
+    ///     DefaultSlot::new(torches(95)),
+    ///     DefaultSlot::new(SWORD_INST.clone()),
+    /// ]);
+    /// assert!(inventory.can_add_item(&torches(15).unwrap()));
+    /// assert!(!inventory.can_add_item(&torches(16).unwrap()));
+    /// assert!(!inventory.can_add_item(&SWORD_INST.clone().unwrap()));
+    /// ```
+    pub fn can_add_item(&self, other: &II) -> bool {
+        let item = other.item();
+        if !item.stackable() {
+            return self.free_for_unstackable(&item).is_some();
+        }
+        let needed = other.quant().to_u128();
+        let mut space = 0;
+        if let Some(entry) = self.items.get(&item.id()) {
+            for &index in &entry.partial {
+                if space >= needed {
+                    return true;
+                }
+                if self.takes(index, other) {
+                    space += self.space_in(index, &item);
+                }
+            }
+        }
+        for &index in &self.free {
+            if space >= needed {
+                return true;
+            }
+            space += self.slots[index].stack_limit(&item).to_u128();
+        }
+        space >= needed
+    }
+
+    /// Same as `add_to_inventory`, but only visits the slots the item can go into.
+    ///
+    /// Slots are filled in order, whether they are empty or hold a stack of the item
+    /// that is not full. Returns the items that did not fit.
+    pub fn add(&mut self, other: II) -> Option<II> {
+        let item = other.item();
+        if !item.stackable() {
+            return match self.free_for_unstackable(&item) {
+                Some(index) => {
+                    self.set_item_instance(index, &Some(other));
+                    None
+                }
+                None => Some(other),
+            };
+        }
+        let id = item.id();
+        let mut remaining = other.quant();
+        let mut from = 0;
+        while remaining > I::Quant::ZERO {
+            let index = match self.next_candidate(&id, from) {
+                Some(index) => index,
+                None => break,
+            };
+            from = index + 1;
+            if !self.takes(index, &other) {
+                continue;
+            }
+            let current = match self.slots[index].item_instance() {
+                Some(ii) => ii.quant(),
+                None => I::Quant::ZERO,
+            };
+            let max = self.slots[index].stack_limit(&item);
+            let moved = remaining.min(max.saturating_sub(current));
+            if moved == I::Quant::ZERO {
+                continue;
+            }
+            remaining = remaining.saturating_sub(moved);
+            self.set_item_instance(
+                index,
+                &Some(other.with_quant(current.saturating_add(moved))),
+            );
+        }
+        if remaining == I::Quant::ZERO {
+            return None;
+        }
+        Some(other.with_quant(remaining))
+    }
+
+    /// Same as `remove_from_inventory`, but only visits the slots holding the item.
+    pub fn remove(&mut self, other: II) -> Option<II> {
+        let indices: Vec<usize> = match self.items.get(&other.item().id()) {
+            Some(entry) => entry.slots.iter().copied().collect(),
+            None => Vec::new(),
+        };
+        let mut remaining = other.quant();
+        for index in indices {
+            if remaining == I::Quant::ZERO {
+                break;
+            }
+            let taken = available_quant_in_slot(&self.slots[index]).min(remaining);
+            if taken == I::Quant::ZERO {
+                continue;
+            }
+            let ii = match self.slots[index].item_instance() {
+                Some(ii) => ii,
+                None => unreachable!("indexed slots always hold an item"),
+            };
+            remaining = remaining.saturating_sub(taken);
+            let left = ii.quant().saturating_sub(taken);
+            if !ii.item().stackable() || left == I::Quant::ZERO {
+                self.set_item_instance(index, &None);
+            } else {
+                self.set_item_instance(index, &Some(ii.with_quant(left)));
+            }
+        }
+        if remaining == I::Quant::ZERO {
+            return None;
+        }
+        Some(other.with_quant(remaining))
+    }
+
+    /// The first empty slot with room for an unstackable item.
+    fn free_for_unstackable(&self, item: &I) -> Option<usize> {
+        self.free
+            .iter()
+            .copied()
+            .find(|&index| self.slots[index].stack_limit(item) > I::Quant::ZERO)
+    }
+
+    /// The first slot from `from` on that is empty or holds a stack of the item that is not full.
+    fn next_candidate(&self, id: &I::Id, from: usize) -> Option<usize> {
+        let free = self.free.range(from..).next();
+        let partial = self
+            .items
+            .get(id)
+            .and_then(|entry| entry.partial.range(from..).next());
+        match (free, partial) {
+            (Some(&free), Some(&partial)) => Some(free.min(partial)),
+            (free, partial) => free.or(partial).copied(),
+        }
+    }
+
+    /// Whether an item instance can be put into a slot, with `Slot::locked` and `ItemInstance::stacks_with`.
+    fn takes(&self, index: usize, other: &II) -> bool {
+        if self.slots[index].locked() {
+            return false;
+        }
+        match self.slots[index].item_instance() {
+            Some(ii) => ii.stacks_with(other),
+            None => true,
+        }
+    }
+
+    /// How many more of an item fit onto the stack in a slot.
+    fn space_in(&self, index: usize, item: &I) -> u128 {
+        let current = match self.slots[index].item_instance() {
+            Some(ii) => ii.quant(),
+            None => I::Quant::ZERO,
+        };
+        self.slots[index]
+            .stack_limit(item)
+            .saturating_sub(current)
+            .to_u128()
+    }
+
+    fn index_slot(&mut self, index: usize) {
+        let ii = match self.slots[index].item_instance() {
+            Some(ii) => ii,
+            None if self.slots[index].locked() => {
+                self.locked_free += 1;
+                return;
+            }
+            None => {
+                self.free.insert(index);
+                return;
+            }
+        };
+        let item = ii.item();
+        let max = self.slots[index].stack_limit(&item);
+        let locked = self.slots[index].locked();
+        let entry = self.items.entry(item.id()).or_default();
+        entry.slots.insert(index);
+        if !item.stackable() {
+            entry.total += 1;
+            return;
+        }
+        entry.total += ii.quant().to_u128();
+        if ii.quant() < max && !locked {
+            entry.partial.insert(index);
+        }
+    }
+
+    fn unindex_slot(&mut self, index: usize) {
+        let ii = match self.slots[index].item_instance() {
+            Some(ii) => ii,
+            None => {
+                if !self.free.remove(&index) {
+                    self.locked_free -= 1;
+                }
+                return;
+            }
+        };
+        let item = ii.item();
+        let entry = match self.items.get_mut(&item.id()) {
+            Some(entry) => entry,
+            None => return,
+        };
+        entry.slots.remove(&index);
+        if !item.stackable() {
+            entry.total -= 1;
+        } else {
+            entry.total -= ii.quant().to_u128();
+            entry.partial.remove(&index);
+        }
+        if entry.slots.is_empty() {
+            self.items.remove(&item.id());
+        }
+    }
+}
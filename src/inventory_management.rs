@@ -1,8 +1,31 @@
-//! A collection of generic functions that operate on a `Vec<Slot>` (A collection of slots, AKA an inventory).
-use crate::slot_management::{combine_stack, unwrap_items_res};
-use crate::traits::{Item, ItemInstance, Slot};
+//! A collection of generic functions that operate on a `[Slot]` (A collection of slots, AKA an inventory).
+//!
+//! Every function takes a slice, so an inventory can be a `Vec`, an array, or anything else
+//! that derefs to a slice of slots, like a `SmallVec`. A `VecDeque` can be passed with
+//! `make_contiguous`. Sub-ranges work too, so `&mut inventory[..9]` only touches the hotbar.
+//!
+//! ```
+//! # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_INST, SWORD_INST};
+//! # use game_inventory::traits::{Item, Slot};
+//! # use game_inventory::helpers::{add_to_inventory, quant_in_inventory};
+//! let mut inventory = [
+//!     DefaultSlot::new(SWORD_INST.clone()),
+//!     DefaultSlot::new(None),
+//!     DefaultSlot::new(None),
+//!     DefaultSlot::new(None),
+//! ];
+//! // Only the first two slots are the hotbar.
+//! add_to_inventory(&mut inventory[..2], TORCH_INST.clone().unwrap());
+//! assert!(add_to_inventory(&mut inventory[..2], TORCH_INST.clone().unwrap()).is_none());
+//! assert!(add_to_inventory(&mut inventory[..2], SWORD_INST.clone().unwrap()).is_some());
+//! assert_eq!(quant_in_inventory(&inventory[..2], TORCH.id()), 46);
+//! ```
+#[cfg(feature = "alloc")]
+use crate::slot_management::StackLimit;
+use crate::slot_management::{combine_stack_limited, unwrap_items_res};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
 
-/// Checks if a `Vec<Slot>` contains an item with a matching name and quantity.
+/// Checks if an inventory contains an item with a matching name and quantity.
 ///
 /// ```
 /// # use game_inventory::samples::{DefaultSlot, TORCH, SWORD, JUNK, TORCH_FULL_STACK_INST, SWORD_INST};
@@ -18,7 +41,7 @@ use crate::traits::{Item, ItemInstance, Slot};
 /// assert!(!inventory_contains_item_type(&inventory, JUNK.id()));
 /// ```
 pub fn inventory_contains_item<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
-    inventory: &Vec<S>,
+    inventory: &[S],
     other: II,
 ) -> bool {
     inventory.iter().any(|s| match s.item_instance() {
@@ -27,7 +50,7 @@ pub fn inventory_contains_item<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S:
     })
 }
 
-/// Checks if a `Vec<Slot>` contains an item with a matching name.
+/// Checks if an inventory contains an item with a matching name.
 ///
 /// ```
 /// # use game_inventory::samples::{DefaultSlot, TORCH_FULL_STACK_INST, SWORD_INST, TORCH_INST};
@@ -51,7 +74,7 @@ pub fn inventory_contains_item_type<
     II: ItemInstance<I>,
     S: Slot<I, II>,
 >(
-    inventory: &Vec<S>,
+    inventory: &[S],
     id: Id,
 ) -> bool {
     inventory.iter().any(|s| match s.item_instance() {
@@ -60,6 +83,31 @@ pub fn inventory_contains_item_type<
     })
 }
 
+/// Checks if an inventory contains an item with a tag, or a tag below it. See `Item::has_tag`.
+///
+/// ```
+/// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, OAK_PLANK};
+/// # use game_inventory::traits::{Slot, ItemInstance};
+/// # use game_inventory::helpers::inventory_contains_tag;
+/// # use std::sync::Arc;
+/// let inventory = vec![
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(OAK_PLANK.clone()), 3))),
+///     DefaultSlot::new(None),
+/// ];
+/// assert!(inventory_contains_tag(&inventory, "wood/plank"));
+/// assert!(inventory_contains_tag(&inventory, "wood"));
+/// assert!(!inventory_contains_tag(&inventory, "wood/log"));
+/// ```
+pub fn inventory_contains_tag<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &[S],
+    tag: &str,
+) -> bool {
+    inventory.iter().any(|s| match s.item_instance() {
+        Some(i) => i.item().has_tag(tag),
+        None => false,
+    })
+}
+
 /// Gets the total quantity of all items with a matching name.
 ///
 /// ```
@@ -76,6 +124,9 @@ pub fn inventory_contains_item_type<
 /// ```
 /// If the item is unstackable, even if the item's amount is greater than one somehow,
 /// This method only counts it as one item.
+///
+/// The total is a `u128`, so it is reported in full even if it does not fit into the item's
+/// quantity type.
 /// ```
 /// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH_FULL_STACK_INST, SWORD_INST, SWORD};
 /// # use game_inventory::traits::{Slot, Item, ItemInstance};
@@ -89,16 +140,16 @@ pub fn inventory_contains_item_type<
 /// assert_eq!(quant_in_inventory(&inventory, SWORD.id()), 2)
 /// ```
 pub fn quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
-    inventory: &Vec<S>,
+    inventory: &[S],
     id: Id,
-) -> u16 {
+) -> u128 {
     inventory
         .iter()
         .fold(0, |quant, slot| match slot.item_instance() {
             Some(ii) => {
                 if ii.item().id() == id {
                     if ii.item().stackable() {
-                        return ii.quant() + quant;
+                        return quant + ii.quant().to_u128();
                     }
                     return quant + 1;
                 }
@@ -108,6 +159,98 @@ pub fn quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot
         })
 }
 
+/// Gets the total quantity of all items with a tag, or a tag below it, counting unstackable items as one.
+///
+/// Like `quant_in_inventory`, the total is a `u128`.
+/// ```
+/// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, OAK_PLANK, BIRCH_PLANK, OAK_LOG};
+/// # use game_inventory::traits::{Slot, ItemInstance};
+/// # use game_inventory::helpers::quant_with_tag_in_inventory;
+/// # use std::sync::Arc;
+/// let inventory = vec![
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(OAK_PLANK.clone()), 3))),
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(BIRCH_PLANK.clone()), 2))),
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(OAK_LOG.clone()), 7))),
+/// ];
+/// assert_eq!(quant_with_tag_in_inventory(&inventory, "wood/plank"), 5);
+/// assert_eq!(quant_with_tag_in_inventory(&inventory, "wood"), 12);
+/// ```
+pub fn quant_with_tag_in_inventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &[S],
+    tag: &str,
+) -> u128 {
+    inventory
+        .iter()
+        .fold(0, |quant, slot| match slot.item_instance() {
+            Some(ii) if ii.item().has_tag(tag) => {
+                if ii.item().stackable() {
+                    return quant + ii.quant().to_u128();
+                }
+                quant + 1
+            }
+            _ => quant,
+        })
+}
+
+/// Gets how much of a slot's stack can be taken out, counting unstackable items as one.
+///
+/// Nothing can be taken out of locked slots, and reserved items stay in the slot.
+/// See `Slot::locked` and `Slot::reserved`.
+pub fn available_quant_in_slot<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(slot: &S) -> I::Quant {
+    let ii = match slot.item_instance() {
+        Some(ii) if !slot.locked() => ii,
+        _ => return I::Quant::ZERO,
+    };
+    if ii.item().stackable() {
+        return ii.quant().saturating_sub(slot.reserved());
+    }
+    if slot.reserved() > I::Quant::ZERO {
+        return I::Quant::ZERO;
+    }
+    I::Quant::ONE
+}
+
+/// The quantity of an item instance, counting unstackable items as one.
+#[cfg(feature = "alloc")]
+pub(crate) fn single_quant<I: Item, II: ItemInstance<I>>(ii: &II) -> I::Quant {
+    if ii.item().stackable() {
+        return ii.quant();
+    }
+    I::Quant::ONE
+}
+
+/// Gets the total quantity of an item that can be taken out of an inventory, unlike `quant_in_inventory`
+/// which also counts the items in locked slots and the reserved items.
+///
+/// Like `quant_in_inventory`, the total is a `u128`.
+/// ```
+/// # use game_inventory::samples::{DefaultItemInstance, ReservableSlot, TORCH};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use game_inventory::helpers::{available_quant_in_inventory, quant_in_inventory};
+/// # use std::sync::Arc;
+/// let torches = |quant| Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant));
+/// let mut inventory = vec![ReservableSlot::new(torches(30)), ReservableSlot::new(torches(10))];
+/// inventory[1].locked = true;
+/// assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 40);
+/// assert_eq!(available_quant_in_inventory(&inventory, TORCH.id()), 30);
+/// ```
+pub fn available_quant_in_inventory<
+    Id: Eq,
+    I: Item<Id = Id>,
+    II: ItemInstance<I>,
+    S: Slot<I, II>,
+>(
+    inventory: &[S],
+    id: Id,
+) -> u128 {
+    inventory
+        .iter()
+        .fold(0, |quant, slot| match slot.item_instance() {
+            Some(ii) if ii.item().id() == id => quant + available_quant_in_slot(slot).to_u128(),
+            _ => quant,
+        })
+}
+
 /// Gets the quantity of empty slots in an inventory.
 ///
 /// ```
@@ -124,7 +267,7 @@ pub fn quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot
 /// assert_eq!(empty_quant_in_inventory(&inventory), 2)
 /// ```
 pub fn empty_quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
-    inventory: &Vec<S>,
+    inventory: &[S],
 ) -> usize {
     inventory
         .iter()
@@ -134,6 +277,9 @@ pub fn empty_quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S
 
 /// Attempts to add an item to the given inventory.
 ///
+/// Stacks never grow past `Slot::stack_limit`, so a large stack may be split over several slots.
+/// Locked slots are skipped.
+///
 /// ```
 /// # use game_inventory::samples::{DefaultSlot, TORCH_FULL_STACK_INST, SWORD_INST, TORCH_INST, TORCH, SWORD};
 /// # use game_inventory::traits::{Slot, ItemInstance, Item};
@@ -150,7 +296,7 @@ pub fn empty_quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S
 /// assert!(inventory[2].item_instance().unwrap().item().id() == TORCH.id());
 /// assert!(inventory[2].item_instance().unwrap().quant() == TORCH_INST.as_ref().unwrap().quant() * 2);
 /// ```
-/// Does not add the item to the given inventory if its full.
+/// Does not add the item to the given inventory if its full, or if it has no slots at all.
 /// ```
 /// # use game_inventory::samples::{DefaultSlot, TORCH_FULL_STACK_INST, SWORD_INST, TORCH_INST, JUNK_INST};
 /// # use game_inventory::traits::{Slot, ItemInstance, Item};
@@ -185,16 +331,12 @@ pub fn empty_quant_in_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S
 /// assert!(inventory[2].item_instance().unwrap().item().id() == SWORD.id());
 /// ```
 pub fn add_to_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I> + Clone, S: Slot<I, II>>(
-    inventory: &mut Vec<S>,
+    inventory: &mut [S],
     other: II,
 ) -> Option<II> {
-    if inventory.capacity() == 0 {
-        return None;
-    }
-
-    return inventory
+    inventory
         .iter_mut()
-        .fold(Some(other), add_to_inventory_try_add_to_slot);
+        .fold(Some(other), add_to_inventory_try_add_to_slot)
 }
 
 fn add_to_inventory_try_add_to_slot<
@@ -210,30 +352,51 @@ fn add_to_inventory_try_add_to_slot<
         None => return None,
         Some(c) => c,
     };
+    if slot.locked() {
+        return other;
+    }
+    let item = c.item();
 
     let s = match slot.item_instance() {
-        None => {
+        None if !item.stackable() => {
+            if slot.stack_limit(&item) == I::Quant::ZERO {
+                return other;
+            }
             slot.set_item_instance(&other);
             return None;
         }
+        None => {
+            let max = slot.stack_limit(&item);
+            if c.quant() <= max {
+                slot.set_item_instance(&other);
+                return None;
+            }
+            if max == I::Quant::ZERO {
+                return other;
+            }
+            slot.set_item_instance(&Some(c.with_quant(max)));
+            return Some(c.with_quant(c.quant().saturating_sub(max)));
+        }
         Some(s) => s,
     };
 
-    if s.item().id() != c.item().id() {
+    if !s.item().stackable() || s.item().id() != item.id() || !s.stacks_with(c) {
         return other;
     }
-    if s.quant() == s.item().max_quant() {
+    let max = slot.stack_limit(&item);
+    if s.quant() >= max {
         return other;
     }
-    let res = unwrap_items_res(combine_stack((slot.item_instance(), Some(c.clone()))));
+    let res = unwrap_items_res(combine_stack_limited((other, Some(s)), max));
     slot.set_item_instance(&res.1);
-    return res.0;
+    res.0
 }
 
 /// Attempts to remove an item from the given inventory.
 ///
 /// If you are trying to remove an item from a specific slot,
-/// index the `Vec<Slot>`. This is only for bulk removal of items.
+/// index the inventory. This is only for bulk removal of items.
+/// Locked slots and reserved items are left alone.
 ///
 /// ```
 /// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH_FULL_STACK_INST, SWORD_INST, TORCH, TORCH_INST};
@@ -298,16 +461,16 @@ fn add_to_inventory_try_add_to_slot<
 /// assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 23);
 /// ```
 pub fn remove_from_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
-    inventory: &mut Vec<S>,
+    inventory: &mut [S],
     other: II,
 ) -> Option<II> {
     let remaining = inventory.iter_mut().fold(other.quant(), |current, slot| {
         remove_from_inventory_try_remove(current, slot, &other)
     });
-    if remaining == 0 {
+    if remaining == I::Quant::ZERO {
         return None;
     }
-    return Some(II::new(other.item(), remaining));
+    Some(other.with_quant(remaining))
 }
 
 fn remove_from_inventory_try_remove<
@@ -316,37 +479,88 @@ fn remove_from_inventory_try_remove<
     II: ItemInstance<I>,
     S: Slot<I, II>,
 >(
-    current: u16,
+    current: I::Quant,
     slot: &mut S,
     other: &II,
-) -> u16 {
+) -> I::Quant {
     let s = match slot.item_instance() {
         None => return current,
         Some(s) => s,
     };
-    if current == 0 {
-        return 0;
+    if current == I::Quant::ZERO {
+        return I::Quant::ZERO;
     }
     if s.item().id() != other.item().id() {
         return current;
     }
+    take_from_slot(current, slot, s)
+}
+
+/// Takes up to `current` of the available items out of a slot, returning how many are still to be taken.
+pub(crate) fn take_from_slot<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    current: I::Quant,
+    slot: &mut S,
+    s: II,
+) -> I::Quant {
+    let available = available_quant_in_slot(slot);
+    if available == I::Quant::ZERO {
+        return current;
+    }
     if !s.item().stackable() {
         slot.set_item_instance(&None);
-        return current - 1;
+        return current.saturating_sub(I::Quant::ONE);
     }
-    if s.quant() <= current {
+    let taken = available.min(current);
+    if taken == s.quant() {
         slot.set_item_instance(&None);
-        return current - s.quant();
+    } else {
+        slot.set_item_instance(&Some(s.with_quant(s.quant().saturating_sub(taken))));
     }
-    slot.set_item_instance(&Some(II::new(s.item(), s.quant() - current)));
-    0
+    current.saturating_sub(taken)
+}
+
+/// Removes a quantity of any items with a tag, or a tag below it, from the first slots that hold them.
+///
+/// Returns how many could not be removed, so check `quant_with_tag_in_inventory` first
+/// if nothing should be removed unless there are enough.
+/// ```
+/// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, OAK_PLANK, BIRCH_PLANK};
+/// # use game_inventory::traits::{Slot, ItemInstance};
+/// # use game_inventory::helpers::{quant_with_tag_in_inventory, remove_tagged_from_inventory};
+/// # use std::sync::Arc;
+/// let mut inventory = vec![
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(OAK_PLANK.clone()), 2))),
+///     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(BIRCH_PLANK.clone()), 5))),
+/// ];
+/// // Any 3 planks.
+/// assert_eq!(remove_tagged_from_inventory(&mut inventory, "wood/plank", 3), 0);
+/// assert!(inventory[0].item_instance().is_none());
+/// assert_eq!(inventory[1].item_instance().unwrap().quant(), 4);
+/// assert_eq!(remove_tagged_from_inventory(&mut inventory, "wood/plank", 10), 6);
+/// ```
+pub fn remove_tagged_from_inventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &mut [S],
+    tag: &str,
+    quant: I::Quant,
+) -> I::Quant {
+    inventory.iter_mut().fold(quant, |current, slot| {
+        let s = match slot.item_instance() {
+            Some(s) => s,
+            None => return current,
+        };
+        if current == I::Quant::ZERO || !s.item().has_tag(tag) {
+            return current;
+        }
+        take_from_slot(current, slot, s)
+    })
 }
 
 /// Checks if an item can be added to the given inventory.
 ///
 /// This function evaluates whether an item can be added based on whether it is stackable or unstackable.
-/// For stackable items, it checks if there is enough space to add more items to existing stacks to the inventory. For unstackable
-/// items, it checks if there are empty slots in the inventory.
+/// For stackable items, it checks if there is enough space to add more items to existing stacks and empty slots,
+/// up to each slot's `Slot::stack_limit`. For unstackable items, it checks if there are empty slots in the inventory
+/// with a stack limit above zero.
 ///
 /// Adding a stackable item to an inventory with space
 /// ```
@@ -418,68 +632,99 @@ fn remove_from_inventory_try_remove<
 /// assert!(!can_add);
 /// ```
 pub fn can_add_item_to_inventory<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>, S: Slot<I, II>>(
-    inventory: &Vec<S>,
+    inventory: &[S],
     other: II,
 ) -> bool {
-    if !other.item().stackable() {
-        return can_add_unstackable_item_to_inventory(inventory);
+    let item = other.item();
+    if !item.stackable() {
+        return inventory.iter().any(|slot| takes_unstackable(slot, &item));
     }
-    return can_add_stackable_item_to_inventory(inventory, other);
+
+    let mut needed = other.quant();
+    for slot in inventory.iter().filter(|slot| !slot.locked()) {
+        let space = match slot.item_instance() {
+            None => slot.stack_limit(&item),
+            Some(s) if s.item().id() == item.id() && s.stacks_with(&other) => {
+                slot.stack_limit(&item).saturating_sub(s.quant())
+            }
+            Some(_) => continue,
+        };
+        if space >= needed {
+            return true;
+        }
+        needed = needed.saturating_sub(space);
+    }
+    false
 }
 
-fn can_add_unstackable_item_to_inventory<
-    Id: Eq,
-    I: Item<Id = Id>,
-    II: ItemInstance<I>,
-    S: Slot<I, II>,
->(
-    inventory: &Vec<S>,
+/// Whether a slot is empty, unlocked, and has room for an unstackable item.
+pub(crate) fn takes_unstackable<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    slot: &S,
+    item: &I,
 ) -> bool {
-    inventory.iter().any(|slot| slot.item_instance().is_none())
+    !slot.locked() && slot.item_instance().is_none() && slot.stack_limit(item) > I::Quant::ZERO
 }
 
-fn can_add_stackable_item_to_inventory<
-    Id: Eq,
-    I: Item<Id = Id>,
-    II: ItemInstance<I>,
-    S: Slot<I, II>,
->(
-    inventory: &Vec<S>,
-    mut other: II,
-) -> bool {
-    let max_stack = other.item().max_quant();
-    if other.quant() == max_stack {
-        return can_add_unstackable_item_to_inventory(inventory);
+/// A copy of a slot's contents, that still uses the slot's stack limit.
+#[cfg(feature = "alloc")]
+pub(crate) struct Preview<'a, S, II> {
+    original: Option<&'a S>,
+    item_instance: Option<II>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, S, II> Preview<'a, S, II> {
+    /// Copies a slot's contents.
+    pub(crate) fn of<I: Item>(slot: &'a S) -> Self
+    where
+        II: ItemInstance<I>,
+        S: Slot<I, II>,
+    {
+        Preview {
+            original: Some(slot),
+            item_instance: slot.item_instance(),
+        }
     }
+}
 
-    for slot in inventory {
-        let item = match slot.item_instance() {
-            None => {
-                return true;
-            }
-            Some(x) => x,
-        };
+#[cfg(feature = "alloc")]
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>> Slot<I, II> for Preview<'_, S, II> {
+    fn item_instance(&self) -> Option<II> {
+        self.item_instance.clone()
+    }
 
-        if item.item().id() == other.item().id() {
-            if item.quant() == max_stack {
-                continue;
-            }
-            if item.quant() + other.quant() <= max_stack {
-                return true;
-            }
+    fn set_item_instance(&mut self, item_instance: &Option<II>) {
+        self.item_instance = item_instance.clone();
+    }
 
-            let space_available = max_stack - item.quant();
-            other = II::new(other.item(), space_available);
+    fn stack_limit(&self, item: &I) -> I::Quant {
+        match self.original {
+            Some(slot) => slot.stack_limit(item),
+            None => StackLimit::Item.of(item),
         }
     }
 
-    if other.quant() == 0 {
-        return true;
+    fn locked(&self) -> bool {
+        self.original.is_some_and(|slot| slot.locked())
     }
 
-    if other.quant() > 0 {
-        return can_add_unstackable_item_to_inventory(inventory);
+    fn reserved(&self) -> I::Quant {
+        match self.original {
+            Some(slot) => slot.reserved(),
+            None => I::Quant::ZERO,
+        }
     }
 
-    false
+    fn modified(&mut self) -> bool {
+        false
+    }
+
+    fn set_modified(&mut self, _modified: bool) {}
+
+    fn new(item_instance: Option<II>) -> Self {
+        Preview {
+            original: None,
+            item_instance,
+        }
+    }
 }
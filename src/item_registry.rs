@@ -0,0 +1,480 @@
+//! A registry of item definitions that can be loaded from data files.
+//!
+//! Instead of hard coding every item as a static like the ones in `sample_items`,
+//! items can be registered at runtime and looked up by their id. Lookups hand out
+//! `Arc<I>`, which is exactly what `ItemInstance::new` takes for instances like `DefaultItemInstance`.
+//!
+//! With the `json`, `ron` or `toml` features enabled, item definitions can be loaded
+//! from files. Every file contains a single `items` list, for example in json:
+//! ```json
+//! {
+//!     "items": [
+//!         { "id": "torch", "max_quantity": 100, "light_radius": 5 },
+//!         { "id": "sword", "stackable": false }
+//!     ]
+//! }
+//! ```
+//! Loaded files are remembered, so that `ItemRegistry::reload` can pick up any changes made
+//! to them while the game is running.
+//!
+//! Ron cannot represent flattened fields (like `DataItem::extra`) with struct syntax,
+//! so in ron files each item has to be written as a map: `{ "id": "torch", "max_quantity": 100 }`.
+use crate::inventory_management::add_to_inventory;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::{error::Error, sync::Arc};
+
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+use serde::de::DeserializeOwned;
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+use std::path::Path;
+
+/// The reasons registering or loading item definitions can fail.
+///
+/// When any definition is rejected, none of the definitions passed in at the
+/// same time are registered.
+#[derive(Debug)]
+pub enum RegistryError<Id> {
+    /// Another item with the same id is already registered or is being registered.
+    DuplicateId(Id),
+    /// A stackable item allows less than 2 items per stack,
+    /// or an unstackable item allows more than 1.
+    BadStackSize(Id),
+    /// A definition file could not be read.
+    Io(PathBuf, std::io::Error),
+    /// A definition file could not be parsed.
+    Parse(String),
+    /// The format of a definition file could not be worked out from its extension.
+    UnknownFormat(PathBuf),
+}
+
+impl<Id: Debug> Display for RegistryError<Id> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::DuplicateId(id) => write!(f, "The item id {:?} is defined twice.", id),
+            RegistryError::BadStackSize(id) => write!(
+                f,
+                "The item {:?} has a max quantity that does not match whether it is stackable.",
+                id
+            ),
+            RegistryError::Io(path, err) => write!(f, "Could not read {}: {}", path.display(), err),
+            RegistryError::Parse(msg) => write!(f, "Could not parse item definitions: {}", msg),
+            RegistryError::UnknownFormat(path) => write!(
+                f,
+                "Could not work out the format of {} from its extension.",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl<Id: Debug> Error for RegistryError<Id> {}
+
+/// The file formats item definitions can be loaded from.
+///
+/// Each format is enabled by the feature of the same name.
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "ron")]
+    Ron,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+impl Format {
+    /// Works out the format of a file from its extension.
+    ///
+    /// ```
+    /// # #[cfg(feature = "json")] {
+    /// # use game_inventory::item_registry::Format;
+    /// # use std::path::Path;
+    /// assert_eq!(Format::from_path(Path::new("items/tools.json")), Some(Format::Json));
+    /// assert_eq!(Format::from_path(Path::new("items/tools.txt")), None);
+    /// # }
+    /// ```
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            #[cfg(feature = "json")]
+            "json" => Some(Format::Json),
+            #[cfg(feature = "ron")]
+            "ron" => Some(Format::Ron),
+            #[cfg(feature = "toml")]
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+#[derive(serde::Deserialize)]
+struct ItemFile<I> {
+    items: Vec<I>,
+}
+
+/// Stores item definitions and hands them out by id.
+///
+/// ```
+/// # use game_inventory::item_registry::ItemRegistry;
+/// # use game_inventory::samples::DataItem;
+/// # use game_inventory::traits::Item;
+/// let registry: ItemRegistry<DataItem> = ItemRegistry::from_items([
+///     DataItem { id: "torch".to_owned(), stackable: true, max_quantity: 100, extra: () },
+///     DataItem { id: "sword".to_owned(), stackable: false, max_quantity: 0, extra: () },
+/// ]).unwrap();
+/// assert_eq!(registry.get(&"torch".to_owned()).unwrap().max_quant(), 100);
+/// assert!(!registry.get(&"sword".to_owned()).unwrap().stackable());
+/// assert!(registry.get(&"junk".to_owned()).is_none());
+/// ```
+/// Definitions are validated before they are registered:
+/// ```
+/// # use game_inventory::item_registry::{ItemRegistry, RegistryError};
+/// # use game_inventory::samples::DataItem;
+/// let torch: DataItem = DataItem { id: "torch".to_owned(), stackable: true, max_quantity: 100, extra: () };
+/// // Ids have to be unique.
+/// assert!(matches!(
+///     ItemRegistry::from_items([torch.clone(), torch.clone()]),
+///     Err(RegistryError::DuplicateId(id)) if id == "torch"
+/// ));
+/// // Stackable items need to fit at least two items in a stack.
+/// let bad_torch = DataItem { max_quantity: 1, ..torch.clone() };
+/// assert!(matches!(
+///     ItemRegistry::from_items([bad_torch]),
+///     Err(RegistryError::BadStackSize(id)) if id == "torch"
+/// ));
+/// // Unstackable items can not fit more than one.
+/// let bad_sword: DataItem = DataItem { id: "sword".to_owned(), stackable: false, max_quantity: 5, extra: () };
+/// assert!(matches!(
+///     ItemRegistry::from_items([bad_sword]),
+///     Err(RegistryError::BadStackSize(id)) if id == "sword"
+/// ));
+/// ```
+pub struct ItemRegistry<I: Item> {
+    items: HashMap<I::Id, Arc<I>>,
+    #[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+    sources: Vec<PathBuf>,
+    /// The ids of the items that came from `sources`.
+    #[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+    loaded: HashSet<I::Id>,
+}
+
+impl<I: Item> Default for ItemRegistry<I> {
+    fn default() -> Self {
+        ItemRegistry {
+            items: HashMap::new(),
+            #[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+            sources: Vec::new(),
+            #[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+            loaded: HashSet::new(),
+        }
+    }
+}
+
+impl<I: Item + Debug> Debug for ItemRegistry<I>
+where
+    I::Id: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ItemRegistry")
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+impl<I: Item> ItemRegistry<I>
+where
+    I::Id: Hash,
+{
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing the given items.
+    pub fn from_items<T: IntoIterator<Item = I>>(items: T) -> Result<Self, RegistryError<I::Id>> {
+        let mut registry = Self::new();
+        registry.register_all(items)?;
+        Ok(registry)
+    }
+
+    /// Registers a single item, returning the shared handle to it.
+    pub fn register(&mut self, item: I) -> Result<Arc<I>, RegistryError<I::Id>> {
+        let id = item.id();
+        self.register_all([item])?;
+        Ok(self.items[&id].clone())
+    }
+
+    /// Registers all of the given items.
+    ///
+    /// If any of them are rejected, none of them are registered.
+    pub fn register_all<T: IntoIterator<Item = I>>(
+        &mut self,
+        items: T,
+    ) -> Result<(), RegistryError<I::Id>> {
+        let items: Vec<I> = items.into_iter().collect();
+        check_definitions(&self.items, &items)?;
+        self.items
+            .extend(items.into_iter().map(|item| (item.id(), Arc::new(item))));
+        Ok(())
+    }
+
+    /// Replaces every definition in the registry with the given items.
+    ///
+    /// Returns the ids that were registered before but are not anymore.
+    /// If any of the items are rejected, the registry is left untouched.
+    ///
+    /// Existing item instances keep pointing at the old definitions until they are
+    /// passed through `rebind` or `rebind_inventory`.
+    pub fn replace_all<T: IntoIterator<Item = I>>(
+        &mut self,
+        items: T,
+    ) -> Result<Vec<I::Id>, RegistryError<I::Id>> {
+        let items: Vec<I> = items.into_iter().collect();
+        check_definitions(&HashMap::new(), &items)?;
+        let items: HashMap<I::Id, Arc<I>> = items
+            .into_iter()
+            .map(|item| (item.id(), Arc::new(item)))
+            .collect();
+        let removed = self
+            .items
+            .drain()
+            .map(|(id, _)| id)
+            .filter(|id| !items.contains_key(id))
+            .collect();
+        self.items = items;
+        Ok(removed)
+    }
+
+    /// Gets the current definition of an item.
+    pub fn get(&self, id: &I::Id) -> Option<Arc<I>> {
+        self.items.get(id).cloned()
+    }
+
+    /// Whether an item with the given id is registered.
+    pub fn contains(&self, id: &I::Id) -> bool {
+        self.items.contains_key(id)
+    }
+
+    /// The amount of registered items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether no items are registered.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterates over every registered item, in no particular order.
+    pub fn items(&self) -> impl Iterator<Item = &Arc<I>> {
+        self.items.values()
+    }
+
+    /// Points an item instance at the current definition of its item.
+    ///
+    /// If the item is no longer registered, the instance is returned untouched.
+    /// The instance is rebuilt with `ItemInstance::with_item`, so it keeps its quantity even if
+    /// that is now above the item's `max_quant`. `rebind_inventory` splits such stacks up.
+    ///
+    /// ```
+    /// # use game_inventory::item_registry::ItemRegistry;
+    /// # use game_inventory::samples::{DataItem, DefaultItemInstance};
+    /// # use game_inventory::traits::{Item, ItemInstance};
+    /// let torch: DataItem = DataItem { id: "torch".to_owned(), stackable: true, max_quantity: 100, extra: () };
+    /// let mut registry = ItemRegistry::from_items([torch.clone()]).unwrap();
+    /// let instance = DefaultItemInstance::new(registry.get(&torch.id).unwrap(), 50);
+    ///
+    /// registry.replace_all([DataItem { max_quantity: 64, ..torch }]).unwrap();
+    /// assert_eq!(instance.item().max_quant(), 100);
+    /// let instance = registry.rebind(instance);
+    /// assert_eq!(instance.item().max_quant(), 64);
+    /// assert_eq!(instance.quant(), 50);
+    /// ```
+    pub fn rebind<II: ItemInstance<I, ItemRef = Arc<I>>>(&self, instance: II) -> II {
+        match self.get(&instance.item().id()) {
+            Some(item) if !Arc::ptr_eq(&item, &instance.item()) => instance.with_item(item),
+            _ => instance,
+        }
+    }
+
+    /// Points every item instance in an inventory at the current definition of its item.
+    ///
+    /// Only slots whose definition actually changed are modified. Stacks that no longer fit
+    /// into their slot are cut down to `Slot::stack_limit`, and the rest is added to the inventory
+    /// like `add_to_inventory`. Returns what did not fit anywhere.
+    ///
+    /// ```
+    /// # use game_inventory::item_registry::ItemRegistry;
+    /// # use game_inventory::samples::{DataItem, DefaultItemInstance, DefaultSlot};
+    /// # use game_inventory::traits::{Item, ItemInstance, Slot};
+    /// let torch: DataItem = DataItem { id: "torch".to_owned(), stackable: true, max_quantity: 100, extra: () };
+    /// let mut registry = ItemRegistry::from_items([torch.clone()]).unwrap();
+    /// let mut inventory = vec![
+    ///     DefaultSlot::new(Some(DefaultItemInstance::new(registry.get(&torch.id).unwrap(), 50))),
+    ///     DefaultSlot::new(None),
+    /// ];
+    ///
+    /// registry.replace_all([DataItem { max_quantity: 40, ..torch }]).unwrap();
+    /// assert!(registry.rebind_inventory(&mut inventory).is_empty());
+    /// assert_eq!(inventory[0].item_instance().unwrap().item().max_quant(), 40);
+    /// assert_eq!(inventory[0].item_instance().unwrap().quant(), 40);
+    /// assert_eq!(inventory[1].item_instance().unwrap().quant(), 10);
+    /// ```
+    pub fn rebind_inventory<II: ItemInstance<I, ItemRef = Arc<I>> + Clone, S: Slot<I, II>>(
+        &self,
+        inventory: &mut [S],
+    ) -> Vec<II> {
+        let mut over = Vec::new();
+        for slot in inventory.iter_mut() {
+            let instance = match slot.item_instance() {
+                Some(instance) => instance,
+                None => continue,
+            };
+            let item = match self.get(&instance.item().id()) {
+                Some(item) if !Arc::ptr_eq(&item, &instance.item()) => item,
+                _ => continue,
+            };
+            let mut instance = instance.with_item(item);
+            let limit = slot.stack_limit(&instance.item());
+            if instance.item().stackable() && instance.quant() > limit {
+                over.push(instance.with_quant(instance.quant().saturating_sub(limit)));
+                instance = instance.with_quant(limit);
+            }
+            slot.set_item_instance(&Some(instance));
+        }
+        over.into_iter()
+            .filter_map(|ii| add_to_inventory(inventory, ii))
+            .collect()
+    }
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+impl<I: Item + DeserializeOwned> ItemRegistry<I>
+where
+    I::Id: Hash,
+{
+    /// Registers every item defined in a string of the given format.
+    ///
+    /// ```
+    /// # #[cfg(feature = "json")] {
+    /// # use game_inventory::item_registry::{Format, ItemRegistry};
+    /// # use game_inventory::samples::DataItem;
+    /// # use game_inventory::traits::Item;
+    /// # use std::collections::HashMap;
+    /// let mut registry: ItemRegistry<DataItem<HashMap<String, serde_json::Value>>> = ItemRegistry::new();
+    /// registry.load_str(r#"{
+    ///     "items": [
+    ///         { "id": "torch", "max_quantity": 100, "light_radius": 5 },
+    ///         { "id": "sword", "stackable": false }
+    ///     ]
+    /// }"#, Format::Json).unwrap();
+    /// let torch = registry.get(&"torch".to_owned()).unwrap();
+    /// assert_eq!(torch.max_quant(), 100);
+    /// assert_eq!(torch.extra["light_radius"], 5);
+    /// assert!(!registry.get(&"sword".to_owned()).unwrap().stackable());
+    /// # }
+    /// ```
+    pub fn load_str(&mut self, src: &str, format: Format) -> Result<(), RegistryError<I::Id>> {
+        self.register_all(parse_definitions(src, format)?)
+    }
+
+    /// Registers every item defined in a file, and remembers the file for `reload`.
+    ///
+    /// The format is worked out from the file extension.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), RegistryError<I::Id>> {
+        let path = path.as_ref().to_path_buf();
+        let items: Vec<I> = read_definitions(&path)?;
+        let ids: Vec<I::Id> = items.iter().map(Item::id).collect();
+        self.register_all(items)?;
+        self.loaded.extend(ids);
+        self.sources.push(path);
+        Ok(())
+    }
+
+    /// Re-reads every file loaded with `load_file` and replaces the definitions that came
+    /// from them with their contents.
+    ///
+    /// Items that were registered any other way are kept. Returns the ids that are no longer
+    /// defined. If any file fails to load, or defines an item that was registered another way,
+    /// the registry is left untouched. See `replace_all` for how existing item instances are affected.
+    pub fn reload(&mut self) -> Result<Vec<I::Id>, RegistryError<I::Id>> {
+        let mut items = Vec::new();
+        for path in &self.sources {
+            items.extend(read_definitions(path)?);
+        }
+        let mut kept: HashMap<I::Id, Arc<I>> = self
+            .items
+            .values()
+            .filter(|item| !self.loaded.contains(&item.id()))
+            .map(|item| (item.id(), item.clone()))
+            .collect();
+        check_definitions(&kept, &items)?;
+        let loaded: HashSet<I::Id> = items.iter().map(Item::id).collect();
+        let removed = self
+            .loaded
+            .drain()
+            .filter(|id| !loaded.contains(id))
+            .collect();
+        kept.extend(items.into_iter().map(|item| (item.id(), Arc::new(item))));
+        self.items = kept;
+        self.loaded = loaded;
+        Ok(removed)
+    }
+}
+
+fn check_definitions<I: Item>(
+    existing: &HashMap<I::Id, Arc<I>>,
+    items: &[I],
+) -> Result<(), RegistryError<I::Id>>
+where
+    I::Id: Hash,
+{
+    let mut seen = HashSet::new();
+    for item in items {
+        let bad_stack_size = if item.stackable() {
+            item.max_quant() <= I::Quant::ONE
+        } else {
+            item.max_quant() > I::Quant::ONE
+        };
+        if bad_stack_size {
+            return Err(RegistryError::BadStackSize(item.id()));
+        }
+        if existing.contains_key(&item.id()) || !seen.insert(item.id()) {
+            return Err(RegistryError::DuplicateId(item.id()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+fn read_definitions<I: Item + DeserializeOwned>(
+    path: &Path,
+) -> Result<Vec<I>, RegistryError<I::Id>> {
+    let format =
+        Format::from_path(path).ok_or_else(|| RegistryError::UnknownFormat(path.to_path_buf()))?;
+    let src =
+        std::fs::read_to_string(path).map_err(|err| RegistryError::Io(path.to_path_buf(), err))?;
+    parse_definitions(&src, format)
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+fn parse_definitions<I: Item + DeserializeOwned>(
+    src: &str,
+    format: Format,
+) -> Result<Vec<I>, RegistryError<I::Id>> {
+    let file: Result<ItemFile<I>, String> = match format {
+        #[cfg(feature = "json")]
+        Format::Json => serde_json::from_str(src).map_err(|err| err.to_string()),
+        #[cfg(feature = "ron")]
+        Format::Ron => ron::from_str(src).map_err(|err| err.to_string()),
+        #[cfg(feature = "toml")]
+        Format::Toml => toml::from_str(src).map_err(|err| err.to_string()),
+    };
+    file.map(|file| file.items).map_err(RegistryError::Parse)
+}
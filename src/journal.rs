@@ -0,0 +1,314 @@
+//! An optional journaling layer that records every change made to an inventory.
+//!
+//! Wrap any inventory operation in `Journal::record` and every slot it modifies is
+//! written to an append-only list of `JournalEntry`s, tagged with what operation
+//! made the change and who (or what system) asked for it.
+//!
+//! The journal can then be used to work out where an item went (`Journal::for_item`),
+//! or to rebuild the state of the inventory at any point from an initial snapshot
+//! (`Journal::replay_to`).
+//!
+//! With the `json` feature enabled, journals can be written to and read back from disk
+//! as json lines, one entry per line.
+//!
+//! Changes are detected through `Slot::modified`, so your slots need to set it
+//! whenever their item instance changes, like `DefaultSlot` does.
+use crate::traits::{Item, ItemInstance, Slot};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "json")]
+use std::io::{BufRead, Write};
+
+/// A single recorded change to a single slot.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JournalEntry<II> {
+    /// The position of this entry in the journal.
+    pub seq: u64,
+    /// When the change was made, in milliseconds since the unix epoch.
+    pub timestamp: u64,
+    /// Who or what made the change, like a player name or the name of a game system.
+    pub actor: String,
+    /// The operation that made the change, like `"add_to_inventory"`.
+    pub operation: String,
+    /// The index of the slot that changed.
+    pub slot: usize,
+    /// The contents of the slot before the change.
+    pub before: Option<II>,
+    /// The contents of the slot after the change.
+    pub after: Option<II>,
+}
+
+/// An append-only log of changes made to a single inventory.
+///
+/// ```
+/// # use game_inventory::journal::Journal;
+/// # use game_inventory::snapshot::Snapshot;
+/// # use game_inventory::samples::{DefaultSlot, TORCH_INST, TORCH_FULL_STACK_INST, SWORD_INST};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use game_inventory::helpers::add_to_inventory;
+/// let mut inventory = vec![
+///     DefaultSlot::new(TORCH_FULL_STACK_INST.clone()),
+///     DefaultSlot::new(TORCH_INST.clone()),
+///     DefaultSlot::new(None),
+/// ];
+/// let initial = Snapshot::take(&inventory);
+/// let mut journal = Journal::new();
+///
+/// journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+///     add_to_inventory(inventory, SWORD_INST.clone().unwrap())
+/// });
+/// assert_eq!(journal.len(), 1);
+/// let entry = &journal.entries()[0];
+/// assert_eq!(entry.actor, "player");
+/// assert_eq!(entry.operation, "add_to_inventory");
+/// assert_eq!(entry.slot, 2);
+/// assert!(entry.before.is_none());
+/// assert_eq!(entry.after.as_ref().unwrap().item().id(), "sword");
+///
+/// // The journal can rebuild the inventory from the initial snapshot.
+/// let replayed = journal.replay(&initial);
+/// assert_eq!(replayed[2].as_ref().unwrap().item().id(), "sword");
+/// ```
+pub struct Journal<I: Item, II: ItemInstance<I>> {
+    entries: Vec<JournalEntry<II>>,
+    clock: fn() -> u64,
+    #[cfg(feature = "json")]
+    written: usize,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Item, II: ItemInstance<I> + Debug> Debug for Journal<I, II> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Journal")
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone> Default for Journal<I, II> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn system_clock() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone> Journal<I, II> {
+    /// Creates an empty journal that timestamps entries with the system clock.
+    pub fn new() -> Self {
+        Self::with_clock(system_clock)
+    }
+
+    /// Creates an empty journal that timestamps entries with the given clock.
+    ///
+    /// Useful for tests, or for recording game time instead of real time.
+    pub fn with_clock(clock: fn() -> u64) -> Self {
+        Journal {
+            entries: Vec::new(),
+            clock,
+            #[cfg(feature = "json")]
+            written: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Every recorded entry, oldest first.
+    pub fn entries(&self) -> &[JournalEntry<II>] {
+        &self.entries
+    }
+
+    /// The amount of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Runs an operation on an inventory and records every slot it modified.
+    ///
+    /// The modified flags of the slots are left as they would have been without the journal.
+    pub fn record<S, T, R, F>(&mut self, inventory: &mut T, actor: &str, operation: &str, f: F) -> R
+    where
+        S: Slot<I, II>,
+        T: AsMut<[S]>,
+        F: FnOnce(&mut T) -> R,
+    {
+        let before: Vec<(Option<II>, bool)> = inventory
+            .as_mut()
+            .iter_mut()
+            .map(|slot| {
+                let modified = slot.modified();
+                slot.set_modified(false);
+                (slot.item_instance(), modified)
+            })
+            .collect();
+        let res = f(inventory);
+        for (index, (slot, (before, modified))) in
+            inventory.as_mut().iter_mut().zip(before).enumerate()
+        {
+            if !slot.modified() {
+                slot.set_modified(modified);
+                continue;
+            }
+            self.record_change(actor, operation, index, before, slot.item_instance());
+        }
+        res
+    }
+
+    /// Records a change to a single slot that was made outside of `record`.
+    pub fn record_change(
+        &mut self,
+        actor: &str,
+        operation: &str,
+        slot: usize,
+        before: Option<II>,
+        after: Option<II>,
+    ) {
+        self.entries.push(JournalEntry {
+            seq: self.entries.len() as u64,
+            timestamp: (self.clock)(),
+            actor: actor.to_owned(),
+            operation: operation.to_owned(),
+            slot,
+            before,
+            after,
+        });
+    }
+
+    /// Every entry where an item with the given id was in the slot before or after the change.
+    ///
+    /// ```
+    /// # use game_inventory::journal::Journal;
+    /// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH, SWORD, JUNK, TORCH_INST, SWORD_INST};
+    /// # use game_inventory::traits::{Item, ItemInstance, Slot};
+    /// # use game_inventory::helpers::{add_to_inventory, remove_from_inventory};
+    /// # use std::sync::Arc;
+    /// let mut inventory = vec![DefaultSlot::new(SWORD_INST.clone()), DefaultSlot::new(None)];
+    /// let mut journal = Journal::new();
+    /// journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+    ///     add_to_inventory(inventory, TORCH_INST.clone().unwrap())
+    /// });
+    /// journal.record(&mut inventory, "blacksmith", "remove_from_inventory", |inventory| {
+    ///     remove_from_inventory(inventory, DefaultItemInstance::new(Arc::new(SWORD.clone()), 1))
+    /// });
+    /// let sword_history: Vec<_> = journal.for_item(SWORD.id()).collect();
+    /// assert_eq!(sword_history.len(), 1);
+    /// assert_eq!(sword_history[0].actor, "blacksmith");
+    /// assert!(sword_history[0].after.is_none());
+    /// assert_eq!(journal.for_item(TORCH.id()).count(), 1);
+    /// assert_eq!(journal.for_item(JUNK.id()).count(), 0);
+    /// ```
+    pub fn for_item(&self, id: I::Id) -> impl Iterator<Item = &JournalEntry<II>> {
+        self.entries.iter().filter(move |entry| {
+            [&entry.before, &entry.after]
+                .iter()
+                .any(|instance| match instance {
+                    Some(instance) => instance.item().id() == id,
+                    None => false,
+                })
+        })
+    }
+
+    /// Rebuilds the state of an inventory by applying every entry to an initial snapshot.
+    pub fn replay(&self, initial: &[Option<II>]) -> Vec<Option<II>> {
+        self.replay_to(initial, self.entries.len())
+    }
+
+    /// Rebuilds the state of an inventory by applying the first `len` entries to an initial snapshot.
+    ///
+    /// To see the inventory right after a specific entry, use `entry.seq + 1`.
+    ///
+    /// ```
+    /// # use game_inventory::journal::Journal;
+    /// # use game_inventory::snapshot::Snapshot;
+    /// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_INST};
+    /// # use game_inventory::traits::{Item, ItemInstance, Slot};
+    /// # use game_inventory::helpers::add_to_inventory;
+    /// let mut inventory = vec![DefaultSlot::new(None)];
+    /// let initial = Snapshot::take(&inventory);
+    /// let mut journal = Journal::new();
+    /// for _ in 0..3 {
+    ///     journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+    ///         add_to_inventory(inventory, TORCH_INST.clone().unwrap())
+    ///     });
+    /// }
+    /// assert!(journal.replay_to(&initial, 0)[0].is_none());
+    /// assert_eq!(journal.replay_to(&initial, 2)[0].as_ref().unwrap().quant(), 46);
+    /// assert_eq!(journal.replay(&initial)[0].as_ref().unwrap().quant(), 69);
+    /// ```
+    pub fn replay_to(&self, initial: &[Option<II>], len: usize) -> Vec<Option<II>> {
+        let mut state = initial.to_vec();
+        for entry in self.entries.iter().take(len) {
+            if entry.slot >= state.len() {
+                state.resize(entry.slot + 1, None);
+            }
+            state[entry.slot] = entry.after.clone();
+        }
+        state
+    }
+}
+
+#[cfg(feature = "json")]
+impl<I: Item, II: ItemInstance<I> + Clone> Journal<I, II> {
+    /// Writes every entry that has not been written yet as json lines.
+    ///
+    /// Calling this after every batch of changes with a file opened in append mode
+    /// keeps an append-only log on disk. If writing fails, the lines written before the error
+    /// count as written, so calling it again carries on from the line that failed.
+    ///
+    /// A line can fail halfway through, leaving part of it in the writer, and calling this
+    /// again writes the whole line after that part. For files, remember their length before
+    /// calling this, and truncate them back to it before trying again.
+    pub fn write_new<W: Write>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        II: serde::Serialize,
+    {
+        while let Some(entry) = self.entries.get(self.written) {
+            let mut line = serde_json::to_vec(entry)?;
+            line.push(b'\n');
+            writer.write_all(&line)?;
+            self.written += 1;
+        }
+        writer.flush()
+    }
+
+    /// Reads a journal written by `write_new`, that timestamps new entries with the system clock.
+    ///
+    /// The read entries count as written already. The items of the read instances are
+    /// not shared with anything else, so you may want to pass them through
+    /// `ItemRegistry::rebind`.
+    pub fn read<R: BufRead>(reader: R) -> std::io::Result<Self>
+    where
+        II: serde::de::DeserializeOwned,
+    {
+        Self::read_with_clock(reader, system_clock)
+    }
+
+    /// Same as `read`, for journals that timestamp new entries with the given clock.
+    pub fn read_with_clock<R: BufRead>(reader: R, clock: fn() -> u64) -> std::io::Result<Self>
+    where
+        II: serde::de::DeserializeOwned,
+    {
+        let mut journal = Self::with_clock(clock);
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            journal.entries.push(serde_json::from_str(&line)?);
+        }
+        journal.written = journal.entries.len();
+        Ok(journal)
+    }
+}
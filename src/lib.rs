@@ -16,10 +16,42 @@
 //!
 //! ## Overall architecture
 //!
-//! - `trait Item` DefaultItem data that never changes, like how the item looks, its base damage, its description e.t.c.
-//! - `trait ItemInstance` DefaultItem data that changes between instances, like enchantments, how many you have, their durability, e.t.c.
-//! - `trait Slot` Manages a single item instance. Good for binding user action to different types of instance modification (stack splitting, stack combining, e.t.c.). Allows for binding to the UI via a callback function.
-//! - `Vec<Slot>` Is the way an inventory is composed. There are builtin functions in `inventory_management` that can help manage the inventory.
+//! - `trait Item` DefaultItem data that never changes, like how the item looks, its base damage, its description e.t.c. Can have tags like `food/meat`, so helpers can work on a whole category of items, and `ItemFlags` that keep quest items and bound gear from being dropped, traded, sold or mailed.
+//! - `trait ItemInstance` DefaultItem data that changes between instances, like enchantments, how many you have, their durability, e.t.c. Instances with data of their own can refuse to stack with each other.
+//! - `trait Slot` Manages a single item instance. Good for binding user action to different types of instance modification (stack splitting, stack combining, e.t.c.). Allows for binding to the UI via a callback function. A slot can be locked, so nothing is added to or taken from it.
+//! - `[Slot]` Is the way an inventory is composed, usually stored in a `Vec` or an array. There are builtin functions in `inventory_management` that can help manage the inventory.
+//! - `OverflowPolicy` Decides what happens to the items that do not fit when adding to an inventory, like rejecting all of them or spilling them into another inventory.
+//! - `Equipment` One slot per place on a character, like head or main hand, that items are equipped into from a backpack. Adds up the stat modifiers and set bonuses of what is equipped.
+//! - `FixedInventory` A `[Slot; N]` that always has the same amount of slots, and never allocates.
+//! - `Socketed` Item instances with sockets that gems or runes taken from an inventory are inserted into, adding their modifiers to the item's.
+//! - `ItemGenerator` Rolls items with a weighted rarity and random prefixes and suffixes from an affix pool, from a seed so rolls can be replayed.
+//! - `GroundItems` Items dropped in the world, with a position. Merges nearby stacks, can be picked up into an inventory, and despawns drops after a while.
+//! - `Hotbar` Binds keys to slots or items in an inventory, and uses up the item a key points at.
+//! - `Machine` Input, fuel and output slots that turn items into other items over time, using the recipes in a `RecipeBook`.
+//! - `Market` An order book of buy and sell orders that hold the items and currency on offer, and trade them between players when prices match.
+//! - `PostOffice` Parcels of items taken out of one player's inventory and held until another player claims them, or sent back when they expire.
+//! - `IndexedInventory` A `Vec<Slot>` that keeps an index of its contents, so big inventories can be queried without looking at every slot.
+//! - `Reservation` Items set aside in an inventory for a queued job, so nothing else can take them. Released when dropped.
+//! - `SharedInventory` A `Vec<Slot>` behind a lock, so the same inventory can be used from many threads.
+//! - `TradeSession` Offers from two inventories that are locked, confirmed and then swapped all at once, for trading between players.
+//! - `Transport` Links between inventories that move items matching a filter every tick, like hoppers or conveyor belts.
+//! - `SalvageTables` What items break down into when they are salvaged, with fixed and random components that can scale with how worn the item is.
+//! - `Snapshot` Copies the contents of an inventory, and can be diffed against a later snapshot to see what changed.
+//! - `Journal` Records every change made to an inventory, so that it can be audited and replayed.
+//! - `ItemRegistry` Stores item definitions by id, and can load them from json, ron or toml files instead of hard coding them.
+//!
+//! ## Derive macros
+//!
+//! With the `derive` feature enabled, `Item`, `ItemInstance` and `Slot` can be derived
+//! for your own structs instead of implementing them by hand. See `game_inventory_derive`
+//! for the attributes they take.
+//!
+//! ## `no_std`
+//!
+//! The crate can be used without the standard library by turning off the default `std` feature.
+//! The traits, `slot_management`, `inventory_management`, `overflow`, `restrictions`, `FixedInventory` and the sample structs
+//! that only borrow their data, like `RefItemInstance`, are still available, and none of them allocate.
+//! Enable the `alloc` feature to get `Arc` backed item instances, `Equipment`, `GroundItems`, `ItemGenerator`, `Machine`, `Market`, `PostOffice`, `SalvageTables`, `Snapshot`, sockets, `TradeSession` and `Transport` back.
 //!
 //! ## Basic example
 //!
@@ -40,6 +72,7 @@
 //!
 //! impl<'a> Item for DefaultItem<'a> {
 //!     type Id = &'a str;
+//!     type Quant = u16;
 //!
 //!     fn stackable(&self) -> bool {
 //!         self.max_quantity > 1
@@ -73,16 +106,59 @@
 //! assert_eq!(inventory[3].item_instance.as_ref().unwrap().quant(), CHEESE_INST.as_ref().unwrap().quant());
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod equipment;
+pub mod fixed_inventory;
+#[cfg(feature = "alloc")]
+pub mod generation;
+#[cfg(feature = "alloc")]
+pub mod ground;
+pub mod hotbar;
+#[cfg(feature = "std")]
+pub mod indexed_inventory;
 pub mod inventory_management;
+#[cfg(feature = "std")]
+pub mod item_registry;
+#[cfg(feature = "std")]
+pub mod journal;
+#[cfg(feature = "alloc")]
+pub mod machine;
+#[cfg(feature = "alloc")]
+pub mod mail;
+#[cfg(feature = "alloc")]
+pub mod market;
+pub mod overflow;
+#[cfg(feature = "std")]
+pub mod reservation;
+pub mod restrictions;
+#[cfg(feature = "alloc")]
+pub mod salvage;
+#[cfg(feature = "std")]
 pub mod sample_items;
 pub mod sample_structs;
+#[cfg(feature = "std")]
+pub mod shared_inventory;
 pub mod slot_management;
+#[cfg(feature = "alloc")]
+pub mod snapshot;
+#[cfg(feature = "alloc")]
+pub mod sockets;
+#[cfg(feature = "alloc")]
+pub mod trade;
 pub mod traits;
+#[cfg(feature = "alloc")]
+pub mod transport;
 
 /// A combination of the `sample_items` and `sample_structs` crates.
 ///
 /// These are used for tests and examples, and if you want you can use them.
 pub mod samples {
+    #[cfg(feature = "std")]
     pub use crate::sample_items::*;
     pub use crate::sample_structs::*;
 }
@@ -0,0 +1,314 @@
+//! Machines that turn items into other items over time, like furnaces, ovens and workbenches.
+//!
+//! A `Machine` has an input, a fuel and an output slot. Every tick it looks up the recipe for
+//! the input in a `RecipeBook`, burns fuel, and once the recipe has had enough ticks it takes
+//! the input and stacks the result onto the output slot with `combine_stack_limited`, up to the
+//! output slot's `Slot::stack_limit`. While the output slot has no room for the result, the
+//! machine pauses and keeps its progress.
+//!
+//! Like the helpers in `inventory_management`, machines leave locked slots and reserved items alone.
+//!
+//! The progress is kept in a `MachineProgress`, which can be saved with the `serde` feature
+//! and put back with `Machine::restore`.
+use crate::inventory_management::{available_quant_in_slot, take_from_slot};
+use crate::slot_management::combine_stack_limited;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+/// Turns a quantity of one item into an item instance.
+#[derive(Debug, Clone)]
+pub struct Recipe<I: Item, II: ItemInstance<I>> {
+    pub input: I::Id,
+    /// How many of the input one run takes. Unstackable items count as one.
+    pub input_quant: I::Quant,
+    /// What one run of the recipe makes.
+    pub output: II,
+    /// How many ticks of burning fuel one run takes.
+    pub ticks: u32,
+}
+
+/// The recipes and fuels a kind of machine accepts. Usually shared by every machine of that kind.
+///
+/// ```
+/// # use game_inventory::machine::{Recipe, RecipeBook};
+/// # use game_inventory::samples::{DefaultItemInstance, TORCH, JUNK};
+/// # use game_inventory::traits::{Item, ItemInstance};
+/// # use std::sync::Arc;
+/// let mut furnace = RecipeBook::new();
+/// furnace.add_recipe(Recipe {
+///     input: JUNK.id(),
+///     input_quant: 2,
+///     output: DefaultItemInstance::new(Arc::new(TORCH.clone()), 1),
+///     ticks: 20,
+/// });
+/// furnace.add_fuel(TORCH.id(), 80);
+/// assert_eq!(furnace.recipe_for(&JUNK.id()).unwrap().ticks, 20);
+/// assert!(furnace.recipe_for(&TORCH.id()).is_none());
+/// assert_eq!(furnace.burn_time(&TORCH.id()), Some(80));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecipeBook<I: Item, II: ItemInstance<I>> {
+    recipes: Vec<Recipe<I, II>>,
+    fuels: Vec<(I::Id, u32)>,
+}
+
+impl<I: Item, II: ItemInstance<I>> Default for RecipeBook<I, II> {
+    fn default() -> Self {
+        RecipeBook {
+            recipes: Vec::new(),
+            fuels: Vec::new(),
+        }
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> RecipeBook<I, II> {
+    /// Creates a book with no recipes and no fuels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a recipe. If two recipes take the same input, the first one is used.
+    pub fn add_recipe(&mut self, recipe: Recipe<I, II>) {
+        self.recipes.push(recipe);
+    }
+
+    /// Makes an item burnable, for the given amount of ticks per item.
+    pub fn add_fuel(&mut self, id: I::Id, ticks: u32) {
+        self.fuels.push((id, ticks));
+    }
+
+    /// The recipe that takes an item as its input.
+    pub fn recipe_for(&self, id: &I::Id) -> Option<&Recipe<I, II>> {
+        self.recipes.iter().find(|recipe| recipe.input == *id)
+    }
+
+    /// How many ticks one of an item burns for, or `None` if it is not a fuel.
+    pub fn burn_time(&self, id: &I::Id) -> Option<u32> {
+        self.fuels
+            .iter()
+            .find(|fuel| fuel.0 == *id)
+            .map(|fuel| fuel.1)
+    }
+}
+
+/// How far along a machine is, without its slots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineProgress<Id> {
+    /// The input item of the recipe being worked on.
+    pub recipe: Option<Id>,
+    /// How many ticks the recipe has been worked on for.
+    pub elapsed: u32,
+    /// How many more ticks the current fuel burns for.
+    pub burn_left: u32,
+    /// How many ticks the current fuel burned for in total, for drawing the flame.
+    pub burn_time: u32,
+}
+
+impl<Id> Default for MachineProgress<Id> {
+    fn default() -> Self {
+        MachineProgress {
+            recipe: None,
+            elapsed: 0,
+            burn_left: 0,
+            burn_time: 0,
+        }
+    }
+}
+
+/// An input, a fuel and an output slot, and the progress of turning one into the other.
+///
+/// ```
+/// # use game_inventory::machine::{Machine, Recipe, RecipeBook};
+/// # use game_inventory::samples::{DefaultItemInstance, DefaultSlot, TORCH, JUNK};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use std::sync::Arc;
+/// let mut book = RecipeBook::new();
+/// book.add_recipe(Recipe {
+///     input: JUNK.id(),
+///     input_quant: 1,
+///     output: DefaultItemInstance::new(Arc::new(TORCH.clone()), 4),
+///     ticks: 10,
+/// });
+/// book.add_fuel(JUNK.id(), 25);
+///
+/// let mut smelter: Machine<_, _, DefaultSlot<_, _>> = Machine::new();
+/// smelter.input.set_item_instance(&Some(DefaultItemInstance::new(Arc::new(JUNK.clone()), 3)));
+/// smelter.fuel.set_item_instance(&Some(DefaultItemInstance::new(Arc::new(JUNK.clone()), 1)));
+/// assert_eq!(smelter.tick(&book, 25), 2);
+/// assert_eq!(smelter.output.item_instance().unwrap().quant(), 8);
+/// assert_eq!(smelter.progress().elapsed, 5);
+/// // Out of fuel, so the last one never finishes.
+/// assert_eq!(smelter.tick(&book, 100), 0);
+/// assert_eq!(smelter.input.item_instance().unwrap().quant(), 1);
+/// ```
+pub struct Machine<I: Item, II: ItemInstance<I>, S: Slot<I, II>> {
+    pub input: S,
+    pub fuel: S,
+    pub output: S,
+    progress: MachineProgress<I::Id>,
+    phantom: PhantomData<II>,
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II> + Debug> Debug for Machine<I, II, S>
+where
+    I::Id: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Machine")
+            .field("input", &self.input)
+            .field("fuel", &self.fuel)
+            .field("output", &self.output)
+            .field("progress", &self.progress)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>> Default for Machine<I, II, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>> Machine<I, II, S> {
+    /// Creates a machine with empty slots, that has not started on anything.
+    pub fn new() -> Self {
+        Machine {
+            input: S::new(None),
+            fuel: S::new(None),
+            output: S::new(None),
+            progress: MachineProgress::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// How far along the machine is.
+    pub fn progress(&self) -> &MachineProgress<I::Id> {
+        &self.progress
+    }
+
+    /// Puts back progress saved with `Machine::progress`.
+    ///
+    /// If the input slot no longer holds the recipe's input, the progress is dropped on the next tick.
+    pub fn restore(&mut self, progress: MachineProgress<I::Id>) {
+        self.progress = progress;
+    }
+
+    /// Whether fuel is burning.
+    pub fn is_burning(&self) -> bool {
+        self.progress.burn_left > 0
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>> Machine<I, II, S> {
+    /// Runs the machine for a number of ticks, and returns how many times a recipe finished.
+    ///
+    /// Fuel that is burning keeps burning whether there is anything to work on or not, but new fuel
+    /// is only taken from the fuel slot when the recipe needs it. Progress is kept while the machine
+    /// is out of fuel or the output is full, and starts over when the input changes to another item.
+    pub fn tick(&mut self, book: &RecipeBook<I, II>, ticks: u32) -> usize {
+        let mut finished = 0;
+        for _ in 0..ticks {
+            if self.tick_once(book) {
+                finished += 1;
+            }
+        }
+        finished
+    }
+
+    fn tick_once(&mut self, book: &RecipeBook<I, II>) -> bool {
+        let found = match self.input.item_instance() {
+            Some(ii) => {
+                let id = ii.item().id();
+                match book.recipe_for(&id) {
+                    Some(recipe) if available_quant_in_slot(&self.input) >= recipe.input_quant => {
+                        Some((id, recipe))
+                    }
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        let recipe = match found {
+            Some((id, recipe)) => {
+                if self.progress.recipe.as_ref() != Some(&id) {
+                    self.progress.recipe = Some(id);
+                    self.progress.elapsed = 0;
+                }
+                recipe
+            }
+            None => {
+                self.progress.recipe = None;
+                self.progress.elapsed = 0;
+                self.burn();
+                return false;
+            }
+        };
+
+        let output = match self.stacked_output(recipe) {
+            Some(output) => output,
+            None => {
+                self.burn();
+                return false;
+            }
+        };
+        if !self.is_burning() && !self.refuel(book) {
+            return false;
+        }
+        self.burn();
+        self.progress.elapsed += 1;
+        if self.progress.elapsed < recipe.ticks {
+            return false;
+        }
+
+        self.progress.elapsed = 0;
+        let input = match self.input.item_instance() {
+            Some(ii) => ii,
+            None => unreachable!("recipes are only found for items in the input slot"),
+        };
+        take_from_slot(recipe.input_quant, &mut self.input, input);
+        self.output.set_item_instance(&Some(output));
+        true
+    }
+
+    /// The output slot with the recipe's output stacked onto it, or `None` if there is no room.
+    fn stacked_output(&self, recipe: &Recipe<I, II>) -> Option<II> {
+        if self.output.locked() {
+            return None;
+        }
+        let output = recipe.output.clone();
+        let max = self.output.stack_limit(&output.item());
+        let current = match self.output.item_instance() {
+            Some(current) => current,
+            None if output.item().stackable() && output.quant() > max => return None,
+            None if max == I::Quant::ZERO => return None,
+            None => return Some(output),
+        };
+        match combine_stack_limited((Some(output), Some(current)), max) {
+            Ok((None, stack)) => stack,
+            _ => None,
+        }
+    }
+
+    fn burn(&mut self) {
+        self.progress.burn_left = self.progress.burn_left.saturating_sub(1);
+    }
+
+    /// Lights one item from the fuel slot.
+    fn refuel(&mut self, book: &RecipeBook<I, II>) -> bool {
+        let fuel = match self.fuel.item_instance() {
+            Some(fuel) if available_quant_in_slot(&self.fuel) > I::Quant::ZERO => fuel,
+            _ => return false,
+        };
+        let burn_time = match book.burn_time(&fuel.item().id()) {
+            Some(burn_time) if burn_time > 0 => burn_time,
+            _ => return false,
+        };
+        take_from_slot(I::Quant::ONE, &mut self.fuel, fuel);
+        self.progress.burn_left = burn_time;
+        self.progress.burn_time = burn_time;
+        true
+    }
+}
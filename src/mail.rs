@@ -0,0 +1,312 @@
+//! Sending items to other players by mail.
+//!
+//! Attaching items to a parcel takes them out of the sender's inventory right away, and the
+//! `PostOffice` holds them until the recipient claims them with `add_to_inventory`. Whatever
+//! does not fit stays in the parcel for later. Parcels that are not claimed in time are sent
+//! back to the sender, and returned parcels never expire.
+//!
+//! Players are referred to by an address of your choosing, like a player id. Times are whatever
+//! clock the game uses, as long as `send` and `expire` use the same one.
+//!
+//! With the `serde` feature the whole `PostOffice` can be saved, and with the `json` feature
+//! `FileStore` keeps it in a file. Anything else that implements `MailStore`, like a database,
+//! can be used instead.
+use crate::inventory_management::{add_to_inventory, available_quant_in_slot};
+use crate::restrictions::{check, Action, ItemFlags};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::Display;
+
+#[cfg(feature = "json")]
+use std::path::PathBuf;
+
+/// Identifies a parcel. Ids are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParcelId(pub u64);
+
+/// Items on their way from one player to another.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parcel<A, II> {
+    pub id: ParcelId,
+    pub sender: A,
+    pub recipient: A,
+    pub items: Vec<II>,
+    pub sent_at: u64,
+    /// When the parcel goes back to the sender. `None` once it has been returned.
+    pub expires_at: Option<u64>,
+    /// Whether this is a parcel the recipient did not claim, on its way back.
+    pub returned: bool,
+}
+
+/// Why sending or claiming a parcel failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailError {
+    /// Parcels need at least one item.
+    Empty,
+    /// The slot at this index does not hold enough to attach, or is attached twice.
+    NotEnough(usize),
+    NoSuchParcel,
+    /// Only the recipient can claim a parcel.
+    NotRecipient,
+    /// The item in the slot at this index can not be mailed, because of these flags.
+    Restricted(usize, ItemFlags),
+}
+
+impl Display for MailError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MailError::Empty => f.write_str("Parcels need at least one item."),
+            MailError::NotEnough(index) => {
+                write!(f, "Slot {index} does not hold enough to attach.")
+            }
+            MailError::NoSuchParcel => f.write_str("There is no such parcel."),
+            MailError::NotRecipient => f.write_str("Only the recipient can claim a parcel."),
+            MailError::Restricted(index, _) => {
+                write!(f, "The item in slot {index} can not be mailed.")
+            }
+        }
+    }
+}
+
+impl Error for MailError {}
+
+/// Every parcel that has been sent and not claimed yet.
+///
+/// ```
+/// # use game_inventory::mail::PostOffice;
+/// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_FULL_STACK_INST, SWORD_INST};
+/// # use game_inventory::traits::{Item, Slot};
+/// # use game_inventory::helpers::quant_in_inventory;
+/// let mut post = PostOffice::new(1000);
+/// let mut alice = vec![DefaultSlot::new(TORCH_FULL_STACK_INST.clone())];
+/// let parcel = post.send("alice", "bob", &mut alice, &[(0, 60)], 0).unwrap();
+/// assert_eq!(quant_in_inventory(&alice, TORCH.id()), 40);
+///
+/// let mut bob = vec![DefaultSlot::new(SWORD_INST.clone()), DefaultSlot::new(None)];
+/// assert_eq!(post.inbox(&"bob").count(), 1);
+/// assert_eq!(post.claim(&"bob", parcel, &mut bob), Ok(0));
+/// assert_eq!(quant_in_inventory(&bob, TORCH.id()), 60);
+/// assert!(post.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PostOffice<A, II> {
+    parcels: Vec<Parcel<A, II>>,
+    next_id: u64,
+    lifetime: u64,
+}
+
+impl<A: PartialEq, II> PostOffice<A, II> {
+    /// Creates a post office where parcels go back to the sender after `lifetime`.
+    pub fn new(lifetime: u64) -> Self {
+        PostOffice {
+            parcels: Vec::new(),
+            next_id: 0,
+            lifetime,
+        }
+    }
+
+    /// Changes how long parcels sent from now on wait to be claimed.
+    pub fn set_lifetime(&mut self, lifetime: u64) {
+        self.lifetime = lifetime;
+    }
+
+    pub fn get(&self, id: ParcelId) -> Option<&Parcel<A, II>> {
+        self.parcels.iter().find(|parcel| parcel.id == id)
+    }
+
+    /// The parcels waiting for a recipient, oldest first.
+    pub fn inbox<'a>(&'a self, recipient: &'a A) -> impl Iterator<Item = &'a Parcel<A, II>> + 'a {
+        self.parcels
+            .iter()
+            .filter(move |parcel| parcel.recipient == *recipient)
+    }
+
+    /// Every parcel, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Parcel<A, II>> {
+        self.parcels.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parcels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parcels.is_empty()
+    }
+
+    /// Sends every parcel that expired at `now` back to its sender, and returns their ids.
+    pub fn expire(&mut self, now: u64) -> Vec<ParcelId>
+    where
+        A: Clone,
+    {
+        let mut expired = Vec::new();
+        for parcel in &mut self.parcels {
+            match parcel.expires_at {
+                Some(expires_at) if expires_at <= now => {}
+                _ => continue,
+            }
+            core::mem::swap(&mut parcel.sender, &mut parcel.recipient);
+            parcel.expires_at = None;
+            parcel.returned = true;
+            expired.push(parcel.id);
+        }
+        expired
+    }
+}
+
+impl<A: PartialEq, II: Clone> PostOffice<A, II> {
+    /// Takes items out of the sender's inventory and sends them to the recipient.
+    ///
+    /// `attachments` are pairs of a slot index and how many to take from it. Unstackable items
+    /// are always attached whole. Nothing is taken if any attachment can not be made.
+    pub fn send<I: Item, S: Slot<I, II>>(
+        &mut self,
+        sender: A,
+        recipient: A,
+        inventory: &mut [S],
+        attachments: &[(usize, I::Quant)],
+        now: u64,
+    ) -> Result<ParcelId, MailError>
+    where
+        II: ItemInstance<I>,
+    {
+        if attachments.is_empty() {
+            return Err(MailError::Empty);
+        }
+        for (i, &(index, quant)) in attachments.iter().enumerate() {
+            let (held, available) = match inventory.get(index) {
+                Some(slot) => match slot.item_instance() {
+                    Some(ii) => (ii, available_quant_in_slot(slot)),
+                    None => return Err(MailError::NotEnough(index)),
+                },
+                None => return Err(MailError::NotEnough(index)),
+            };
+            let twice = attachments[..i].iter().any(|other| other.0 == index);
+            let short =
+                available == I::Quant::ZERO || (held.item().stackable() && available < quant);
+            if twice || quant == I::Quant::ZERO || short {
+                return Err(MailError::NotEnough(index));
+            }
+            if let Err(restricted) = check(&held, Action::Mail) {
+                return Err(MailError::Restricted(index, restricted.flags));
+            }
+        }
+
+        let mut items = Vec::new();
+        for &(index, quant) in attachments {
+            let held = match inventory[index].item_instance() {
+                Some(ii) => ii,
+                None => unreachable!("attachments were checked above"),
+            };
+            let left = held.quant().saturating_sub(quant);
+            if !held.item().stackable() || left == I::Quant::ZERO {
+                inventory[index].set_item_instance(&None);
+                items.push(held);
+            } else {
+                inventory[index].set_item_instance(&Some(held.with_quant(left)));
+                items.push(held.with_quant(quant));
+            }
+        }
+
+        let id = ParcelId(self.next_id);
+        self.next_id += 1;
+        self.parcels.push(Parcel {
+            id,
+            sender,
+            recipient,
+            items,
+            sent_at: now,
+            expires_at: Some(now.saturating_add(self.lifetime)),
+            returned: false,
+        });
+        Ok(id)
+    }
+
+    /// Adds the items of a parcel to the recipient's inventory.
+    ///
+    /// Returns how many item instances did not fit and stay in the parcel. Once it is empty
+    /// the parcel is removed.
+    pub fn claim<I: Item, S: Slot<I, II>>(
+        &mut self,
+        recipient: &A,
+        id: ParcelId,
+        inventory: &mut [S],
+    ) -> Result<usize, MailError>
+    where
+        II: ItemInstance<I>,
+    {
+        let index = match self.parcels.iter().position(|parcel| parcel.id == id) {
+            Some(index) => index,
+            None => return Err(MailError::NoSuchParcel),
+        };
+        let parcel = &mut self.parcels[index];
+        if parcel.recipient != *recipient {
+            return Err(MailError::NotRecipient);
+        }
+        parcel.items = core::mem::take(&mut parcel.items)
+            .into_iter()
+            .filter_map(|ii| add_to_inventory(inventory, ii))
+            .collect();
+        let left = parcel.items.len();
+        if left == 0 {
+            self.parcels.remove(index);
+        }
+        Ok(left)
+    }
+}
+
+/// Somewhere a `PostOffice` is kept between sessions.
+pub trait MailStore<A, II> {
+    type Error;
+
+    /// Loads the post office, or `None` if nothing has been saved yet.
+    fn load(&self) -> Result<Option<PostOffice<A, II>>, Self::Error>;
+
+    fn save(&mut self, post: &PostOffice<A, II>) -> Result<(), Self::Error>;
+}
+
+/// Keeps a `PostOffice` in a json file.
+///
+/// Saving writes to a temporary file next to it first, so a crash while saving never leaves
+/// a half written file behind.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    pub path: PathBuf,
+}
+
+#[cfg(feature = "json")]
+impl FileStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileStore { path: path.into() }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<A, II> MailStore<A, II> for FileStore
+where
+    A: serde::Serialize + serde::de::DeserializeOwned,
+    II: serde::Serialize + serde::de::DeserializeOwned,
+{
+    type Error = std::io::Error;
+
+    fn load(&self) -> std::io::Result<Option<PostOffice<A, II>>> {
+        let src = match std::fs::read_to_string(&self.path) {
+            Ok(src) => src,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        Ok(Some(serde_json::from_str(&src)?))
+    }
+
+    fn save(&mut self, post: &PostOffice<A, II>) -> std::io::Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, serde_json::to_vec(post)?)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
@@ -0,0 +1,556 @@
+//! A player market where items are bought and sold through an order book.
+//!
+//! Sell orders take their items out of the seller's inventory, and buy orders take their
+//! currency out of the buyer's wallet, so the `Market` holds everything that is on offer.
+//! A new order is matched against the resting orders for the same item right away, best price
+//! first and oldest first among equal prices, and trades at the resting order's price.
+//! Orders can be filled in parts, and whatever is left stays in the book until it is cancelled.
+//!
+//! What traders get out of trades and cancellations goes into their `Account`, so they do not
+//! have to be online. Currency is taken out with `Market::withdraw` and items with
+//! `Market::collect`. Every change is recorded in `Market::history`.
+//!
+//! Currency is a plain `u64` amount, and prices are per single item. Unstackable items are
+//! always traded one at a time.
+use crate::inventory_management::{add_to_inventory, available_quant_in_slot, single_quant};
+use crate::restrictions::{check, Action, ItemFlags};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Debug, Display};
+
+/// Identifies an order. Ids are never reused, and later orders have larger ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderId(pub u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// An order in the book.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Order<Id, Q, A> {
+    pub id: OrderId,
+    pub trader: A,
+    pub item: Id,
+    pub side: OrderSide,
+    /// The price of a single item. Buyers never pay more, sellers never get less.
+    pub price: u64,
+    /// How many are still to be bought or sold.
+    pub quant: Q,
+}
+
+/// Something that happened on the market, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarketEvent<Id, Q, A> {
+    Placed(Order<Id, Q, A>),
+    Filled {
+        buy: OrderId,
+        sell: OrderId,
+        buyer: A,
+        seller: A,
+        item: Id,
+        quant: Q,
+        price: u64,
+        /// Taken from what the seller got.
+        fee: u64,
+    },
+    /// An order was cancelled, and what it held went back to the trader's account.
+    Cancelled {
+        order: OrderId,
+        trader: A,
+        quant: Q,
+    },
+    Withdrawn {
+        trader: A,
+        money: u64,
+    },
+    Collected {
+        trader: A,
+        item: Id,
+        quant: Q,
+    },
+}
+
+/// What a trader got out of the market and has not taken out yet.
+#[derive(Debug, Clone)]
+pub struct Account<A, II> {
+    pub trader: A,
+    pub money: u64,
+    pub items: Vec<II>,
+}
+
+/// Why an order could not be placed or cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketError {
+    /// Orders need a quantity above zero.
+    ZeroQuantity,
+    /// The slot does not hold enough to sell.
+    NotEnough,
+    /// The wallet can not pay for the whole order.
+    InsufficientFunds,
+    /// The order costs more than a `u64` can hold.
+    Overflow,
+    NoSuchOrder,
+    /// Only the trader who placed an order can cancel it.
+    NotOwner,
+    /// The item can not be sold, because of these flags.
+    Restricted(ItemFlags),
+}
+
+impl Display for MarketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MarketError::ZeroQuantity => f.write_str("Orders need a quantity above zero."),
+            MarketError::NotEnough => f.write_str("The slot does not hold enough to sell."),
+            MarketError::InsufficientFunds => {
+                f.write_str("The wallet can not pay for the whole order.")
+            }
+            MarketError::Overflow => f.write_str("The order costs too much."),
+            MarketError::NoSuchOrder => f.write_str("There is no such order."),
+            MarketError::NotOwner => {
+                f.write_str("Only the trader who placed an order can cancel it.")
+            }
+            MarketError::Restricted(_) => f.write_str("That item can not be sold."),
+        }
+    }
+}
+
+impl Error for MarketError {}
+
+/// An order and what it holds.
+struct Entry<I: Item, II, A> {
+    order: Order<I::Id, I::Quant, A>,
+    /// The items a sell order sells.
+    items: Option<II>,
+    /// The currency a buy order pays with.
+    money: u64,
+}
+
+/// The order book and accounts of a market.
+///
+/// ```
+/// # use game_inventory::market::Market;
+/// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_FULL_STACK_INST};
+/// # use game_inventory::traits::{Item, Slot};
+/// # use game_inventory::helpers::quant_in_inventory;
+/// // Takes a 5% fee.
+/// let mut market = Market::new(500);
+/// let mut alice = vec![DefaultSlot::new(TORCH_FULL_STACK_INST.clone())];
+/// market.sell("alice", &mut alice, 0, 60, 10).unwrap();
+///
+/// let mut wallet = 1000;
+/// market.buy("bob", &mut wallet, TORCH.id(), 50, 12).unwrap();
+/// assert_eq!(wallet, 400);
+/// // Bob offered 12 but the torches were for sale at 10.
+/// assert_eq!(market.withdraw(&"bob"), 100);
+/// assert_eq!(market.withdraw(&"alice"), 475);
+/// assert_eq!(market.fees(), 25);
+///
+/// let mut bob = vec![DefaultSlot::new(None)];
+/// assert_eq!(market.collect(&"bob", &mut bob), 0);
+/// assert_eq!(quant_in_inventory(&bob, TORCH.id()), 50);
+/// assert_eq!(market.orders(&TORCH.id()).next().unwrap().quant, 10);
+/// ```
+pub struct Market<I: Item, II: ItemInstance<I>, A> {
+    entries: Vec<Entry<I, II, A>>,
+    accounts: Vec<Account<A, II>>,
+    history: Vec<MarketEvent<I::Id, I::Quant, A>>,
+    next_id: u64,
+    fee: u32,
+    fees: u64,
+}
+
+impl<I: Item, II: ItemInstance<I> + Debug, A: Debug> Debug for Market<I, II, A>
+where
+    Order<I::Id, I::Quant, A>: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let orders: Vec<_> = self.entries.iter().map(|entry| &entry.order).collect();
+        f.debug_struct("Market")
+            .field("orders", &orders)
+            .field("accounts", &self.accounts)
+            .field("fee", &self.fee)
+            .field("fees", &self.fees)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone, A: PartialEq + Clone> Market<I, II, A>
+where
+    I::Id: Clone,
+{
+    /// Creates an empty market that takes `fee` basis points (hundredths of a percent) of every sale.
+    ///
+    /// Fees above 10 000, the whole sale, are clamped to it.
+    pub fn new(fee: u32) -> Self {
+        Market {
+            entries: Vec::new(),
+            accounts: Vec::new(),
+            history: Vec::new(),
+            next_id: 0,
+            fee: fee.min(10_000),
+            fees: 0,
+        }
+    }
+
+    /// How much currency the market has taken in fees.
+    pub fn fees(&self) -> u64 {
+        self.fees
+    }
+
+    pub fn order(&self, id: OrderId) -> Option<&Order<I::Id, I::Quant, A>> {
+        self.entries
+            .iter()
+            .map(|entry| &entry.order)
+            .find(|order| order.id == id)
+    }
+
+    /// The resting orders for an item, oldest first.
+    pub fn orders<'a>(
+        &'a self,
+        item: &'a I::Id,
+    ) -> impl Iterator<Item = &'a Order<I::Id, I::Quant, A>> + 'a {
+        self.entries
+            .iter()
+            .map(|entry| &entry.order)
+            .filter(move |order| order.item == *item)
+    }
+
+    pub fn account(&self, trader: &A) -> Option<&Account<A, II>> {
+        self.accounts
+            .iter()
+            .find(|account| account.trader == *trader)
+    }
+
+    /// Everything that happened on the market, oldest first.
+    pub fn history(&self) -> &[MarketEvent<I::Id, I::Quant, A>] {
+        &self.history
+    }
+
+    /// Takes items out of a slot and offers them for `price` each.
+    pub fn sell<S: Slot<I, II>>(
+        &mut self,
+        trader: A,
+        inventory: &mut [S],
+        slot: usize,
+        quant: I::Quant,
+        price: u64,
+    ) -> Result<OrderId, MarketError> {
+        if quant == I::Quant::ZERO {
+            return Err(MarketError::ZeroQuantity);
+        }
+        let held = match inventory.get(slot).and_then(|slot| slot.item_instance()) {
+            Some(held) => held,
+            None => return Err(MarketError::NotEnough),
+        };
+        if let Err(restricted) = check(&held, Action::Sell) {
+            return Err(MarketError::Restricted(restricted.flags));
+        }
+        let item = held.item();
+        let available = available_quant_in_slot(&inventory[slot]);
+        if available == I::Quant::ZERO {
+            return Err(MarketError::NotEnough);
+        }
+        let (quant, items) = if !item.stackable() {
+            inventory[slot].set_item_instance(&None);
+            (I::Quant::ONE, held)
+        } else if available < quant {
+            return Err(MarketError::NotEnough);
+        } else {
+            let left = held.quant().saturating_sub(quant);
+            if left == I::Quant::ZERO {
+                inventory[slot].set_item_instance(&None);
+            } else {
+                inventory[slot].set_item_instance(&Some(held.with_quant(left)));
+            }
+            (quant, held.with_quant(quant))
+        };
+        Ok(self.place(trader, item.id(), price, quant, Some(items), 0))
+    }
+
+    /// Takes the currency for `quant` items at `price` each out of the wallet, and offers to buy them.
+    ///
+    /// If the items are bought for less, the difference goes to the buyer's account.
+    pub fn buy(
+        &mut self,
+        trader: A,
+        wallet: &mut u64,
+        item: I::Id,
+        quant: I::Quant,
+        price: u64,
+    ) -> Result<OrderId, MarketError> {
+        if quant == I::Quant::ZERO {
+            return Err(MarketError::ZeroQuantity);
+        }
+        let money = match cost(price, quant) {
+            Some(money) => money,
+            None => return Err(MarketError::Overflow),
+        };
+        if *wallet < money {
+            return Err(MarketError::InsufficientFunds);
+        }
+        *wallet -= money;
+        Ok(self.place(trader, item, price, quant, None, money))
+    }
+
+    /// Removes an order from the book, and puts what it held in the trader's account.
+    pub fn cancel(&mut self, trader: &A, id: OrderId) -> Result<(), MarketError> {
+        let index = match self.entries.iter().position(|entry| entry.order.id == id) {
+            Some(index) => index,
+            None => return Err(MarketError::NoSuchOrder),
+        };
+        if self.entries[index].order.trader != *trader {
+            return Err(MarketError::NotOwner);
+        }
+        let quant = self.entries[index].order.quant;
+        self.remove_entry(index);
+        self.history.push(MarketEvent::Cancelled {
+            order: id,
+            trader: trader.clone(),
+            quant,
+        });
+        Ok(())
+    }
+
+    /// Takes all the currency out of a trader's account.
+    pub fn withdraw(&mut self, trader: &A) -> u64 {
+        let money = match self
+            .accounts
+            .iter_mut()
+            .find(|account| account.trader == *trader)
+        {
+            Some(account) => core::mem::take(&mut account.money),
+            None => 0,
+        };
+        if money > 0 {
+            self.history.push(MarketEvent::Withdrawn {
+                trader: trader.clone(),
+                money,
+            });
+        }
+        money
+    }
+
+    /// Adds the items in a trader's account to an inventory.
+    ///
+    /// Returns how many item instances did not fit and stay in the account.
+    pub fn collect<S: Slot<I, II>>(&mut self, trader: &A, inventory: &mut [S]) -> usize {
+        let account = match self
+            .accounts
+            .iter_mut()
+            .find(|account| account.trader == *trader)
+        {
+            Some(account) => account,
+            None => return 0,
+        };
+        let mut left = Vec::new();
+        for ii in core::mem::take(&mut account.items) {
+            let item = ii.item();
+            let quant = single_quant(&ii);
+            let not_added = add_to_inventory(inventory, ii);
+            let added = match &not_added {
+                Some(_) if !item.stackable() => I::Quant::ZERO,
+                Some(not_added) => quant.saturating_sub(not_added.quant()),
+                None => quant,
+            };
+            if added > I::Quant::ZERO {
+                self.history.push(MarketEvent::Collected {
+                    trader: trader.clone(),
+                    item: item.id(),
+                    quant: added,
+                });
+            }
+            left.extend(not_added);
+        }
+        account.items = left;
+        account.items.len()
+    }
+
+    /// Adds an order to the book and matches it. Orders that hold items are sell orders.
+    fn place(
+        &mut self,
+        trader: A,
+        item: I::Id,
+        price: u64,
+        quant: I::Quant,
+        items: Option<II>,
+        money: u64,
+    ) -> OrderId {
+        let side = match items {
+            Some(_) => OrderSide::Sell,
+            None => OrderSide::Buy,
+        };
+        let id = OrderId(self.next_id);
+        self.next_id += 1;
+        let order = Order {
+            id,
+            trader,
+            item,
+            side,
+            price,
+            quant,
+        };
+        self.history.push(MarketEvent::Placed(order.clone()));
+        self.entries.push(Entry {
+            order,
+            items,
+            money,
+        });
+
+        let mut taker = self.entries.len() - 1;
+        while self.entries[taker].order.quant > I::Quant::ZERO {
+            let maker = match self.best_match(taker) {
+                Some(maker) => maker,
+                None => break,
+            };
+            let (buy, sell) = match side {
+                OrderSide::Buy => (taker, maker),
+                OrderSide::Sell => (maker, taker),
+            };
+            self.fill(buy, sell);
+            if self.entries[maker].order.quant == I::Quant::ZERO {
+                self.remove_entry(maker);
+                taker -= 1;
+            }
+        }
+        if self.entries[taker].order.quant == I::Quant::ZERO {
+            self.remove_entry(taker);
+        }
+        id
+    }
+
+    /// The resting order a new order trades with first.
+    fn best_match(&self, taker: usize) -> Option<usize> {
+        let order = &self.entries[taker].order;
+        let mut best: Option<usize> = None;
+        for (index, entry) in self.entries[..taker].iter().enumerate() {
+            let other = &entry.order;
+            if other.item != order.item || other.side == order.side {
+                continue;
+            }
+            let crosses = match order.side {
+                OrderSide::Buy => other.price <= order.price,
+                OrderSide::Sell => other.price >= order.price,
+            };
+            if !crosses {
+                continue;
+            }
+            // Earlier entries are older, so only a strictly better price replaces the best.
+            let better = match best {
+                None => true,
+                Some(best) => match order.side {
+                    OrderSide::Buy => other.price < self.entries[best].order.price,
+                    OrderSide::Sell => other.price > self.entries[best].order.price,
+                },
+            };
+            if better {
+                best = Some(index);
+            }
+        }
+        best
+    }
+
+    /// Trades as much as possible between a buy and a sell order, at the price of the older one.
+    fn fill(&mut self, buy: usize, sell: usize) {
+        let quant = if self.entries[buy].order.quant < self.entries[sell].order.quant {
+            self.entries[buy].order.quant
+        } else {
+            self.entries[sell].order.quant
+        };
+        let price = if buy < sell {
+            self.entries[buy].order.price
+        } else {
+            self.entries[sell].order.price
+        };
+        let total = match cost(price, quant) {
+            Some(total) => total,
+            None => unreachable!("the buy order holds at least this much"),
+        };
+        let fee = (total as u128 * self.fee as u128 / 10_000) as u64;
+
+        let held = match self.entries[sell].items.take() {
+            Some(held) => held,
+            None => unreachable!("sell orders hold their items until they are filled"),
+        };
+        let bought = if !held.item().stackable() {
+            held
+        } else {
+            let left = held.quant().saturating_sub(quant);
+            if left > I::Quant::ZERO {
+                self.entries[sell].items = Some(held.with_quant(left));
+            }
+            held.with_quant(quant)
+        };
+        self.entries[sell].order.quant = self.entries[sell].order.quant.saturating_sub(quant);
+        self.entries[buy].order.quant = self.entries[buy].order.quant.saturating_sub(quant);
+        self.entries[buy].money -= total;
+        self.fees += fee;
+        // Buying for less than offered frees up some of what the buy order holds.
+        let keep = match cost(self.entries[buy].order.price, self.entries[buy].order.quant) {
+            Some(keep) => keep,
+            None => unreachable!("the rest of the order cost less than the whole"),
+        };
+        let refund = self.entries[buy].money - keep;
+        self.entries[buy].money = keep;
+
+        let buyer = self.entries[buy].order.trader.clone();
+        let seller = self.entries[sell].order.trader.clone();
+        let account = self.account_mut(&buyer);
+        account.items.push(bought);
+        account.money += refund;
+        self.account_mut(&seller).money += total.saturating_sub(fee);
+        self.history.push(MarketEvent::Filled {
+            buy: self.entries[buy].order.id,
+            sell: self.entries[sell].order.id,
+            buyer,
+            seller,
+            item: self.entries[sell].order.item.clone(),
+            quant,
+            price,
+            fee,
+        });
+    }
+
+    /// Removes an order, and puts what it still holds in the trader's account.
+    fn remove_entry(&mut self, index: usize) {
+        let entry = self.entries.remove(index);
+        if entry.money == 0 && entry.items.is_none() {
+            return;
+        }
+        let account = self.account_mut(&entry.order.trader);
+        account.money += entry.money;
+        account.items.extend(entry.items);
+    }
+
+    fn account_mut(&mut self, trader: &A) -> &mut Account<A, II> {
+        let index = match self
+            .accounts
+            .iter()
+            .position(|account| account.trader == *trader)
+        {
+            Some(index) => index,
+            None => {
+                self.accounts.push(Account {
+                    trader: trader.clone(),
+                    money: 0,
+                    items: Vec::new(),
+                });
+                self.accounts.len() - 1
+            }
+        };
+        &mut self.accounts[index]
+    }
+}
+
+/// What `quant` items cost at `price` each, if it fits in a `u64`.
+fn cost<Q: Quantity>(price: u64, quant: Q) -> Option<u64> {
+    let total = (price as u128).checked_mul(quant.to_u128())?;
+    u64::try_from(total).ok()
+}
@@ -0,0 +1,137 @@
+//! What to do with the items that do not fit when adding to an inventory.
+//!
+//! `add_to_inventory` hands the leftover back to the caller. An `OverflowPolicy` decides what
+//! happens to it instead, either for a single call, or for every add through `WithPolicy`.
+//!
+//! - `Reject` only adds the item if all of it fits.
+//! - `Partial` adds as much as fits and returns the rest, like `add_to_inventory`.
+//! - `Spill` adds the rest to a second inventory.
+//! - `DropInWorld` adds as much as fits and passes the rest to a callback, to drop it on the ground.
+//!
+//! ```
+//! # use game_inventory::overflow::{OverflowPolicy, Reject, Spill};
+//! # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH, TORCH_INST};
+//! # use game_inventory::traits::{Item, ItemInstance, Slot};
+//! # use game_inventory::helpers::quant_in_inventory;
+//! # use std::sync::Arc;
+//! let mut backpack = vec![DefaultSlot::new(TORCH_INST.clone())];
+//! let mut chest = vec![DefaultSlot::new(None)];
+//! let torches = || DefaultItemInstance::new(Arc::new(TORCH.clone()), 90);
+//!
+//! assert_eq!(Reject.add(&mut backpack, torches()).unwrap().quant(), 90);
+//! assert_eq!(quant_in_inventory(&backpack, TORCH.id()), 23);
+//!
+//! assert!(Spill(&mut chest).add(&mut backpack, torches()).is_none());
+//! assert_eq!(quant_in_inventory(&backpack, TORCH.id()), 100);
+//! assert_eq!(quant_in_inventory(&chest, TORCH.id()), 13);
+//! ```
+use crate::inventory_management::{add_to_inventory, can_add_item_to_inventory};
+use crate::traits::{Item, ItemInstance, Slot};
+use core::ops::DerefMut;
+
+/// Adds items to an inventory, and decides what happens to the ones that do not fit.
+pub trait OverflowPolicy<I: Item, II: ItemInstance<I>, S: Slot<I, II>> {
+    /// Adds an item instance to the inventory, returning whatever the caller still has to deal with.
+    fn add(&mut self, inventory: &mut [S], other: II) -> Option<II>;
+}
+
+/// Adds nothing unless all of the item fits, and returns it untouched otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reject;
+
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>> OverflowPolicy<I, II, S> for Reject {
+    fn add(&mut self, inventory: &mut [S], other: II) -> Option<II> {
+        if !can_add_item_to_inventory(inventory, other.clone()) {
+            return Some(other);
+        }
+        add_to_inventory(inventory, other)
+    }
+}
+
+/// Adds as much as fits, and returns the rest. Same as `add_to_inventory`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Partial;
+
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>> OverflowPolicy<I, II, S> for Partial {
+    fn add(&mut self, inventory: &mut [S], other: II) -> Option<II> {
+        add_to_inventory(inventory, other)
+    }
+}
+
+/// Adds what does not fit to a second inventory, and returns what does not fit in either.
+#[derive(Debug)]
+pub struct Spill<'a, S>(pub &'a mut [S]);
+
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>, T: Slot<I, II>> OverflowPolicy<I, II, S>
+    for Spill<'_, T>
+{
+    fn add(&mut self, inventory: &mut [S], other: II) -> Option<II> {
+        match add_to_inventory(inventory, other) {
+            Some(left) => add_to_inventory(self.0, left),
+            None => None,
+        }
+    }
+}
+
+/// Adds as much as fits, and passes the rest to a callback, so it can be dropped in the world.
+///
+/// Never returns anything, since the callback takes care of all of it.
+///
+/// ```
+/// # use game_inventory::overflow::{DropInWorld, OverflowPolicy};
+/// # use game_inventory::samples::{DefaultSlot, SWORD_INST};
+/// # use game_inventory::traits::{ItemInstance, Slot};
+/// let mut inventory = vec![DefaultSlot::new(SWORD_INST.clone())];
+/// let mut dropped = Vec::new();
+/// let left = DropInWorld(|ii| dropped.push(ii)).add(&mut inventory, SWORD_INST.clone().unwrap());
+/// assert!(left.is_none());
+/// assert_eq!(dropped.len(), 1);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DropInWorld<F>(pub F);
+
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>, F: FnMut(II)> OverflowPolicy<I, II, S>
+    for DropInWorld<F>
+{
+    fn add(&mut self, inventory: &mut [S], other: II) -> Option<II> {
+        if let Some(left) = add_to_inventory(inventory, other) {
+            (self.0)(left);
+        }
+        None
+    }
+}
+
+/// An inventory that always uses the same `OverflowPolicy`.
+///
+/// Works with anything that derefs to `[S]`, like a `Vec` or a `FixedInventory`.
+///
+/// ```
+/// # use game_inventory::overflow::{Reject, WithPolicy};
+/// # use game_inventory::samples::{DefaultSlot, TORCH_FULL_STACK_INST, TORCH_INST};
+/// # use game_inventory::traits::Slot;
+/// let mut inventory = WithPolicy::new(vec![DefaultSlot::new(TORCH_INST.clone())], Reject);
+/// assert!(inventory.add(TORCH_FULL_STACK_INST.clone().unwrap()).is_some());
+/// assert!(inventory.add(TORCH_INST.clone().unwrap()).is_none());
+/// assert_eq!(inventory.inventory.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WithPolicy<C, P> {
+    pub inventory: C,
+    pub policy: P,
+}
+
+impl<C, P> WithPolicy<C, P> {
+    /// Pairs an inventory with a policy.
+    pub fn new(inventory: C, policy: P) -> Self {
+        WithPolicy { inventory, policy }
+    }
+
+    /// Adds an item instance to the inventory, using the policy for what does not fit.
+    pub fn add<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(&mut self, other: II) -> Option<II>
+    where
+        C: DerefMut<Target = [S]>,
+        P: OverflowPolicy<I, II, S>,
+    {
+        self.policy.add(&mut self.inventory, other)
+    }
+}
@@ -0,0 +1,186 @@
+//! Reserving items in an inventory, so other systems can not take them.
+//!
+//! A queued crafting job or a pending trade can `reserve` the items it is going to use.
+//! Reserved items stay in their slots and still count towards `quant_in_inventory`,
+//! but `remove_from_inventory` and the other helpers leave them alone, and
+//! `available_quant_in_inventory` does not count them.
+//!
+//! Reservations are kept in a `Ledger` on each slot, and `reserve` hands out a `Reservation`
+//! that releases them when it is dropped, so a cancelled job can never leave items stuck.
+//! Reservations belong to slots, not items, so they stay on the slot if its contents
+//! are changed directly.
+use crate::inventory_management::available_quant_in_slot;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+static NEXT_RESERVATION: AtomicU64 = AtomicU64::new(0);
+
+/// The reservations on a single slot.
+///
+/// Cloning a ledger gives another handle to the same reservations.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger<Q> {
+    entries: Arc<Mutex<Vec<(u64, Q)>>>,
+}
+
+impl<Q: Quantity> Ledger<Q> {
+    /// Creates a ledger without any reservations.
+    pub fn new() -> Self {
+        Ledger {
+            entries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// The total quantity reserved.
+    pub fn total(&self) -> Q {
+        self.lock()
+            .iter()
+            .fold(Q::ZERO, |total, entry| total.saturating_add(entry.1))
+    }
+
+    /// Whether anything is reserved.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(u64, Q)>> {
+        // Entries are only pushed and removed, so a panic can not leave them half changed.
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A slot that keeps its reservations in a `Ledger`, so `reserve` can add to them.
+///
+/// `Slot::reserved` should return the ledger's total.
+pub trait Reservable<I: Item, II: ItemInstance<I>>: Slot<I, II> {
+    fn ledger(&self) -> &Ledger<I::Quant>;
+}
+
+/// Items reserved in one or more slots of an inventory. Released when dropped.
+#[must_use = "the items are released as soon as the reservation is dropped"]
+#[derive(Debug)]
+pub struct Reservation<Q: Quantity> {
+    id: u64,
+    /// The index of each slot, its ledger, and how much is reserved in it.
+    parts: Vec<(usize, Ledger<Q>, Q)>,
+}
+
+impl<Q: Quantity> Reservation<Q> {
+    /// The total quantity reserved.
+    pub fn quant(&self) -> Q {
+        self.parts
+            .iter()
+            .fold(Q::ZERO, |total, part| total.saturating_add(part.2))
+    }
+
+    /// The index of each reserved slot, with how much is reserved in it.
+    pub fn slots(&self) -> impl Iterator<Item = (usize, Q)> + '_ {
+        self.parts.iter().map(|part| (part.0, part.2))
+    }
+
+    /// Releases the reservation and takes the reserved items out of the inventory it was made on.
+    ///
+    /// Returns how many could not be taken, if a slot no longer holds what was reserved in it.
+    pub fn consume<I: Item<Quant = Q>, II: ItemInstance<I>, S: Slot<I, II>>(
+        mut self,
+        inventory: &mut [S],
+    ) -> Q {
+        let parts = core::mem::take(&mut self.parts);
+        let mut missing = Q::ZERO;
+        for (index, ledger, quant) in parts {
+            release(&ledger, self.id);
+            let slot = match inventory.get_mut(index) {
+                Some(slot) => slot,
+                None => {
+                    missing = missing.saturating_add(quant);
+                    continue;
+                }
+            };
+            let taken = available_quant_in_slot(slot).min(quant);
+            missing = missing.saturating_add(quant.saturating_sub(taken));
+            let ii = match slot.item_instance() {
+                Some(ii) if taken > Q::ZERO => ii,
+                _ => continue,
+            };
+            let left = ii.quant().saturating_sub(taken);
+            if !ii.item().stackable() || left == Q::ZERO {
+                slot.set_item_instance(&None);
+            } else {
+                slot.set_item_instance(&Some(ii.with_quant(left)));
+            }
+        }
+        missing
+    }
+}
+
+impl<Q: Quantity> Drop for Reservation<Q> {
+    fn drop(&mut self) {
+        for (_, ledger, _) in &self.parts {
+            release(ledger, self.id);
+        }
+    }
+}
+
+fn release<Q: Quantity>(ledger: &Ledger<Q>, id: u64) {
+    ledger.lock().retain(|entry| entry.0 != id);
+}
+
+/// Reserves a quantity of an item in the first unlocked slots that have it available,
+/// counting unstackable items as one.
+///
+/// Returns `None` without reserving anything if not enough is available.
+///
+/// ```
+/// # use game_inventory::reservation::reserve;
+/// # use game_inventory::samples::{DefaultItemInstance, ReservableSlot, TORCH};
+/// # use game_inventory::traits::{Item, ItemInstance, Slot};
+/// # use game_inventory::helpers::{available_quant_in_inventory, remove_from_inventory};
+/// # use std::sync::Arc;
+/// let torches = |quant| DefaultItemInstance::new(Arc::new(TORCH.clone()), quant);
+/// let mut inventory = vec![ReservableSlot::new(Some(torches(10)))];
+/// let job = reserve(&inventory, TORCH.id(), 8).unwrap();
+/// assert!(reserve(&inventory, TORCH.id(), 3).is_none());
+///
+/// // Only the 2 that are not reserved can be taken.
+/// assert_eq!(remove_from_inventory(&mut inventory, torches(5)).unwrap().quant(), 3);
+/// assert_eq!(available_quant_in_inventory(&inventory, TORCH.id()), 0);
+///
+/// drop(job);
+/// assert_eq!(available_quant_in_inventory(&inventory, TORCH.id()), 8);
+/// ```
+pub fn reserve<I: Item, II: ItemInstance<I>, S: Reservable<I, II>>(
+    inventory: &[S],
+    id: I::Id,
+    quant: I::Quant,
+) -> Option<Reservation<I::Quant>> {
+    let mut parts = Vec::new();
+    let mut left = quant;
+    for (index, slot) in inventory.iter().enumerate() {
+        if left == I::Quant::ZERO {
+            break;
+        }
+        match slot.item_instance() {
+            Some(ii) if ii.item().id() == id => {}
+            _ => continue,
+        }
+        let reserved = available_quant_in_slot(slot).min(left);
+        if reserved > I::Quant::ZERO {
+            parts.push((index, slot.ledger().clone(), reserved));
+            left = left.saturating_sub(reserved);
+        }
+    }
+    if left > I::Quant::ZERO {
+        return None;
+    }
+    let reservation = Reservation {
+        id: NEXT_RESERVATION.fetch_add(1, Ordering::Relaxed),
+        parts,
+    };
+    for (_, ledger, reserved) in &reservation.parts {
+        ledger.lock().push((reservation.id, *reserved));
+    }
+    Some(reservation)
+}
@@ -0,0 +1,210 @@
+//! Rules for items that must not leave the player's inventory, like quest items and bound gear.
+//!
+//! Items get their `ItemFlags` from `Item::flags`, and instances can add their own through
+//! `ItemInstance::flags`. Every path in this crate that hands items to someone else checks them
+//! with `check` and rejects restricted items with an error naming the flags:
+//!
+//! - `TradeSession::offer` and `TradeSession::execute` for `Action::Trade`
+//! - `Market::sell` for `Action::Sell`
+//! - `PostOffice::send` for `Action::Mail`
+//! - `GroundItems::drop_from` for `Action::Drop`
+//! - `SharedInventory::move_to` and `Transport` for `Action::Move`, which leave restricted
+//!   items where they are
+//!
+//! ```
+//! # use game_inventory::restrictions::{check, Action, ItemFlags};
+//! # use game_inventory::samples::{DefaultItemInstance, QUEST_MAP};
+//! # use game_inventory::traits::ItemInstance;
+//! # use std::sync::Arc;
+//! let map = DefaultItemInstance::new(Arc::new(QUEST_MAP.clone()), 0);
+//! let err = check(&map, Action::Trade).unwrap_err();
+//! assert_eq!(err.flags, ItemFlags::BOUND);
+//! assert!(check(&map, Action::Sell).is_err());
+//! ```
+use crate::inventory_management::{available_quant_in_slot, take_from_slot};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use core::error::Error;
+use core::fmt::Display;
+use core::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// A set of rules for where an item may go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemFlags(u8);
+
+impl ItemFlags {
+    /// No restrictions.
+    pub const NONE: ItemFlags = ItemFlags(0);
+    /// Bound to whoever holds it, so it can not be dropped, traded, sold or mailed.
+    pub const BOUND: ItemFlags = ItemFlags(1);
+    /// Can not be dropped on the ground.
+    pub const NO_DROP: ItemFlags = ItemFlags(1 << 1);
+    /// Can not be given to another player, by trading, mailing or selling it.
+    pub const NO_TRADE: ItemFlags = ItemFlags(1 << 2);
+    /// Can not be sold.
+    pub const NO_SELL: ItemFlags = ItemFlags(1 << 3);
+    /// Removed by `destroy_on_logout`.
+    pub const DESTROY_ON_LOGOUT: ItemFlags = ItemFlags(1 << 4);
+
+    /// The flags set in either `self` or `other`. Same as `|`, but usable in statics.
+    pub const fn union(self, other: ItemFlags) -> ItemFlags {
+        ItemFlags(self.0 | other.0)
+    }
+
+    /// Whether no flags are set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all of the flags in `other` are set.
+    pub fn contains(self, other: ItemFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ItemFlags {
+    type Output = ItemFlags;
+    fn bitor(self, other: ItemFlags) -> ItemFlags {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for ItemFlags {
+    fn bitor_assign(&mut self, other: ItemFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for ItemFlags {
+    type Output = ItemFlags;
+    fn bitand(self, other: ItemFlags) -> ItemFlags {
+        ItemFlags(self.0 & other.0)
+    }
+}
+
+/// A way items can leave a player's inventory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    /// Dropping it on the ground.
+    Drop,
+    /// Trading it to another player.
+    Trade,
+    /// Selling it on a market.
+    Sell,
+    /// Mailing it to another player.
+    Mail,
+    /// Moving it into another inventory, like a shared chest or along a transport link.
+    Move,
+}
+
+impl Action {
+    /// The flags that forbid the action.
+    pub fn forbidden_by(self) -> ItemFlags {
+        ItemFlags::BOUND
+            | match self {
+                Action::Drop => ItemFlags::NO_DROP,
+                Action::Trade | Action::Mail | Action::Move => ItemFlags::NO_TRADE,
+                Action::Sell => ItemFlags::NO_TRADE | ItemFlags::NO_SELL,
+            }
+    }
+}
+
+/// An item was not allowed to leave the inventory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Restricted {
+    pub action: Action,
+    /// The item's flags that forbid the action.
+    pub flags: ItemFlags,
+}
+
+impl Display for Restricted {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "The item can not be used for {:?}.", self.action)
+    }
+}
+
+impl Error for Restricted {}
+
+/// Checks whether an item instance may be used for an action.
+pub fn check<I: Item, II: ItemInstance<I>>(ii: &II, action: Action) -> Result<(), Restricted> {
+    let flags = ii.flags() & action.forbidden_by();
+    if flags.is_empty() {
+        return Ok(());
+    }
+    Err(Restricted { action, flags })
+}
+
+/// Same as `available_quant_in_inventory`, but only counts the items that may be used for an action.
+pub fn allowed_quant_in_inventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &[S],
+    id: I::Id,
+    action: Action,
+) -> I::Quant {
+    inventory
+        .iter()
+        .fold(I::Quant::ZERO, |quant, slot| match slot.item_instance() {
+            Some(ii) if ii.item().id() == id && check(&ii, action).is_ok() => {
+                quant.saturating_add(available_quant_in_slot(slot))
+            }
+            _ => quant,
+        })
+}
+
+/// Same as `remove_from_inventory`, but leaves the items that may not be used for an action alone.
+///
+/// Useful when only some instances of an item are bound, so the unbound ones are taken.
+pub fn remove_allowed_from_inventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &mut [S],
+    other: II,
+    action: Action,
+) -> Option<II> {
+    let id = other.item().id();
+    let remaining = inventory.iter_mut().fold(other.quant(), |current, slot| {
+        let s = match slot.item_instance() {
+            Some(s) => s,
+            None => return current,
+        };
+        if current == I::Quant::ZERO || s.item().id() != id || check(&s, action).is_err() {
+            return current;
+        }
+        take_from_slot(current, slot, s)
+    });
+    if remaining == I::Quant::ZERO {
+        return None;
+    }
+    Some(other.with_quant(remaining))
+}
+
+/// Empties every slot holding an item flagged `ItemFlags::DESTROY_ON_LOGOUT`,
+/// and returns how many slots were emptied.
+///
+/// Locks and reservations do not keep the items around.
+///
+/// ```
+/// # use game_inventory::restrictions::destroy_on_logout;
+/// # use game_inventory::samples::{DefaultItemInstance, DefaultSlot, EVENT_TOKEN};
+/// # use game_inventory::traits::{ItemInstance, Slot};
+/// # use std::sync::Arc;
+/// let tokens = DefaultItemInstance::new(Arc::new(EVENT_TOKEN.clone()), 5);
+/// let mut inventory = vec![DefaultSlot::new(Some(tokens)), DefaultSlot::new(None)];
+/// assert_eq!(destroy_on_logout(&mut inventory), 1);
+/// assert!(inventory[0].item_instance().is_none());
+/// ```
+pub fn destroy_on_logout<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &mut [S],
+) -> usize {
+    let mut destroyed = 0;
+    for slot in inventory.iter_mut() {
+        let flagged = match slot.item_instance() {
+            Some(ii) => ii.flags().contains(ItemFlags::DESTROY_ON_LOGOUT),
+            None => false,
+        };
+        if flagged {
+            slot.set_item_instance(&None);
+            slot.set_modified(true);
+            destroyed += 1;
+        }
+    }
+    destroyed
+}
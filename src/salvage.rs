@@ -0,0 +1,218 @@
+//! Breaking items down into the components they are made of, like gear into scrap and gems.
+//!
+//! `SalvageTables` maps item ids to the components they yield. A component either always
+//! yields the same amount, or rolls its amount and whether it drops at all with an `Rng`.
+//! Components can scale with the `ItemInstance::condition` of what is salvaged, so worn gear
+//! yields less.
+//!
+//! `SalvageTables::salvage` takes one item out of an inventory slot and adds what it yields
+//! to the same inventory, but only if all of it fits.
+//!
+//! ```
+//! # use game_inventory::generation::SeededRng;
+//! # use game_inventory::salvage::{Component, SalvageTables};
+//! # use game_inventory::samples::{DefaultItemInstance, DefaultSlot, JUNK, SWORD, TORCH};
+//! # use game_inventory::traits::{Item, ItemInstance, Slot};
+//! # use game_inventory::helpers::quant_in_inventory;
+//! # use std::sync::Arc;
+//! let mut tables = SalvageTables::new();
+//! tables.add_table(
+//!     SWORD.id(),
+//!     vec![
+//!         Component::fixed(DefaultItemInstance::new(Arc::new(JUNK.clone()), 3)),
+//!         Component::random(DefaultItemInstance::new(Arc::new(TORCH.clone()), 2), 1, 50),
+//!     ],
+//! );
+//! let mut inventory = vec![
+//!     DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(SWORD.clone()), 0))),
+//!     DefaultSlot::new(None),
+//! ];
+//! let results = tables.salvage(&mut inventory, 0, &mut SeededRng::new(1)).unwrap();
+//! assert_eq!(results[0].quant(), 3);
+//! assert_eq!(quant_in_inventory(&inventory, JUNK.id()), 3);
+//! assert_eq!(quant_in_inventory(&inventory, SWORD.id()), 0);
+//! ```
+use crate::generation::Rng;
+use crate::inventory_management::{
+    add_to_inventory, available_quant_in_slot, can_add_item_to_inventory, take_from_slot, Preview,
+};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::Display;
+
+/// Something an item yields when it is salvaged.
+#[derive(Debug, Clone)]
+pub struct Component<I: Item, II: ItemInstance<I>> {
+    /// The most the component yields. Unstackable outputs always yield the instance itself.
+    pub output: II,
+    /// The least the component yields, before scaling. The amount is rolled between this and
+    /// the quantity of `output`.
+    pub min_quant: I::Quant,
+    /// How likely the component is to yield anything, out of 100.
+    pub chance: u32,
+    /// Whether the amount is multiplied by the `condition` of the salvaged instance.
+    pub scaled: bool,
+}
+
+impl<I: Item, II: ItemInstance<I>> Component<I, II> {
+    /// A component that always yields all of `output`.
+    pub fn fixed(output: II) -> Self {
+        Component {
+            min_quant: output.quant(),
+            output,
+            chance: 100,
+            scaled: false,
+        }
+    }
+
+    /// A component that yields between `min_quant` and all of `output`, `chance` times out of 100.
+    pub fn random(output: II, min_quant: I::Quant, chance: u32) -> Self {
+        Component {
+            output,
+            min_quant,
+            chance,
+            scaled: false,
+        }
+    }
+
+    /// Rolls what the component yields for an instance in a condition.
+    fn roll<R: Rng>(&self, condition: f32, rng: &mut R) -> Option<II>
+    where
+        II: Clone,
+    {
+        if self.chance < 100 && rng.below(100) >= self.chance as u64 {
+            return None;
+        }
+        if !self.output.item().stackable() {
+            return Some(self.output.clone());
+        }
+        let (min, max) = (self.min_quant.to_u128(), self.output.quant().to_u128());
+        let mut quant = match max > min {
+            true => min + rng.below((max - min + 1) as u64) as u128,
+            false => max,
+        };
+        if self.scaled {
+            quant = (quant as f32 * condition.clamp(0.0, 1.0)) as u128;
+        }
+        if quant == 0 {
+            return None;
+        }
+        Some(
+            self.output
+                .with_quant(I::Quant::from_u128_saturating(quant)),
+        )
+    }
+}
+
+/// Why an item could not be salvaged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SalvageError {
+    /// There is no item in the slot.
+    EmptySlot,
+    /// The slot is locked, or all of its items are reserved.
+    Locked,
+    /// There is no salvage table for the item.
+    NotSalvageable,
+    /// The inventory has no room for what the item yields.
+    NoSpace,
+}
+
+impl Display for SalvageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            SalvageError::EmptySlot => "There is no item in that slot.",
+            SalvageError::Locked => "That item is locked or reserved.",
+            SalvageError::NotSalvageable => "That item can not be salvaged.",
+            SalvageError::NoSpace => "There is no room in the inventory for the salvaged items.",
+        })
+    }
+}
+
+impl Error for SalvageError {}
+
+/// What each salvageable item yields, by item id.
+#[derive(Debug, Clone)]
+#[allow(clippy::type_complexity)]
+pub struct SalvageTables<I: Item, II: ItemInstance<I>> {
+    tables: Vec<(I::Id, Vec<Component<I, II>>)>,
+}
+
+impl<I: Item, II: ItemInstance<I>> Default for SalvageTables<I, II> {
+    fn default() -> Self {
+        SalvageTables { tables: Vec::new() }
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone> SalvageTables<I, II> {
+    /// Creates tables that nothing can be salvaged with.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes an item salvageable. If an item has two tables, the first one is used.
+    pub fn add_table(&mut self, id: I::Id, components: Vec<Component<I, II>>) {
+        self.tables.push((id, components));
+    }
+
+    /// The components an item yields, or `None` if it can not be salvaged.
+    pub fn table_for(&self, id: &I::Id) -> Option<&[Component<I, II>]> {
+        self.tables
+            .iter()
+            .find(|table| table.0 == *id)
+            .map(|table| table.1.as_slice())
+    }
+
+    /// Rolls what salvaging one of an instance yields, without taking it from anywhere.
+    ///
+    /// Returns `None` if the item can not be salvaged.
+    pub fn roll<R: Rng>(&self, ii: &II, rng: &mut R) -> Option<Vec<II>> {
+        let condition = ii.condition();
+        let components = self.table_for(&ii.item().id())?;
+        Some(
+            components
+                .iter()
+                .filter_map(|component| component.roll(condition, rng))
+                .collect(),
+        )
+    }
+
+    /// Salvages one item out of an inventory slot, adding what it yields to the same inventory.
+    ///
+    /// Returns what was added. Nothing changes if it does not all fit, even though the rolls
+    /// still advance `rng`.
+    pub fn salvage<S: Slot<I, II>, R: Rng>(
+        &self,
+        inventory: &mut [S],
+        index: usize,
+        rng: &mut R,
+    ) -> Result<Vec<II>, SalvageError> {
+        let ii = match inventory.get(index).and_then(|slot| slot.item_instance()) {
+            Some(ii) => ii,
+            None => return Err(SalvageError::EmptySlot),
+        };
+        if available_quant_in_slot(&inventory[index]) == I::Quant::ZERO {
+            return Err(SalvageError::Locked);
+        }
+        let results = match self.roll(&ii, rng) {
+            Some(results) => results,
+            None => return Err(SalvageError::NotSalvageable),
+        };
+        let mut preview: Vec<Preview<S, II>> = inventory.iter().map(Preview::of).collect();
+        take_from_slot(I::Quant::ONE, &mut preview[index], ii.clone());
+        let fits = results.iter().all(|result| {
+            if !can_add_item_to_inventory(&preview, result.clone()) {
+                return false;
+            }
+            add_to_inventory(&mut preview, result.clone()).is_none()
+        });
+        if !fits {
+            return Err(SalvageError::NoSpace);
+        }
+        take_from_slot(I::Quant::ONE, &mut inventory[index], ii);
+        for result in &results {
+            add_to_inventory(inventory, result.clone());
+        }
+        Ok(results)
+    }
+}
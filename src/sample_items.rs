@@ -7,7 +7,8 @@
 
 use std::sync::Arc;
 
-use crate::sample_structs::{DefaultItem, DefaultItemInstance};
+use crate::restrictions::ItemFlags;
+use crate::sample_structs::{DefaultItem, DefaultItemInstance, FlaggedItem, TaggedItem};
 
 // Define simple stackable items as static, not const
 
@@ -29,6 +30,41 @@ pub static SWORD: DefaultItem = DefaultItem {
     max_quantity: 0,
 };
 
+/// A stackable item tagged as a plank.
+pub static OAK_PLANK: TaggedItem = TaggedItem {
+    name: "oak_plank",
+    max_quantity: 64,
+    tags: &["wood/plank"],
+};
+
+/// Another stackable item tagged as a plank.
+pub static BIRCH_PLANK: TaggedItem = TaggedItem {
+    name: "birch_plank",
+    max_quantity: 64,
+    tags: &["wood/plank"],
+};
+
+/// A stackable item that is a log and a fuel, but not a plank.
+pub static OAK_LOG: TaggedItem = TaggedItem {
+    name: "oak_log",
+    max_quantity: 64,
+    tags: &["wood/log", "fuel"],
+};
+
+/// An unstackable quest item that is bound and can not be dropped.
+pub static QUEST_MAP: FlaggedItem = FlaggedItem {
+    name: "quest_map",
+    max_quantity: 0,
+    flags: ItemFlags::BOUND.union(ItemFlags::NO_DROP),
+};
+
+/// A stackable item that can be traded but not sold, and is destroyed on logout.
+pub static EVENT_TOKEN: FlaggedItem = FlaggedItem {
+    name: "event_token",
+    max_quantity: 50,
+    flags: ItemFlags::NO_SELL.union(ItemFlags::DESTROY_ON_LOGOUT),
+};
+
 lazy_static::lazy_static! {
     /// A DefaultItemInstance of the sword item for testing slot and inventory management.
     pub static ref SWORD_INST: Option<DefaultItemInstance<DefaultItem<'static>>> = Some(DefaultItemInstance {
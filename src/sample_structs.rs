@@ -1,7 +1,20 @@
 //! A collection of sample structs used for testing the system, and showing how it can be used.
-use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+use core::{fmt::Debug, marker::PhantomData};
 
-use crate::traits::{Item, ItemInstance, Slot};
+#[cfg(feature = "alloc")]
+use crate::equipment::{EquipSlot, Equippable, Modifier};
+#[cfg(feature = "alloc")]
+use crate::generation::{Rollable, Rolls};
+#[cfg(feature = "alloc")]
+use crate::sockets::Socketed;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+#[cfg(feature = "std")]
+use crate::reservation::{Ledger, Reservable};
+use crate::restrictions::ItemFlags;
+use crate::slot_management::StackLimit;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
 
 /// A sample item struct used for testing.
 ///
@@ -9,19 +22,23 @@ use crate::traits::{Item, ItemInstance, Slot};
 ///
 /// As long as your implementation satisfies the trait bounds it does not matter what immutable
 /// item data you put in here.
+///
+/// Quantities are stored as `u16` unless you choose a different `Quantity` type.
 #[derive(Debug, Clone)]
-pub struct DefaultItem<'a> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefaultItem<'a, Q = u16> {
     pub name: &'a str,
-    pub max_quantity: u16,
+    pub max_quantity: Q,
 }
 
-impl<'a> Item for DefaultItem<'a> {
+impl<'a, Q: Quantity> Item for DefaultItem<'a, Q> {
     type Id = &'a str;
+    type Quant = Q;
     fn stackable(&self) -> bool {
-        self.max_quantity > 1
+        self.max_quantity > Q::ONE
     }
 
-    fn max_quant(&self) -> u16 {
+    fn max_quant(&self) -> Q {
         self.max_quantity
     }
 
@@ -30,20 +47,124 @@ impl<'a> Item for DefaultItem<'a> {
     }
 }
 
+/// A sample item struct with tags, like `DefaultItem` otherwise.
+#[derive(Debug, Clone)]
+pub struct TaggedItem<'a, Q = u16> {
+    pub name: &'a str,
+    pub max_quantity: Q,
+    pub tags: &'a [&'a str],
+}
+
+impl<'a, Q: Quantity> Item for TaggedItem<'a, Q> {
+    type Id = &'a str;
+    type Quant = Q;
+    fn stackable(&self) -> bool {
+        self.max_quantity > Q::ONE
+    }
+
+    fn max_quant(&self) -> Q {
+        self.max_quantity
+    }
+
+    fn id(&self) -> &'a str {
+        self.name
+    }
+
+    fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().copied()
+    }
+}
+
+/// A sample item struct with `ItemFlags`, like `DefaultItem` otherwise.
+#[derive(Debug, Clone)]
+pub struct FlaggedItem<'a, Q = u16> {
+    pub name: &'a str,
+    pub max_quantity: Q,
+    pub flags: ItemFlags,
+}
+
+impl<'a, Q: Quantity> Item for FlaggedItem<'a, Q> {
+    type Id = &'a str;
+    type Quant = Q;
+    fn stackable(&self) -> bool {
+        self.max_quantity > Q::ONE
+    }
+
+    fn max_quant(&self) -> Q {
+        self.max_quantity
+    }
+
+    fn id(&self) -> &'a str {
+        self.name
+    }
+
+    fn flags(&self) -> ItemFlags {
+        self.flags
+    }
+}
+
+/// A sample item struct that owns all of its data.
+///
+/// Unlike `DefaultItem` this does not borrow its name, so it can be
+/// deserialized from item definition files and stored in an `ItemRegistry`.
+///
+/// `extra` is flattened into the item definition, so any fields other than
+/// `id`, `stackable` and `max_quantity` end up in it. Use your own struct or a map
+/// like `HashMap<String, serde_json::Value>` for it.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataItem<E = (), Q = u16> {
+    pub id: String,
+    #[cfg_attr(feature = "serde", serde(default = "stackable_by_default"))]
+    pub stackable: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_quantity: Q,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: E,
+}
+
+#[cfg(feature = "serde")]
+fn stackable_by_default() -> bool {
+    true
+}
+
+#[cfg(feature = "alloc")]
+impl<E, Q: Quantity> Item for DataItem<E, Q> {
+    type Id = String;
+    type Quant = Q;
+    fn stackable(&self) -> bool {
+        self.stackable
+    }
+
+    fn max_quant(&self) -> Q {
+        self.max_quantity
+    }
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
+
 /// A sample item instance struct used for testing.
 ///
 /// Has the minimum amount of fields required to make the system work.
 ///
 /// As long as your implementation satisfies the trait bounds it does not matter what instanced
 /// item data you put in here.
+#[cfg(feature = "alloc")]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DefaultItemInstance<I: Item> {
     pub item: Arc<I>,
-    pub quantity: u16,
+    pub quantity: I::Quant,
 }
 
-impl<'a, I: Item> ItemInstance<I> for DefaultItemInstance<I> {
-    fn quant(&self) -> u16 {
+#[cfg(feature = "alloc")]
+impl<I: Item> ItemInstance<I> for DefaultItemInstance<I> {
+    type ItemRef = Arc<I>;
+
+    fn quant(&self) -> I::Quant {
         self.quantity
     }
 
@@ -51,11 +172,335 @@ impl<'a, I: Item> ItemInstance<I> for DefaultItemInstance<I> {
         self.item.clone()
     }
 
-    fn new(item: Arc<I>, quantity: u16) -> Self {
+    fn new(item: Arc<I>, quantity: I::Quant) -> Self {
         DefaultItemInstance { item, quantity }
     }
 }
 
+/// A sample item instance struct with flags of its own, on top of its item's flags.
+///
+/// New instances have no flags of their own. Only instances with the same flags stack, and
+/// splitting a stack keeps its flags.
+///
+/// ```
+/// # use game_inventory::restrictions::{check, Action, ItemFlags};
+/// # use game_inventory::samples::{FlaggedItemInstance, SWORD};
+/// # use game_inventory::traits::ItemInstance;
+/// # use std::sync::Arc;
+/// let mut sword = FlaggedItemInstance::new(Arc::new(SWORD.clone()), 0);
+/// assert!(check(&sword, Action::Trade).is_ok());
+/// sword.flags = ItemFlags::BOUND;
+/// assert!(check(&sword, Action::Trade).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlaggedItemInstance<I: Item> {
+    pub item: Arc<I>,
+    pub quantity: I::Quant,
+    pub flags: ItemFlags,
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item> ItemInstance<I> for FlaggedItemInstance<I> {
+    type ItemRef = Arc<I>;
+
+    fn quant(&self) -> I::Quant {
+        self.quantity
+    }
+
+    fn item(&self) -> Arc<I> {
+        self.item.clone()
+    }
+
+    fn new(item: Arc<I>, quantity: I::Quant) -> Self {
+        FlaggedItemInstance {
+            item,
+            quantity,
+            flags: ItemFlags::NONE,
+        }
+    }
+
+    fn with_quant(&self, quantity: I::Quant) -> Self {
+        FlaggedItemInstance {
+            item: self.item.clone(),
+            quantity,
+            flags: self.flags,
+        }
+    }
+
+    fn with_item(&self, item: Self::ItemRef) -> Self {
+        let mut ii = self.with_quant(self.quantity);
+        ii.item = item;
+        ii
+    }
+
+    fn stacks_with(&self, other: &Self) -> bool {
+        self.flags == other.flags
+    }
+
+    fn flags(&self) -> ItemFlags {
+        self.flags | self.item.flags()
+    }
+}
+
+/// A sample item instance struct that can hold the rolls of an `ItemGenerator`.
+///
+/// Rolled instances never stack with other instances, so each keeps its own affixes.
+///
+/// ```
+/// # use game_inventory::generation::{Rollable, Rolls};
+/// # use game_inventory::helpers::combine_stack;
+/// # use game_inventory::samples::{RolledItemInstance, TORCH};
+/// # use game_inventory::traits::ItemInstance;
+/// # use std::sync::Arc;
+/// let plain = || Some(RolledItemInstance::new(Arc::new(TORCH.clone()), 1));
+/// let rolls = Rolls { rarity: "magic", affixes: Vec::new() };
+/// let rolled = Some(RolledItemInstance::rolled(Arc::new(TORCH.clone()), rolls));
+/// assert!(combine_stack((plain(), plain())).is_ok());
+/// assert!(combine_stack((rolled, plain())).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RolledItemInstance<I: Item, K = &'static str> {
+    pub item: Arc<I>,
+    pub quantity: I::Quant,
+    pub rolls: Option<Rolls<K>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item, K: Clone> ItemInstance<I> for RolledItemInstance<I, K> {
+    type ItemRef = Arc<I>;
+
+    fn quant(&self) -> I::Quant {
+        self.quantity
+    }
+
+    fn item(&self) -> Arc<I> {
+        self.item.clone()
+    }
+
+    fn new(item: Arc<I>, quantity: I::Quant) -> Self {
+        RolledItemInstance {
+            item,
+            quantity,
+            rolls: None,
+        }
+    }
+
+    fn with_quant(&self, quantity: I::Quant) -> Self {
+        RolledItemInstance {
+            item: self.item.clone(),
+            quantity,
+            rolls: self.rolls.clone(),
+        }
+    }
+
+    fn with_item(&self, item: Self::ItemRef) -> Self {
+        let mut ii = self.with_quant(self.quantity);
+        ii.item = item;
+        ii
+    }
+
+    fn stacks_with(&self, other: &Self) -> bool {
+        self.rolls.is_none() && other.rolls.is_none()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item, K: Clone> Rollable<I, K> for RolledItemInstance<I, K> {
+    fn rolled(item: Arc<I>, rolls: Rolls<K>) -> Self {
+        RolledItemInstance {
+            item,
+            quantity: I::Quant::ONE,
+            rolls: Some(rolls),
+        }
+    }
+}
+
+/// A sample item instance struct that wears down, see `ItemInstance::condition`.
+///
+/// Only instances in the same condition stack, and splitting a stack keeps its condition.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DurableItemInstance<I: Item> {
+    pub item: Arc<I>,
+    pub quantity: I::Quant,
+    pub condition: f32,
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item> ItemInstance<I> for DurableItemInstance<I> {
+    type ItemRef = Arc<I>;
+
+    fn quant(&self) -> I::Quant {
+        self.quantity
+    }
+
+    fn item(&self) -> Arc<I> {
+        self.item.clone()
+    }
+
+    fn new(item: Arc<I>, quantity: I::Quant) -> Self {
+        DurableItemInstance {
+            item,
+            quantity,
+            condition: 1.0,
+        }
+    }
+
+    fn with_quant(&self, quantity: I::Quant) -> Self {
+        DurableItemInstance {
+            item: self.item.clone(),
+            quantity,
+            condition: self.condition,
+        }
+    }
+
+    fn with_item(&self, item: Self::ItemRef) -> Self {
+        let mut ii = self.with_quant(self.quantity);
+        ii.item = item;
+        ii
+    }
+
+    fn stacks_with(&self, other: &Self) -> bool {
+        self.condition == other.condition
+    }
+
+    fn condition(&self) -> f32 {
+        self.condition
+    }
+}
+
+/// A sample item instance struct with sockets, that hold other instances like gems or runes.
+///
+/// Instances only stack with the same number of sockets, while all of them are empty.
+///
+/// ```
+/// # use game_inventory::helpers::combine_stack;
+/// # use game_inventory::samples::{SocketedItemInstance, TORCH};
+/// # use game_inventory::sockets::Socketed;
+/// # use game_inventory::traits::ItemInstance;
+/// # use std::sync::Arc;
+/// let torch = || SocketedItemInstance::with_sockets(Arc::new(TORCH.clone()), 1);
+/// assert!(combine_stack((Some(torch()), Some(torch()))).is_ok());
+/// let mut socketed = torch();
+/// socketed.set_socket(0, Some(torch()));
+/// assert!(combine_stack((Some(socketed), Some(torch()))).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SocketedItemInstance<I: Item> {
+    pub item: Arc<I>,
+    pub quantity: I::Quant,
+    pub sockets: Vec<Option<SocketedItemInstance<I>>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item> SocketedItemInstance<I> {
+    /// Creates a single item with a number of empty sockets.
+    pub fn with_sockets(item: Arc<I>, sockets: usize) -> Self {
+        let mut ii = Self::new(item, I::Quant::ONE);
+        ii.sockets.resize_with(sockets, || None);
+        ii
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item> ItemInstance<I> for SocketedItemInstance<I> {
+    type ItemRef = Arc<I>;
+
+    fn quant(&self) -> I::Quant {
+        self.quantity
+    }
+
+    fn item(&self) -> Arc<I> {
+        self.item.clone()
+    }
+
+    fn new(item: Arc<I>, quantity: I::Quant) -> Self {
+        SocketedItemInstance {
+            item,
+            quantity,
+            sockets: Vec::new(),
+        }
+    }
+
+    fn with_quant(&self, quantity: I::Quant) -> Self {
+        SocketedItemInstance {
+            item: self.item.clone(),
+            quantity,
+            sockets: self
+                .sockets
+                .iter()
+                .map(|gem| gem.as_ref().map(|gem| gem.with_quant(gem.quant())))
+                .collect(),
+        }
+    }
+
+    fn with_item(&self, item: Self::ItemRef) -> Self {
+        let mut ii = self.with_quant(self.quantity);
+        ii.item = item;
+        ii
+    }
+
+    fn stacks_with(&self, other: &Self) -> bool {
+        self.sockets.len() == other.sockets.len()
+            && self
+                .sockets
+                .iter()
+                .chain(&other.sockets)
+                .all(Option::is_none)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Item> Socketed<I> for SocketedItemInstance<I> {
+    fn sockets(&self) -> &[Option<Self>] {
+        &self.sockets
+    }
+
+    fn set_socket(&mut self, socket: usize, gem: Option<Self>) {
+        self.sockets[socket] = gem;
+    }
+}
+
+/// A sample item instance struct that borrows its item instead of sharing it through an `Arc`.
+///
+/// Works without an allocator, usually with items stored in statics.
+#[derive(Debug)]
+pub struct RefItemInstance<'a, I: Item> {
+    pub item: &'a I,
+    pub quantity: I::Quant,
+}
+
+impl<I: Item> Clone for RefItemInstance<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: Item> Copy for RefItemInstance<'_, I> {}
+
+impl<'a, I: Item> ItemInstance<I> for RefItemInstance<'a, I> {
+    type ItemRef = &'a I;
+
+    fn quant(&self) -> I::Quant {
+        self.quantity
+    }
+
+    fn item(&self) -> &'a I {
+        self.item
+    }
+
+    fn new(item: &'a I, quantity: I::Quant) -> Self {
+        RefItemInstance { item, quantity }
+    }
+}
+
 /// A sample slot struct used for testing.
 ///
 /// A significant reduction in boiler plate would
@@ -72,7 +517,7 @@ pub struct DefaultSlot<'a, I: Item, II: ItemInstance<I>> {
 }
 
 impl<'a, I: Item, II: ItemInstance<I> + Debug> Debug for DefaultSlot<'a, I, II> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("BasicSlot")
             .field("item_instance", &self.item_instance)
             .field("modified", &self.modified)
@@ -106,3 +551,213 @@ impl<'a, I: Item, II: ItemInstance<I> + Sized + Clone> Slot<I, II> for DefaultSl
         }
     }
 }
+
+/// A sample slot struct that can be locked and have items reserved in it.
+///
+/// Otherwise the same as `DefaultSlot`.
+///
+/// ```
+/// # use game_inventory::samples::{ReservableSlot, TORCH_INST, SWORD_INST};
+/// # use game_inventory::helpers::add_to_inventory;
+/// # use game_inventory::traits::Slot;
+/// let mut inventory = vec![ReservableSlot::new(None), ReservableSlot::new(None)];
+/// inventory[0].locked = true;
+/// add_to_inventory(&mut inventory, TORCH_INST.clone().unwrap());
+/// assert!(inventory[0].item_instance().is_none());
+/// assert!(inventory[1].item_instance().is_some());
+/// // Locked slots can not be clicked either.
+/// assert!(inventory[0].transfer(SWORD_INST.clone(), "left_click").is_some());
+/// ```
+#[cfg(feature = "std")]
+pub struct ReservableSlot<'a, I: Item, II: ItemInstance<I>> {
+    pub item_instance: Option<II>,
+    pub modified: bool,
+    pub locked: bool,
+    pub ledger: Ledger<I::Quant>,
+    pub phantom: PhantomData<&'a I>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, I: Item, II: ItemInstance<I> + Debug> Debug for ReservableSlot<'a, I, II> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReservableSlot")
+            .field("item_instance", &self.item_instance)
+            .field("modified", &self.modified)
+            .field("locked", &self.locked)
+            .field("ledger", &self.ledger)
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, I: Item, II: ItemInstance<I> + Clone> Slot<I, II> for ReservableSlot<'a, I, II> {
+    fn item_instance(&self) -> Option<II> {
+        self.item_instance.clone()
+    }
+
+    fn set_item_instance(&mut self, item_instance: &Option<II>) {
+        self.set_modified(true);
+        self.item_instance = item_instance.clone()
+    }
+
+    fn locked(&self) -> bool {
+        self.locked
+    }
+
+    fn reserved(&self) -> I::Quant {
+        self.ledger.total()
+    }
+
+    fn modified(&mut self) -> bool {
+        self.modified
+    }
+
+    fn set_modified(&mut self, modified: bool) {
+        self.modified = modified
+    }
+
+    fn new(item_instance: Option<II>) -> Self {
+        ReservableSlot {
+            item_instance,
+            modified: false,
+            locked: false,
+            ledger: Ledger::new(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, I: Item, II: ItemInstance<I> + Clone> Reservable<I, II> for ReservableSlot<'a, I, II> {
+    fn ledger(&self) -> &Ledger<I::Quant> {
+        &self.ledger
+    }
+}
+
+/// A sample slot struct with its own stack limit, like a slot in an ammo pouch.
+///
+/// Otherwise the same as `DefaultSlot`. `Slot::new` gives it `StackLimit::Item`.
+///
+/// ```
+/// # use game_inventory::samples::{LimitedSlot, TORCH_INST, TORCH_FULL_STACK_INST};
+/// # use game_inventory::helpers::{StackLimit, add_to_inventory};
+/// # use game_inventory::traits::{ItemInstance, Slot};
+/// let mut pouch = vec![
+///     LimitedSlot::with_limit(None, StackLimit::Cap(20)),
+///     LimitedSlot::with_limit(None, StackLimit::Cap(20)),
+/// ];
+/// let left = add_to_inventory(&mut pouch, TORCH_FULL_STACK_INST.clone().unwrap());
+/// assert_eq!(pouch[0].item_instance().unwrap().quant(), 20);
+/// assert_eq!(pouch[1].item_instance().unwrap().quant(), 20);
+/// assert_eq!(left.unwrap().quant(), 60);
+/// ```
+pub struct LimitedSlot<'a, I: Item, II: ItemInstance<I>> {
+    pub item_instance: Option<II>,
+    pub modified: bool,
+    pub limit: StackLimit<I::Quant>,
+    pub phantom: PhantomData<&'a I>,
+}
+
+impl<'a, I: Item, II: ItemInstance<I>> LimitedSlot<'a, I, II> {
+    /// Creates a slot with the given stack limit.
+    pub fn with_limit(item_instance: Option<II>, limit: StackLimit<I::Quant>) -> Self {
+        LimitedSlot {
+            item_instance,
+            modified: false,
+            limit,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, I: Item, II: ItemInstance<I> + Debug> Debug for LimitedSlot<'a, I, II> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LimitedSlot")
+            .field("item_instance", &self.item_instance)
+            .field("modified", &self.modified)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<'a, I: Item, II: ItemInstance<I> + Clone> Slot<I, II> for LimitedSlot<'a, I, II> {
+    fn item_instance(&self) -> Option<II> {
+        self.item_instance.clone()
+    }
+
+    fn set_item_instance(&mut self, item_instance: &Option<II>) {
+        self.set_modified(true);
+        self.item_instance = item_instance.clone()
+    }
+
+    fn stack_limit(&self, item: &I) -> I::Quant {
+        self.limit.of(item)
+    }
+
+    fn modified(&mut self) -> bool {
+        self.modified
+    }
+
+    fn set_modified(&mut self, modified: bool) {
+        self.modified = modified
+    }
+
+    fn new(item_instance: Option<II>) -> Self {
+        Self::with_limit(item_instance, StackLimit::Item)
+    }
+}
+
+/// A sample equippable item.
+///
+/// Stacks up to `max_quantity`, so gear with a `max_quantity` of 1 is unstackable
+/// while gems that are socketed into it can stack.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct Gear<'a> {
+    pub name: &'a str,
+    pub max_quantity: u16,
+    pub equip_slot: Option<EquipSlot>,
+    pub two_handed: bool,
+    pub set: Option<&'a str>,
+    pub modifiers: Vec<Modifier<&'a str>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Item for Gear<'a> {
+    type Id = &'a str;
+    type Quant = u16;
+
+    fn stackable(&self) -> bool {
+        self.max_quantity > 1
+    }
+
+    fn max_quant(&self) -> u16 {
+        self.max_quantity
+    }
+
+    fn id(&self) -> &'a str {
+        self.name
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Equippable for Gear<'a> {
+    type Stat = &'a str;
+    type Set = &'a str;
+
+    fn equip_slot(&self) -> Option<EquipSlot> {
+        self.equip_slot
+    }
+
+    fn two_handed(&self) -> bool {
+        self.two_handed
+    }
+
+    fn modifiers(&self) -> &[Modifier<&'a str>] {
+        &self.modifiers
+    }
+
+    fn set(&self) -> Option<&'a str> {
+        self.set
+    }
+}
@@ -0,0 +1,279 @@
+//! An inventory that can be shared between threads.
+//!
+//! `SharedInventory` puts the slots behind a single `RwLock`, so queries from many threads can
+//! run at the same time, while every modification sees and leaves the inventory in a consistent
+//! state. Each method takes the lock once, so an add or remove is never interleaved with
+//! another thread's changes and items can not be lost or duplicated.
+//!
+//! To run several operations as one, use `SharedInventory::write`.
+use crate::inventory_management::{
+    add_to_inventory, available_quant_in_slot, can_add_item_to_inventory, empty_quant_in_inventory,
+    inventory_contains_item_type, quant_in_inventory, remove_from_inventory, take_from_slot,
+    takes_unstackable,
+};
+use crate::overflow::OverflowPolicy;
+use crate::restrictions::{check, Action};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A `Vec<Slot>` behind an `Arc<RwLock>`.
+///
+/// Cloning it gives another handle to the same inventory, which can be sent to another thread.
+///
+/// ```
+/// # use game_inventory::shared_inventory::SharedInventory;
+/// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_INST};
+/// # use game_inventory::traits::{Item, Slot};
+/// let chest = SharedInventory::new((0..10).map(|_| DefaultSlot::new(None)).collect());
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         let chest = chest.clone();
+///         scope.spawn(move || chest.add(TORCH_INST.clone().unwrap()));
+///     }
+/// });
+/// assert_eq!(chest.quant(TORCH.id()), 92);
+/// ```
+pub struct SharedInventory<I: Item, II: ItemInstance<I>, S: Slot<I, II>> {
+    slots: Arc<RwLock<Vec<S>>>,
+    phantom: PhantomData<fn() -> (I, II)>,
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II>> Clone for SharedInventory<I, II, S> {
+    fn clone(&self) -> Self {
+        SharedInventory {
+            slots: self.slots.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>, S: Slot<I, II> + Debug> Debug for SharedInventory<I, II, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedInventory")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>> SharedInventory<I, II, S> {
+    /// Shares the given slots.
+    pub fn new(slots: Vec<S>) -> Self {
+        SharedInventory {
+            slots: Arc::new(RwLock::new(slots)),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Runs a function with read access to the slots.
+    ///
+    /// Other threads can read at the same time, but not write.
+    ///
+    /// # Panics
+    /// If another thread panicked while writing to the inventory.
+    pub fn read<R, F: FnOnce(&[S]) -> R>(&self, f: F) -> R {
+        f(&self.read_guard())
+    }
+
+    /// Runs a function with exclusive access to the slots.
+    ///
+    /// Everything the function does is seen by other threads as a single change.
+    ///
+    /// ```
+    /// # use game_inventory::shared_inventory::SharedInventory;
+    /// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_INST};
+    /// # use game_inventory::traits::{Item, Slot};
+    /// # use game_inventory::helpers::{can_add_item_to_inventory, add_to_inventory};
+    /// let chest = SharedInventory::new(vec![DefaultSlot::new(None)]);
+    /// let added = chest.write(|slots| {
+    ///     if !can_add_item_to_inventory(slots, TORCH_INST.clone().unwrap()) {
+    ///         return false;
+    ///     }
+    ///     add_to_inventory(slots, TORCH_INST.clone().unwrap());
+    ///     true
+    /// });
+    /// assert!(added);
+    /// ```
+    ///
+    /// # Panics
+    /// If another thread panicked while writing to the inventory.
+    pub fn write<R, F: FnOnce(&mut [S]) -> R>(&self, f: F) -> R {
+        f(&mut self.write_guard())
+    }
+
+    /// Same as `add_to_inventory`.
+    pub fn add(&self, other: II) -> Option<II> {
+        self.write(|slots| add_to_inventory(slots, other))
+    }
+
+    /// Adds an item instance using an `OverflowPolicy`, as a single change.
+    pub fn add_with<P: OverflowPolicy<I, II, S>>(&self, other: II, policy: &mut P) -> Option<II> {
+        self.write(|slots| policy.add(slots, other))
+    }
+
+    /// Same as `remove_from_inventory`.
+    pub fn remove(&self, other: II) -> Option<II> {
+        self.write(|slots| remove_from_inventory(slots, other))
+    }
+
+    /// Calls `Slot::transfer` on a slot.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn transfer(&self, index: usize, item_instance: Option<II>, button: &str) -> Option<II> {
+        self.write(|slots| slots[index].transfer(item_instance, button))
+    }
+
+    /// Same as `quant_in_inventory`.
+    pub fn quant(&self, id: I::Id) -> u128 {
+        self.read(|slots| quant_in_inventory(slots, id))
+    }
+
+    /// Same as `inventory_contains_item_type`.
+    pub fn contains_item_type(&self, id: I::Id) -> bool {
+        self.read(|slots| inventory_contains_item_type(slots, id))
+    }
+
+    /// Same as `empty_quant_in_inventory`.
+    pub fn empty_quant(&self) -> usize {
+        self.read(|slots| empty_quant_in_inventory(slots))
+    }
+
+    /// Same as `can_add_item_to_inventory`.
+    ///
+    /// Another thread may change the inventory right after this returns, so use
+    /// `SharedInventory::write` if the answer needs to hold while adding.
+    pub fn can_add_item(&self, other: II) -> bool {
+        self.read(|slots| can_add_item_to_inventory(slots, other))
+    }
+
+    /// Moves items from this inventory into another one, as a single change to both.
+    ///
+    /// Only moves as many items as this inventory has and the other one has room for.
+    /// Instances that may not be used for `Action::Move`, like bound items, stay where they are.
+    /// Returns the items that were not moved, like `remove_from_inventory`. Moving items into
+    /// the same inventory moves nothing, so all of them are returned.
+    ///
+    /// Both inventories are locked in the same order no matter which way items move,
+    /// so moving items back and forth from different threads can not deadlock.
+    ///
+    /// ```
+    /// # use game_inventory::shared_inventory::SharedInventory;
+    /// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH, TORCH_INST, SWORD_INST};
+    /// # use game_inventory::traits::{Item, ItemInstance, Slot};
+    /// # use std::sync::Arc;
+    /// let chest = SharedInventory::new(vec![DefaultSlot::new(TORCH_INST.clone())]);
+    /// let backpack = SharedInventory::new(vec![DefaultSlot::new(SWORD_INST.clone()), DefaultSlot::new(None)]);
+    /// let left = chest.move_to(&backpack, DefaultItemInstance::new(Arc::new(TORCH.clone()), 30));
+    /// assert_eq!(left.unwrap().quant(), 7);
+    /// assert_eq!(chest.quant(TORCH.id()), 0);
+    /// assert_eq!(backpack.quant(TORCH.id()), 23);
+    /// ```
+    ///
+    /// # Panics
+    /// If either inventory is poisoned.
+    pub fn move_to(&self, other: &SharedInventory<I, II, S>, item: II) -> Option<II> {
+        if Arc::ptr_eq(&self.slots, &other.slots) {
+            return Some(item);
+        }
+        let (mut from, mut to) = if Arc::as_ptr(&self.slots) < Arc::as_ptr(&other.slots) {
+            let from = self.write_guard();
+            (from, other.write_guard())
+        } else {
+            let to = other.write_guard();
+            (self.write_guard(), to)
+        };
+        if item.item().stackable() {
+            move_stack(&mut from, &mut to, item)
+        } else {
+            move_unstackable(&mut from, &mut to, item)
+        }
+    }
+
+    fn read_guard(&self) -> RwLockReadGuard<'_, Vec<S>> {
+        self.slots
+            .read()
+            .expect("a thread panicked while writing to the inventory")
+    }
+
+    fn write_guard(&self) -> RwLockWriteGuard<'_, Vec<S>> {
+        self.slots
+            .write()
+            .expect("a thread panicked while writing to the inventory")
+    }
+}
+
+fn move_stack<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>>(
+    from: &mut [S],
+    to: &mut [S],
+    item: II,
+) -> Option<II> {
+    let id = item.item().id();
+    let mut left = item.quant();
+    for slot in from.iter_mut() {
+        if left == I::Quant::ZERO {
+            break;
+        }
+        let s = match slot.item_instance() {
+            Some(s) if s.item().id() == id && check(&s, Action::Move).is_ok() => s,
+            _ => continue,
+        };
+        let moving = available_quant_in_slot(slot).min(left);
+        if moving == I::Quant::ZERO {
+            continue;
+        }
+        // Only takes what the other inventory accepted, so nothing has to be put back.
+        let not_moved = match add_to_inventory(to, s.with_quant(moving)) {
+            Some(not_moved) => not_moved.quant(),
+            None => I::Quant::ZERO,
+        };
+        let moved = moving.saturating_sub(not_moved);
+        if moved == I::Quant::ZERO {
+            continue;
+        }
+        take_from_slot(moved, slot, s);
+        left = left.saturating_sub(moved);
+    }
+    if left == I::Quant::ZERO {
+        return None;
+    }
+    Some(item.with_quant(left))
+}
+
+/// Moves the instances themselves, since every unstackable item needs its own slot.
+fn move_unstackable<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>>(
+    from: &mut [S],
+    to: &mut [S],
+    item: II,
+) -> Option<II> {
+    let id = item.item().id();
+    let mut left = item.quant();
+    while left > I::Quant::ZERO {
+        let index = match from.iter().position(|slot| match slot.item_instance() {
+            Some(ii) => {
+                ii.item().id() == id
+                    && available_quant_in_slot(slot) > I::Quant::ZERO
+                    && check(&ii, Action::Move).is_ok()
+            }
+            None => false,
+        }) {
+            Some(index) => index,
+            None => break,
+        };
+        let free = match to
+            .iter()
+            .position(|slot| takes_unstackable(slot, &item.item()))
+        {
+            Some(free) => free,
+            None => break,
+        };
+        to[free].set_item_instance(&from[index].item_instance());
+        from[index].set_item_instance(&None);
+        left = left.saturating_sub(I::Quant::ONE);
+    }
+    if left == I::Quant::ZERO {
+        return None;
+    }
+    Some(item.with_quant(left))
+}
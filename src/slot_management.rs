@@ -2,19 +2,84 @@
 //!
 //! All methods, if they edit the item values, try to transfer
 //! the items from `items.0` to `items.1`.
-use crate::traits::{Item, ItemInstance};
-use std::{error::Error, fmt::Display};
+use crate::traits::{Item, ItemInstance, Quantity};
+use core::{error::Error, fmt::Display};
 
-#[derive(Debug)]
-struct SlotErr(String);
+/// Why a slot management function could not be applied to the items.
+///
+/// Does not allocate, so it can be used without the standard library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotError {
+    /// Both items need to be `Some`.
+    MissingItems,
+    /// `items.0` needs to be `Some`.
+    MissingSource,
+    /// `items.0` needs to be stackable.
+    Unstackable,
+    /// The items need to be the same item.
+    DifferentItems,
+    /// One of the stacks is already full, or `items.1` is at its stack limit.
+    FullStack,
+    /// `items.0` only has a single item, so it can not be split.
+    SingleItem,
+}
 
-impl Display for SlotErr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+impl Display for SlotError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            SlotError::MissingItems => "Both items need to be Some for this operation to work.",
+            SlotError::MissingSource => "items.0 must be Some for this operation to work.",
+            SlotError::Unstackable => "items.0 must be stackable for this operation to work.",
+            SlotError::DifferentItems => "Both items must be the same for this operation to work.",
+            SlotError::FullStack => {
+                "Cannot combine stacks when the stack amount is reached on an item."
+            }
+            SlotError::SingleItem => {
+                "items.0 has 1 item in its stack. This cannot be split in two."
+            }
+        })
     }
 }
 
-impl Error for SlotErr {}
+impl Error for SlotError {}
+
+/// A stack limit for a slot, relative to the item's own `max_quant`.
+///
+/// Unstackable items count as having a `max_quant` of one.
+///
+/// ```
+/// # use game_inventory::helpers::StackLimit;
+/// # use game_inventory::samples::TORCH;
+/// assert_eq!(StackLimit::Item.of(&TORCH), 100);
+/// assert_eq!(StackLimit::Cap(30).of(&TORCH), 30);
+/// assert_eq!(StackLimit::Cap(300).of(&TORCH), 100);
+/// assert_eq!(StackLimit::Override(300).of(&TORCH), 300);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StackLimit<Q> {
+    /// The item's own `max_quant`.
+    #[default]
+    Item,
+    /// The item's own `max_quant`, but never more than this.
+    Cap(Q),
+    /// This, no matter what the item's `max_quant` is.
+    Override(Q),
+}
+
+impl<Q: Quantity> StackLimit<Q> {
+    /// The stack limit for the given item.
+    pub fn of<I: Item<Quant = Q>>(&self, item: &I) -> Q {
+        let max = match item.stackable() {
+            true => item.max_quant(),
+            false => Q::ONE,
+        };
+        match self {
+            StackLimit::Item => max,
+            StackLimit::Cap(cap) => max.min(*cap),
+            StackLimit::Override(max) => *max,
+        }
+    }
+}
 
 /// Two item instances to represent slot to slot interaction.
 ///
@@ -23,15 +88,15 @@ pub type Items<II> = (Option<II>, Option<II>);
 /// `Items<II>` Wrapped in `Result`, so that if the slot management
 /// function fails you can choose your fallback option.
 ///
-/// The `&'a str` is for the error message.
+/// The `SlotError` says why the function failed.
 ///
 /// For example, you cannot combine two stacks if they are different items
 /// Therefore you need to choose a fallback for what happens next.
 /// Personally, swapping them has worked for me in my games.
-pub type ItemsRes<II> = Result<Items<II>, (Box<dyn std::error::Error>, Items<II>)>;
+pub type ItemsRes<II> = Result<Items<II>, (SlotError, Items<II>)>;
 
 /// Returns the inverse of the two inputs, specifically `(items.1, items.0)`.
-pub fn swap<'a, Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+pub fn swap<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
     items: (Option<II>, Option<II>),
 ) -> (Option<II>, Option<II>) {
     (items.1, items.0)
@@ -49,7 +114,7 @@ pub fn swap<'a, Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
 /// assert_eq!(items.0.as_ref().unwrap().item().id(), unwrapped.1.as_ref().unwrap().item().id());
 /// assert_eq!(items.0.as_ref().unwrap().quant(), unwrapped.1.unwrap().quant());
 /// ```
-pub fn swap_if_err<'a, Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+pub fn swap_if_err<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
     items: ItemsRes<II>,
 ) -> Items<II> {
     match items {
@@ -70,7 +135,7 @@ pub fn swap_if_err<'a, Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
 /// assert_eq!(items.0.unwrap().quant(), unwrapped.0.unwrap().quant());
 /// assert_eq!(items.1.is_none(), unwrapped.1.is_none());
 /// ```
-pub fn unwrap_items_res<'a, Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+pub fn unwrap_items_res<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
     items: ItemsRes<II>,
 ) -> Items<II> {
     match items {
@@ -117,50 +182,75 @@ pub fn unwrap_items_res<'a, Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
 /// ```
 pub fn combine_stack<'a, Id: Eq, I: Item<Id = Id> + 'a, II: ItemInstance<I>>(
     items: Items<II>,
+) -> ItemsRes<II> {
+    combine(items, None)
+}
+
+/// Same as `combine_stack`, but `items.1` can hold at most `max` items instead of the item's `max_quant`.
+///
+/// Use it for slots with their own stack limit, passing in `Slot::stack_limit`.
+/// Unlike `combine_stack`, `items.0` may hold more than `max` items, as it may come from a slot with a bigger limit.
+///
+/// ```
+/// # use game_inventory::samples::{TORCH_INST, TORCH_FULL_STACK_INST};
+/// # use game_inventory::helpers::combine_stack_limited;
+/// # use game_inventory::traits::ItemInstance;
+/// let res = combine_stack_limited((TORCH_INST.clone(), TORCH_INST.clone()), 30).ok().unwrap();
+/// assert_eq!(res.0.unwrap().quant(), 16);
+/// assert_eq!(res.1.unwrap().quant(), 30);
+/// // A display case that can hold more than a normal stack.
+/// let res = combine_stack_limited((TORCH_FULL_STACK_INST.clone(), TORCH_INST.clone()), 500).ok().unwrap();
+/// assert!(res.0.is_none());
+/// assert_eq!(res.1.unwrap().quant(), 123);
+/// ```
+pub fn combine_stack_limited<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+    items: Items<II>,
+    max: I::Quant,
+) -> ItemsRes<II> {
+    combine(items, Some(max))
+}
+
+fn combine<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+    items: Items<II>,
+    max: Option<I::Quant>,
 ) -> ItemsRes<II> {
     let (c, o) = match &items {
         (Some(c), Some(o)) => (c, o),
-        _ => {
-            return Err((
-                Box::new(SlotErr(
-                    "Both items need to be Some for this operation to work.".to_owned(),
-                )),
-                items,
-            ))
-        }
+        _ => return Err((SlotError::MissingItems, items)),
     };
     if !c.item().stackable() {
-        return Err((
-            Box::new(SlotErr("Cannot combine unstackable items.".to_owned())),
-            items,
-        ));
+        return Err((SlotError::Unstackable, items));
     }
-    if c.item().id() != o.item().id() {
-        return Err((
-            Box::new(SlotErr(
-                "Both items must be the same for this operation to work.".to_owned(),
-            )),
-            items,
-        ));
+    if c.item().id() != o.item().id() || !c.stacks_with(o) {
+        return Err((SlotError::DifferentItems, items));
     }
-    let stack_size = c.item().max_quant();
-    if c.quant() >= stack_size || o.quant() >= stack_size {
-        return Err((
-            Box::new(SlotErr(
-                "Cannot combine stacks when the stack amount is reached on an item.".to_owned(),
-            )),
-            items,
-        ));
+    let stack_size = match max {
+        Some(max) => max,
+        None => {
+            let max = c.item().max_quant();
+            if c.quant() >= max {
+                return Err((SlotError::FullStack, items));
+            }
+            max
+        }
+    };
+    if o.quant() >= stack_size {
+        return Err((SlotError::FullStack, items));
     }
-    let combined = c.quant() + o.quant();
-    if combined < stack_size {
-        return Ok((None, Some(II::new(c.item(), combined))));
+    // `o` is below the stack size, so this can not underflow, and neither can
+    // anything that fits into the space overflow.
+    let space = stack_size.saturating_sub(o.quant());
+    if c.quant() <= space {
+        return Ok((
+            None,
+            Some(c.with_quant(o.quant().saturating_add(c.quant()))),
+        ));
     }
-    let left_over = combined - stack_size;
-    return Ok((
-        Some(II::new(c.item(), left_over)),
-        Some(II::new(c.item(), stack_size)),
-    ));
+    let left_over = c.quant().saturating_sub(space);
+    Ok((
+        Some(c.with_quant(left_over)),
+        Some(c.with_quant(stack_size)),
+    ))
 }
 
 /// Splits a stack of items into two. Tries to split `items.0` and put the second half into `items.1`
@@ -200,57 +290,62 @@ pub fn combine_stack<'a, Id: Eq, I: Item<Id = Id> + 'a, II: ItemInstance<I>>(
 /// ```
 pub fn half_stack_split<'a, Id: Eq, I: Item<Id = Id> + 'a, II: ItemInstance<I>>(
     items: Items<II>,
+) -> ItemsRes<II> {
+    let max = match &items.0 {
+        Some(c) => c.item().max_quant(),
+        None => I::Quant::ZERO,
+    };
+    half_stack_split_limited(items, max)
+}
+
+/// Same as `half_stack_split`, but `items.1` can hold at most `max` items instead of the item's `max_quant`.
+///
+/// If half of `items.0` does not fit, only as many items as fit are moved.
+///
+/// ```
+/// # use game_inventory::samples::{TORCH, DefaultItemInstance};
+/// # use game_inventory::helpers::half_stack_split_limited;
+/// # use game_inventory::traits::{Item, ItemInstance};
+/// # use std::sync::Arc;
+/// let torches = |quant| Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant));
+/// let res = half_stack_split_limited((torches(40), torches(12)), 16).ok().unwrap();
+/// assert_eq!(res.0.unwrap().quant(), 36);
+/// assert_eq!(res.1.unwrap().quant(), 16);
+/// assert!(half_stack_split_limited((torches(40), torches(16)), 16).is_err());
+/// ```
+pub fn half_stack_split_limited<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+    items: Items<II>,
+    max: I::Quant,
 ) -> ItemsRes<II> {
     let c = match &items.0 {
         Some(c) => c,
-        None => {
-            return Err((
-                Box::new(SlotErr(
-                    "items.0 must be Some for this operation to work.".to_owned(),
-                )),
-                items,
-            ))
-        }
+        None => return Err((SlotError::MissingSource, items)),
     };
     if !c.item().stackable() {
-        return Err((
-            Box::new(SlotErr(
-                "items.0 must be stackable for this operation to work".to_owned(),
-            )),
-            items,
-        ));
+        return Err((SlotError::Unstackable, items));
     }
     if match &items.1 {
-        Some(o) => c.item().id() != o.item().id(),
+        Some(o) => c.item().id() != o.item().id() || !c.stacks_with(o),
         None => false,
     } {
-        return Err((
-            Box::new(SlotErr(
-                "Both items must be the same for this operation to work.".to_owned(),
-            )),
-            items,
-        ));
+        return Err((SlotError::DifferentItems, items));
     }
-    if c.quant() < 2 {
-        return Err((
-            Box::new(SlotErr(
-                "items.0 has 1 item in its stack. This cannot be split in two.".to_owned(),
-            )),
-            items,
-        ));
+    if c.quant() <= I::Quant::ONE {
+        return Err((SlotError::SingleItem, items));
     }
-    let other_quant = match items.1 {
+    let other_quant = match &items.1 {
         Some(o) => o.quant(),
-        None => 0,
+        None => I::Quant::ZERO,
     };
-    let half_stack = c.quant() / 2;
-    return Ok((
-        Some(II::new(c.item(), half_stack)),
-        Some(II::new(
-            c.item(),
-            other_quant + half_stack + (c.quant() % 2),
-        )),
-    ));
+    if other_quant >= max {
+        return Err((SlotError::FullStack, items));
+    }
+    // `other_quant` is below `max`, so neither of these can overflow.
+    let moved = c.quant().halve().1.min(max.saturating_sub(other_quant));
+    Ok((
+        Some(c.with_quant(c.quant().saturating_sub(moved))),
+        Some(c.with_quant(other_quant.saturating_add(moved))),
+    ))
 }
 
 /// Removes a single item from a stack. Tries to take a single item from `items.0` and put it into `items.1`.
@@ -325,59 +420,63 @@ pub fn half_stack_split<'a, Id: Eq, I: Item<Id = Id> + 'a, II: ItemInstance<I>>(
 /// ```
 pub fn remove_from_stack<'a, Id: Eq, I: Item<Id = Id> + 'a, II: ItemInstance<I>>(
     items: Items<II>,
+) -> ItemsRes<II> {
+    let max = match &items.1 {
+        Some(o) => o.item().max_quant(),
+        None => I::Quant::MAX,
+    };
+    remove_from_stack_limited(items, max)
+}
+
+/// Same as `remove_from_stack`, but `items.1` can hold at most `max` items instead of the item's `max_quant`.
+///
+/// ```
+/// # use game_inventory::samples::{TORCH, DefaultItemInstance};
+/// # use game_inventory::helpers::remove_from_stack_limited;
+/// # use game_inventory::traits::{Item, ItemInstance};
+/// # use std::sync::Arc;
+/// let torches = |quant| Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant));
+/// assert!(remove_from_stack_limited((torches(40), torches(11)), 12).is_ok());
+/// assert!(remove_from_stack_limited((torches(40), torches(12)), 12).is_err());
+/// ```
+pub fn remove_from_stack_limited<Id: Eq, I: Item<Id = Id>, II: ItemInstance<I>>(
+    items: Items<II>,
+    max: I::Quant,
 ) -> ItemsRes<II> {
     let c = match &items.0 {
         Some(c) => c,
-        None => {
-            return Err((
-                Box::new(SlotErr(
-                    "items.0 must be Some for this operation to work.".to_owned(),
-                )),
-                items,
-            ))
-        }
+        None => return Err((SlotError::MissingSource, items)),
     };
     if !c.item().stackable() {
-        return Err((
-            Box::new(SlotErr(
-                "items.0 must be stackable for this operation to work".to_owned(),
-            )),
-            items,
-        ));
+        return Err((SlotError::Unstackable, items));
     }
     let o = match &items.1 {
         Some(o) => o,
         None => {
-            if c.quant() < 2 {
-                return Ok((None, Some(II::new(c.item(), 1))));
+            if c.quant() <= I::Quant::ONE {
+                return Ok((None, Some(c.with_quant(I::Quant::ONE))));
             }
             return Ok((
-                Some(II::new(c.item(), c.quant() - 1)),
-                Some(II::new(c.item(), 1)),
+                Some(c.with_quant(c.quant().saturating_sub(I::Quant::ONE))),
+                Some(c.with_quant(I::Quant::ONE)),
             ));
         }
     };
-    if o.item().id() != c.item().id() {
-        return Err((
-            Box::new(SlotErr(
-                "Both items must be the same for this operation to work.".to_owned(),
-            )),
-            items,
-        ));
+    if o.item().id() != c.item().id() || !c.stacks_with(o) {
+        return Err((SlotError::DifferentItems, items));
     }
-    if o.quant() >= o.item().max_quant() {
-        return Err((
-            Box::new(SlotErr(
-                "Cannot add to other as it is the max quantity".to_owned(),
-            )),
-            items,
-        ));
+    if o.quant() >= max {
+        return Err((SlotError::FullStack, items));
     }
-    if c.quant() < 2 {
-        return Ok((None, Some(II::new(o.item(), o.quant() + 1))));
+    // `o` is below its max quantity, so adding one to it can not overflow.
+    if c.quant() <= I::Quant::ONE {
+        return Ok((
+            None,
+            Some(o.with_quant(o.quant().saturating_add(I::Quant::ONE))),
+        ));
     }
-    return Ok((
-        Some(II::new(c.item(), c.quant() - 1)),
-        Some(II::new(o.item(), o.quant() + 1)),
-    ));
+    Ok((
+        Some(c.with_quant(c.quant().saturating_sub(I::Quant::ONE))),
+        Some(o.with_quant(o.quant().saturating_add(I::Quant::ONE))),
+    ))
 }
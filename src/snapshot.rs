@@ -0,0 +1,254 @@
+//! Snapshots of an inventory, and the differences between them.
+//!
+//! Taking a `Snapshot` only clones the item instances in the inventory, so it is cheap
+//! enough to do before any operation you want to inspect afterwards. Diffing two
+//! snapshots gives both the slots that changed and how much of each item was gained or lost.
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// A copy of the contents of every slot in an inventory at one point in time.
+///
+/// Derefs to `[Option<II>]`, so it can be passed anywhere a list of slot contents is expected,
+/// like `Journal::replay`.
+pub struct Snapshot<I: Item, II: ItemInstance<I>> {
+    slots: Vec<Option<II>>,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone> Clone for Snapshot<I, II> {
+    fn clone(&self) -> Self {
+        Snapshot {
+            slots: self.slots.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Debug> Debug for Snapshot<I, II> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> Deref for Snapshot<I, II> {
+    type Target = [Option<II>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.slots
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> From<Vec<Option<II>>> for Snapshot<I, II> {
+    fn from(slots: Vec<Option<II>>) -> Self {
+        Snapshot {
+            slots,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> Snapshot<I, II> {
+    /// Copies the contents of every slot in an inventory.
+    pub fn take<S: Slot<I, II>>(inventory: &[S]) -> Self {
+        inventory
+            .iter()
+            .map(|slot| slot.item_instance())
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// Works out what changed between this snapshot and a later one.
+    ///
+    /// A slot counts as changed if its item or its quantity changed.
+    /// If the snapshots have a different amount of slots, the missing slots count as empty.
+    ///
+    /// ```
+    /// # use game_inventory::snapshot::{Snapshot, QuantDelta};
+    /// # use game_inventory::samples::{DefaultSlot, TORCH, SWORD, JUNK, TORCH_INST, SWORD_INST, JUNK_INST};
+    /// # use game_inventory::traits::{Item, Slot};
+    /// # use game_inventory::helpers::add_to_inventory;
+    /// let mut inventory = vec![
+    ///     DefaultSlot::new(TORCH_INST.clone()),
+    ///     DefaultSlot::new(JUNK_INST.clone()),
+    ///     DefaultSlot::new(None),
+    /// ];
+    /// let before = Snapshot::take(&inventory);
+    /// add_to_inventory(&mut inventory, TORCH_INST.clone().unwrap());
+    /// add_to_inventory(&mut inventory, SWORD_INST.clone().unwrap());
+    /// let diff = before.diff(&Snapshot::take(&inventory));
+    ///
+    /// assert_eq!(diff.slots.iter().map(|change| change.slot).collect::<Vec<_>>(), vec![0, 2]);
+    /// assert_eq!(diff.quant_delta(TORCH.id()), QuantDelta { before: 23, after: 46 });
+    /// assert_eq!(diff.quant_delta(SWORD.id()).gained(), 1);
+    /// assert_eq!(diff.quant_delta(JUNK.id()).gained(), 0);
+    /// ```
+    pub fn diff(&self, later: &Snapshot<I, II>) -> InventoryDiff<I, II>
+    where
+        II: Clone,
+    {
+        let mut diff = InventoryDiff {
+            slots: Vec::new(),
+            items: Vec::new(),
+        };
+        for slot in 0..self.slots.len().max(later.slots.len()) {
+            let before = self.slots.get(slot).cloned().flatten();
+            let after = later.slots.get(slot).cloned().flatten();
+            if same_contents(&before, &after) {
+                continue;
+            }
+            if let Some(before) = &before {
+                diff.track(before.item().id());
+            }
+            if let Some(after) = &after {
+                diff.track(after.item().id());
+            }
+            diff.slots.push(SlotChange {
+                slot,
+                before,
+                after,
+            });
+        }
+        for (id, delta) in diff.items.iter_mut() {
+            delta.before = total_quant(&self.slots, id);
+            delta.after = total_quant(&later.slots, id);
+        }
+        diff.items.retain(|(_, delta)| delta.before != delta.after);
+        diff
+    }
+}
+
+/// The contents of a single slot before and after a change.
+#[derive(Debug, Clone)]
+pub struct SlotChange<II> {
+    /// The index of the slot that changed.
+    pub slot: usize,
+    /// The contents of the slot before the change.
+    pub before: Option<II>,
+    /// The contents of the slot after the change.
+    pub after: Option<II>,
+}
+
+/// The total quantity of an item in an inventory before and after a change.
+///
+/// Unstackable items count as one item each, and like in `quant_in_inventory`,
+/// the totals are `u128`s so they are not limited to the quantity type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QuantDelta {
+    pub before: u128,
+    pub after: u128,
+}
+
+impl QuantDelta {
+    /// How many of the item were gained, or `0` if the quantity went down.
+    pub fn gained(&self) -> u128 {
+        self.after.saturating_sub(self.before)
+    }
+
+    /// How many of the item were lost, or `0` if the quantity went up.
+    pub fn lost(&self) -> u128 {
+        self.before.saturating_sub(self.after)
+    }
+}
+
+/// Everything that changed between two snapshots.
+pub struct InventoryDiff<I: Item, II: ItemInstance<I>> {
+    /// Every slot that changed, in slot order.
+    pub slots: Vec<SlotChange<II>>,
+    /// Every item whose total quantity changed, in the order they were first seen.
+    pub items: Vec<(I::Id, QuantDelta)>,
+}
+
+impl<I: Item, II: ItemInstance<I> + Debug> Debug for InventoryDiff<I, II>
+where
+    I::Id: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InventoryDiff")
+            .field("slots", &self.slots)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> InventoryDiff<I, II> {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// How the total quantity of an item changed. Items that did not change are reported
+    /// with both quantities as `0`.
+    pub fn quant_delta(&self, id: I::Id) -> QuantDelta {
+        self.items
+            .iter()
+            .find(|(other, _)| *other == id)
+            .map(|(_, delta)| *delta)
+            .unwrap_or_default()
+    }
+
+    /// Every item whose total quantity went up, with how much it went up by.
+    ///
+    /// ```
+    /// # use game_inventory::snapshot::Snapshot;
+    /// # use game_inventory::samples::{DefaultSlot, DefaultItemInstance, TORCH, JUNK, TORCH_INST, JUNK_INST};
+    /// # use game_inventory::traits::{Item, ItemInstance, Slot};
+    /// # use game_inventory::helpers::{add_to_inventory, remove_from_inventory};
+    /// # use std::sync::Arc;
+    /// let mut inventory = vec![DefaultSlot::new(JUNK_INST.clone()), DefaultSlot::new(None)];
+    /// let before = Snapshot::take(&inventory);
+    /// // Things that happened during a cutscene.
+    /// add_to_inventory(&mut inventory, TORCH_INST.clone().unwrap());
+    /// remove_from_inventory(&mut inventory, DefaultItemInstance::new(Arc::new(JUNK.clone()), 10));
+    ///
+    /// let diff = before.diff(&Snapshot::take(&inventory));
+    /// assert_eq!(diff.gained().collect::<Vec<_>>(), vec![(&TORCH.id(), 23)]);
+    /// assert_eq!(diff.lost().collect::<Vec<_>>(), vec![(&JUNK.id(), 10)]);
+    /// ```
+    pub fn gained(&self) -> impl Iterator<Item = (&I::Id, u128)> {
+        self.items
+            .iter()
+            .filter(|(_, delta)| delta.gained() > 0)
+            .map(|(id, delta)| (id, delta.gained()))
+    }
+
+    /// Every item whose total quantity went down, with how much it went down by.
+    pub fn lost(&self) -> impl Iterator<Item = (&I::Id, u128)> {
+        self.items
+            .iter()
+            .filter(|(_, delta)| delta.lost() > 0)
+            .map(|(id, delta)| (id, delta.lost()))
+    }
+
+    fn track(&mut self, id: I::Id) {
+        if !self.items.iter().any(|(other, _)| *other == id) {
+            self.items.push((id, QuantDelta::default()));
+        }
+    }
+}
+
+fn same_contents<I: Item, II: ItemInstance<I>>(a: &Option<II>, b: &Option<II>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.item().id() == b.item().id() && a.quant() == b.quant(),
+        _ => false,
+    }
+}
+
+fn total_quant<I: Item, II: ItemInstance<I>>(slots: &[Option<II>], id: &I::Id) -> u128 {
+    slots
+        .iter()
+        .flatten()
+        .filter(|ii| ii.item().id() == *id)
+        .fold(0, |quant, ii| {
+            if ii.item().stackable() {
+                quant + ii.quant().to_u128()
+            } else {
+                quant + 1
+            }
+        })
+}
@@ -0,0 +1,175 @@
+//! Sockets in items that gems, runes and the like are inserted into.
+//!
+//! An item instance with sockets implements `Socketed`, and holds the instances in its sockets
+//! itself. `insert_gem` takes a single gem off a stack in an inventory with `remove_from_stack`,
+//! and `remove_gem` either puts it back with `add_to_inventory` or destroys it, depending on the
+//! `Removal` rule.
+//!
+//! For `Equippable` items, `socketed_modifiers` adds up the modifiers of an item and its gems.
+//!
+//! ```
+//! # use game_inventory::equipment::Modifier;
+//! # use game_inventory::sockets::{insert_gem, remove_gem, socketed_modifiers, Removal};
+//! # use game_inventory::samples::{DefaultSlot, Gear, SocketedItemInstance};
+//! # use game_inventory::traits::{ItemInstance, Slot};
+//! # use std::sync::Arc;
+//! let ruby = Gear {
+//!     name: "ruby",
+//!     max_quantity: 10,
+//!     equip_slot: None,
+//!     two_handed: false,
+//!     set: None,
+//!     modifiers: vec![Modifier::Add("fire", 3.0)],
+//! };
+//! let mut inventory = vec![
+//!     DefaultSlot::new(Some(SocketedItemInstance::new(Arc::new(ruby.clone()), 5))),
+//!     DefaultSlot::new(None),
+//! ];
+//! let sword = Gear { name: "sword", max_quantity: 1, modifiers: Vec::new(), ..ruby.clone() };
+//! let mut sword = SocketedItemInstance::with_sockets(Arc::new(sword), 2);
+//!
+//! insert_gem(&mut sword, 0, &mut inventory, 0).unwrap();
+//! assert_eq!(inventory[0].item_instance().unwrap().quant(), 4);
+//! assert_eq!(socketed_modifiers(&sword), vec![Modifier::Add("fire", 3.0)]);
+//!
+//! remove_gem(&mut sword, 0, &mut inventory, Removal::Return).unwrap();
+//! assert_eq!(inventory[0].item_instance().unwrap().quant(), 5);
+//! assert!(socketed_modifiers(&sword).is_empty());
+//! ```
+use crate::equipment::{Equippable, Modifier};
+use crate::inventory_management::{
+    add_to_inventory, available_quant_in_slot, can_add_item_to_inventory,
+};
+use crate::slot_management::remove_from_stack;
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::Display;
+
+/// An item instance with sockets, that hold instances of other items.
+pub trait Socketed<I: Item>: ItemInstance<I> + Sized {
+    /// The contents of every socket, in order.
+    fn sockets(&self) -> &[Option<Self>];
+    /// Sets the contents of a socket. `socket` is always below the number of sockets.
+    fn set_socket(&mut self, socket: usize, gem: Option<Self>);
+    /// Whether a socket accepts a gem, like only runes in rune sockets.
+    ///
+    /// Accepts everything unless this is overridden.
+    fn accepts(&self, _socket: usize, _gem: &Self) -> bool {
+        true
+    }
+}
+
+/// What happens to a gem taken out of its socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Removal {
+    /// The gem goes back into the inventory.
+    Return,
+    /// The gem is destroyed.
+    Destroy,
+}
+
+/// Why a gem could not be inserted or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketError {
+    /// The item has no socket at that index.
+    NoSuchSocket,
+    /// There is already a gem in the socket.
+    Occupied,
+    /// There is no gem in the socket.
+    EmptySocket,
+    /// The inventory slot is empty, locked, or all of its items are reserved.
+    NoGem,
+    /// The socket does not accept the gem.
+    NotAccepted,
+    /// The inventory has no room for the returned gem.
+    NoSpace,
+}
+
+impl Display for SocketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            SocketError::NoSuchSocket => "The item has no socket at that index.",
+            SocketError::Occupied => "There is already a gem in that socket.",
+            SocketError::EmptySocket => "There is no gem in that socket.",
+            SocketError::NoGem => "There is no gem in that slot to insert.",
+            SocketError::NotAccepted => "The socket does not accept that gem.",
+            SocketError::NoSpace => "There is no room in the inventory for the gem.",
+        })
+    }
+}
+
+impl Error for SocketError {}
+
+/// Takes a single gem out of an inventory slot and inserts it into an item's socket.
+///
+/// Stackable gems are taken off their stack with `remove_from_stack`. Nothing changes on failure.
+pub fn insert_gem<I: Item, II: Socketed<I>, S: Slot<I, II>>(
+    item: &mut II,
+    socket: usize,
+    inventory: &mut [S],
+    gem_slot: usize,
+) -> Result<(), SocketError> {
+    match item.sockets().get(socket) {
+        Some(None) => {}
+        Some(Some(_)) => return Err(SocketError::Occupied),
+        None => return Err(SocketError::NoSuchSocket),
+    }
+    let slot = match inventory.get_mut(gem_slot) {
+        Some(slot) => slot,
+        None => return Err(SocketError::NoGem),
+    };
+    let stack = match slot.item_instance() {
+        Some(stack) if available_quant_in_slot(slot) > I::Quant::ZERO => stack,
+        _ => return Err(SocketError::NoGem),
+    };
+    if !item.accepts(socket, &stack) {
+        return Err(SocketError::NotAccepted);
+    }
+    let (rest, gem) = if stack.item().stackable() {
+        match remove_from_stack((Some(stack), None)) {
+            Ok(items) => items,
+            Err(_) => return Err(SocketError::NoGem),
+        }
+    } else {
+        (None, Some(stack))
+    };
+    slot.set_item_instance(&rest);
+    item.set_socket(socket, gem);
+    Ok(())
+}
+
+/// Takes the gem out of an item's socket, and adds it to an inventory or destroys it.
+///
+/// Nothing changes if the gem should be returned and does not fit into the inventory.
+pub fn remove_gem<I: Item, II: Socketed<I> + Clone, S: Slot<I, II>>(
+    item: &mut II,
+    socket: usize,
+    inventory: &mut [S],
+    removal: Removal,
+) -> Result<(), SocketError> {
+    let gem = match item.sockets().get(socket) {
+        Some(Some(gem)) => gem.clone(),
+        Some(None) => return Err(SocketError::EmptySocket),
+        None => return Err(SocketError::NoSuchSocket),
+    };
+    if removal == Removal::Return {
+        if !can_add_item_to_inventory(inventory, gem.clone()) {
+            return Err(SocketError::NoSpace);
+        }
+        add_to_inventory(inventory, gem);
+    }
+    item.set_socket(socket, None);
+    Ok(())
+}
+
+/// The modifiers of an item, followed by the modifiers of the gems in its sockets.
+///
+/// Gems with sockets of their own add the modifiers of their gems too.
+pub fn socketed_modifiers<I: Equippable, II: Socketed<I>>(item: &II) -> Vec<Modifier<I::Stat>> {
+    let mut modifiers = item.item().modifiers().to_vec();
+    for gem in item.sockets().iter().flatten() {
+        modifiers.extend(socketed_modifiers(gem));
+    }
+    modifiers
+}
@@ -0,0 +1,365 @@
+//! Trading items between two players.
+//!
+//! A `TradeSession` holds what each side offers, without taking it out of their inventories yet.
+//! Offers point at the slot their items are in, so a side can only offer what it really holds.
+//! Both sides lock their offer once they are happy with it, then both confirm. Changing an offer
+//! takes back every confirmation, so nobody can swap an item out right before the other side accepts.
+//!
+//! `TradeSession::execute` checks that both sides still have what they offered and have room
+//! for what they get, and only then moves the items. It either moves everything or nothing,
+//! and each side gets the instances that were taken out of the other side's slots.
+use crate::inventory_management::{
+    add_to_inventory, available_quant_in_slot, can_add_item_to_inventory, single_quant,
+    take_from_slot, Preview,
+};
+use crate::restrictions::{check, Action, ItemFlags};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Debug, Display};
+use core::marker::PhantomData;
+
+/// One of the two sides of a trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    /// The side on the other end of the trade.
+    pub fn other(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+/// Why a trade action was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeError {
+    /// The side's offer is locked, so it can not be changed.
+    Locked,
+    /// Both offers need to be locked before confirming.
+    NotLocked,
+    /// Both sides need to confirm before the trade happens.
+    NotConfirmed,
+    /// The side does not have the items it offers in the slot it offers them from.
+    MissingItems(Side),
+    /// The side has no room for the items it would get.
+    NoSpace(Side),
+    /// There is no offer at that index.
+    NoSuchOffer,
+    /// The side offered an item that can not be traded, because of these flags.
+    Restricted(Side, ItemFlags),
+}
+
+impl Display for TradeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TradeError::Locked => f.write_str("The offer is locked and can not be changed."),
+            TradeError::NotLocked => f.write_str("Both offers must be locked before confirming."),
+            TradeError::NotConfirmed => f.write_str("Both sides must confirm the trade."),
+            TradeError::MissingItems(side) => {
+                write!(f, "The {side:?} side does not have the items it offers.")
+            }
+            TradeError::NoSpace(side) => {
+                write!(
+                    f,
+                    "The {side:?} side has no room for the items it would get."
+                )
+            }
+            TradeError::NoSuchOffer => f.write_str("There is no offer at that index."),
+            TradeError::Restricted(side, _) => {
+                write!(
+                    f,
+                    "The {side:?} side offered an item that can not be traded."
+                )
+            }
+        }
+    }
+}
+
+impl Error for TradeError {}
+
+/// Items a side offers, and the slot of its inventory they are taken out of.
+#[derive(Debug, Clone)]
+pub struct Offer<II> {
+    /// The index of the slot in the side's inventory.
+    pub slot: usize,
+    /// What the slot held when the offer was made, with the offered quantity.
+    pub item_instance: II,
+}
+
+/// The offers, locks and confirmations of a trade between two inventories.
+///
+/// ```
+/// # use game_inventory::trade::{Side, TradeSession};
+/// # use game_inventory::samples::{DefaultSlot, TORCH, SWORD, TORCH_INST, SWORD_INST};
+/// # use game_inventory::traits::{Item, Slot};
+/// # use game_inventory::helpers::quant_in_inventory;
+/// let mut alice = vec![DefaultSlot::new(SWORD_INST.clone())];
+/// let mut bob = vec![DefaultSlot::new(TORCH_INST.clone()), DefaultSlot::new(None)];
+///
+/// let mut trade = TradeSession::new();
+/// trade.offer(Side::Left, &alice, 0, 1).unwrap();
+/// trade.offer(Side::Right, &bob, 0, 20).unwrap();
+/// trade.lock(Side::Left);
+/// trade.lock(Side::Right);
+/// trade.confirm(Side::Left).unwrap();
+/// trade.confirm(Side::Right).unwrap();
+/// trade.execute(&mut alice, &mut bob).unwrap();
+///
+/// assert_eq!(quant_in_inventory(&alice, TORCH.id()), 20);
+/// assert_eq!(quant_in_inventory(&bob, TORCH.id()), 3);
+/// assert_eq!(quant_in_inventory(&bob, SWORD.id()), 1);
+/// ```
+pub struct TradeSession<I: Item, II: ItemInstance<I>> {
+    offers: [Vec<Offer<II>>; 2],
+    locked: [bool; 2],
+    confirmed: [bool; 2],
+    phantom: PhantomData<I>,
+}
+
+impl<I: Item, II: ItemInstance<I> + Debug> Debug for TradeSession<I, II> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TradeSession")
+            .field("offers", &self.offers)
+            .field("locked", &self.locked)
+            .field("confirmed", &self.confirmed)
+            .finish()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> Default for TradeSession<I, II> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Item, II: ItemInstance<I>> TradeSession<I, II> {
+    /// Starts a trade with nothing offered.
+    pub fn new() -> Self {
+        TradeSession {
+            offers: [Vec::new(), Vec::new()],
+            locked: [false; 2],
+            confirmed: [false; 2],
+            phantom: PhantomData,
+        }
+    }
+
+    /// What a side offers.
+    pub fn offers(&self, side: Side) -> &[Offer<II>] {
+        &self.offers[side as usize]
+    }
+
+    /// Whether a side locked its offer.
+    pub fn is_locked(&self, side: Side) -> bool {
+        self.locked[side as usize]
+    }
+
+    /// Whether a side confirmed the trade.
+    pub fn is_confirmed(&self, side: Side) -> bool {
+        self.confirmed[side as usize]
+    }
+
+    /// Takes an offer back.
+    pub fn withdraw(&mut self, side: Side, index: usize) -> Result<Offer<II>, TradeError> {
+        if self.is_locked(side) {
+            return Err(TradeError::Locked);
+        }
+        if index >= self.offers[side as usize].len() {
+            return Err(TradeError::NoSuchOffer);
+        }
+        self.confirmed = [false; 2];
+        Ok(self.offers[side as usize].remove(index))
+    }
+
+    /// Locks a side's offer, so it can not change until it is unlocked.
+    pub fn lock(&mut self, side: Side) {
+        self.locked[side as usize] = true;
+    }
+
+    /// Unlocks a side's offer, taking back both confirmations.
+    pub fn unlock(&mut self, side: Side) {
+        self.locked[side as usize] = false;
+        self.confirmed = [false; 2];
+    }
+
+    /// Confirms the trade for a side. Both offers have to be locked.
+    pub fn confirm(&mut self, side: Side) -> Result<(), TradeError> {
+        if self.locked != [true; 2] {
+            return Err(TradeError::NotLocked);
+        }
+        self.confirmed[side as usize] = true;
+        Ok(())
+    }
+}
+
+impl<I: Item, II: ItemInstance<I> + Clone> TradeSession<I, II> {
+    /// Offers a quantity of the items in a slot of a side's inventory. Unstackable items are
+    /// offered whole, whatever the quantity.
+    ///
+    /// The items stay in the inventory until the trade happens, but the slot has to hold
+    /// all of them, together with everything else the side offers from it.
+    pub fn offer<S: Slot<I, II>>(
+        &mut self,
+        side: Side,
+        inventory: &[S],
+        slot: usize,
+        quant: I::Quant,
+    ) -> Result<(), TradeError> {
+        if self.is_locked(side) {
+            return Err(TradeError::Locked);
+        }
+        let held = match inventory.get(slot).and_then(|slot| slot.item_instance()) {
+            Some(held) => held,
+            None => return Err(TradeError::MissingItems(side)),
+        };
+        if let Err(restricted) = check(&held, Action::Trade) {
+            return Err(TradeError::Restricted(side, restricted.flags));
+        }
+        let item_instance = match held.item().stackable() {
+            true => held.with_quant(quant),
+            false => held,
+        };
+        self.offers[side as usize].push(Offer {
+            slot,
+            item_instance,
+        });
+        if !holds_offers(inventory, &self.offers[side as usize]) {
+            self.offers[side as usize].pop();
+            return Err(TradeError::MissingItems(side));
+        }
+        self.confirmed = [false; 2];
+        Ok(())
+    }
+
+    /// Swaps the offers between the two inventories, once both sides confirmed.
+    ///
+    /// Fails without changing anything if either side no longer has what it offered, or has no room
+    /// for what it gets, after its own offer is taken out. Confirmations are taken back on failure,
+    /// and the session starts over with no offers on success.
+    pub fn execute<L: Slot<I, II>, R: Slot<I, II>>(
+        &mut self,
+        left: &mut [L],
+        right: &mut [R],
+    ) -> Result<(), TradeError> {
+        if self.confirmed != [true; 2] {
+            return Err(TradeError::NotConfirmed);
+        }
+        let res = self.check(left, right);
+        if res.is_err() {
+            self.confirmed = [false; 2];
+            return res;
+        }
+
+        let [left_offers, right_offers] = core::mem::take(&mut self.offers);
+        let from_left = take_offers(left, &left_offers);
+        let from_right = take_offers(right, &right_offers);
+        for ii in from_right {
+            add_to_inventory(left, ii);
+        }
+        for ii in from_left {
+            add_to_inventory(right, ii);
+        }
+        *self = Self::new();
+        Ok(())
+    }
+
+    fn check<L: Slot<I, II>, R: Slot<I, II>>(
+        &self,
+        left: &[L],
+        right: &[R],
+    ) -> Result<(), TradeError> {
+        let [left_offers, right_offers] = &self.offers;
+        if !holds_offers(left, left_offers) {
+            return Err(TradeError::MissingItems(Side::Left));
+        }
+        if !holds_offers(right, right_offers) {
+            return Err(TradeError::MissingItems(Side::Right));
+        }
+        // Plays the trade out on copies of the inventories' contents.
+        let mut left: Vec<Preview<L, II>> = left.iter().map(Preview::of).collect();
+        let mut right: Vec<Preview<R, II>> = right.iter().map(Preview::of).collect();
+        let from_left = take_offers(&mut left, left_offers);
+        let from_right = take_offers(&mut right, right_offers);
+        if !fits(&mut left, &from_right) {
+            return Err(TradeError::NoSpace(Side::Left));
+        }
+        if !fits(&mut right, &from_left) {
+            return Err(TradeError::NoSpace(Side::Right));
+        }
+        Ok(())
+    }
+}
+
+/// Whether every offered slot still holds an instance like the offered one, with enough of it
+/// available for all the offers from that slot.
+fn holds_offers<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &[S],
+    offers: &[Offer<II>],
+) -> bool {
+    offers.iter().all(|offer| {
+        let slot = match inventory.get(offer.slot) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let held = match slot.item_instance() {
+            Some(held) => held,
+            None => return false,
+        };
+        let offered = &offer.item_instance;
+        if held.item().id() != offered.item().id()
+            || !held.stacks_with(offered)
+            || check(&held, Action::Trade).is_err()
+        {
+            return false;
+        }
+        let total = offers
+            .iter()
+            .filter(|other| other.slot == offer.slot)
+            .fold(I::Quant::ZERO, |total, other| {
+                total.saturating_add(single_quant(&other.item_instance))
+            });
+        available_quant_in_slot(slot) >= total
+    })
+}
+
+/// Takes the offered items out of their slots, and returns the instances that were taken.
+fn take_offers<I: Item, II: ItemInstance<I>, S: Slot<I, II>>(
+    inventory: &mut [S],
+    offers: &[Offer<II>],
+) -> Vec<II> {
+    let mut taken = Vec::new();
+    for offer in offers {
+        let slot = &mut inventory[offer.slot];
+        let held = match slot.item_instance() {
+            Some(held) => held,
+            None => continue,
+        };
+        let quant = single_quant(&offer.item_instance);
+        if held.item().stackable() {
+            taken.push(held.with_quant(quant));
+            take_from_slot(quant, slot, held);
+        } else {
+            slot.set_item_instance(&None);
+            taken.push(held);
+        }
+    }
+    taken
+}
+
+/// Adds items to an inventory, and returns whether all of them fit.
+fn fits<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>>(
+    inventory: &mut [S],
+    items: &[II],
+) -> bool {
+    items.iter().all(|ii| {
+        if !can_add_item_to_inventory(inventory, ii.clone()) {
+            return false;
+        }
+        add_to_inventory(inventory, ii.clone()).is_none()
+    })
+}
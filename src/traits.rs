@@ -1,35 +1,175 @@
 //! All traits that are needed to interface with the inventory system.
 
-use std::sync::Arc;
+use core::fmt::Debug;
+use core::ops::Deref;
+
+use crate::restrictions::ItemFlags;
+use crate::slot_management::{swap, StackLimit};
+
+#[cfg(feature = "derive")]
+pub use game_inventory_derive::{Item, ItemInstance, Slot};
+
+/// Trait for the numeric type item quantities are stored as.
+///
+/// Implemented for every unsigned integer type, so stacks can be as small
+/// as a `u8` or as big as a `u64` depending on what your game needs.
+///
+/// All arithmetic in this crate goes through the checked methods, so quantities never overflow silently.
+pub trait Quantity: Copy + Ord + Debug {
+    /// No items.
+    const ZERO: Self;
+    /// A single item.
+    const ONE: Self;
+    /// The biggest representable quantity.
+    const MAX: Self;
+    /// Adds two quantities, returning `None` if the result does not fit.
+    fn checked_add(self, other: Self) -> Option<Self>;
+    /// Subtracts a quantity, returning `None` if the result would be negative.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    /// Splits a quantity into two halves. If it is odd, the second half gets the extra item.
+    fn halve(self) -> (Self, Self);
+    /// Converts the quantity into a `u128`, which every quantity fits into.
+    ///
+    /// Used for totals across many stacks, which may not fit into the quantity type itself.
+    fn to_u128(self) -> u128;
+    /// Converts a `u128` into a quantity, clamping it to `Quantity::MAX`.
+    fn from_u128_saturating(value: u128) -> Self;
+    /// Adds two quantities, clamping the result to `Quantity::MAX`.
+    fn saturating_add(self, other: Self) -> Self {
+        self.checked_add(other).unwrap_or(Self::MAX)
+    }
+    /// Subtracts a quantity, clamping the result to `Quantity::ZERO`.
+    fn saturating_sub(self, other: Self) -> Self {
+        self.checked_sub(other).unwrap_or(Self::ZERO)
+    }
+}
+
+macro_rules! impl_quantity {
+    ($($t:ty),*) => {
+        $(
+            impl Quantity for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+                fn halve(self) -> (Self, Self) {
+                    (self / 2, self - self / 2)
+                }
+                fn to_u128(self) -> u128 {
+                    self as u128
+                }
+                fn from_u128_saturating(value: u128) -> Self {
+                    <$t>::try_from(value).unwrap_or(<$t>::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_quantity!(u8, u16, u32, u64, u128, usize);
 
-use crate::slot_management::swap;
 /// Trait for defining what static item data is necessary for the inventory system.
 ///
 /// Static item data are things like the items name, the items base damage. Data
 /// about the item that does not change item stack to item stack should be stored here.
 pub trait Item {
     type Id: Eq;
+    /// The type quantities of this item are stored as.
+    type Quant: Quantity;
     /// Whether the item can be put into stacks.
     /// A sword you may only want to have one of,
     /// While throwing knives may be stackable.
     fn stackable(&self) -> bool;
     /// The maximum quantity of a stack. This does not matter
     /// for non stackable items.
-    fn max_quant(&self) -> u16;
+    fn max_quant(&self) -> Self::Quant;
     /// The Unique ID of the item
     fn id(&self) -> Self::Id;
+    /// The categories the item belongs to, like `"food/meat"` or `"plank"`.
+    ///
+    /// Tags are paths separated by `/`, where each part is a subcategory of the one before it.
+    /// Items have no tags unless this is overridden.
+    fn tags(&self) -> impl Iterator<Item = &str> {
+        core::iter::empty()
+    }
+    /// Whether the item has a tag, or a tag below it. An item tagged `"food/meat"`
+    /// has the tags `"food/meat"` and `"food"`, but not `"foo"` or `"meat"`.
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags().any(|own| match own.strip_prefix(tag) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        })
+    }
+    /// Where the item may go, like whether it can be traded or dropped. See `restrictions`.
+    fn flags(&self) -> ItemFlags {
+        ItemFlags::NONE
+    }
 }
 /// Trait for storing item instance data.
 ///
 /// If you have two stacks of items, the quantity of items
 /// in each stack is stored separately. This is where you store that data.
+///
+/// Instances refer to their item through `ItemRef`, which is usually an `Arc<I>`.
+/// Without an allocator, a `&'static I` works just as well.
 pub trait ItemInstance<I: Item> {
+    /// How this instance refers to its item.
+    type ItemRef: Deref<Target = I> + Clone;
     /// The quantity of items in this instance.
-    fn quant(&self) -> u16;
+    fn quant(&self) -> I::Quant;
     /// The item stored by this instance.
-    fn item(&self) -> Arc<I>;
+    fn item(&self) -> Self::ItemRef;
     /// Creates a new item instance.
-    fn new(item: Arc<I>, quantity: u16) -> Self;
+    fn new(item: Self::ItemRef, quantity: I::Quant) -> Self;
+    /// A copy of this instance with another quantity, used whenever a stack is split or merged.
+    ///
+    /// Defaults to `new`. Override it for instances with data of their own, like durability or
+    /// flags, so the data is not lost when the stack is rebuilt.
+    fn with_quant(&self, quantity: I::Quant) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(self.item(), quantity)
+    }
+    /// A copy of this instance pointing at another definition of its item, used when item
+    /// definitions are reloaded. See `item_registry`.
+    ///
+    /// Defaults to `new`. Override it together with `with_quant`.
+    fn with_item(&self, item: Self::ItemRef) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, self.quant())
+    }
+    /// Whether this instance can be combined into one stack with another instance of the same item.
+    ///
+    /// Defaults to `true`. Return `false` for instances that carry their own data, like rolled
+    /// affixes, so they keep a slot of their own instead of merging into another stack.
+    fn stacks_with(&self, _other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        true
+    }
+    /// Where this instance may go. Defaults to the item's flags.
+    ///
+    /// Override it for flags that only some instances have, like gear that is bound once equipped.
+    /// Such instances should also override `with_quant` and `stacks_with`, so the flags survive
+    /// splitting a stack and bound items do not merge into unbound stacks.
+    fn flags(&self) -> ItemFlags {
+        self.item().flags()
+    }
+    /// How intact this instance is, from 0.0 when it is broken to 1.0 when it is new.
+    ///
+    /// Defaults to 1.0. Salvaging worn gear yields less, see `salvage`.
+    fn condition(&self) -> f32 {
+        1.0
+    }
 }
 /// Trait for defining an item slot.
 ///
@@ -53,12 +193,40 @@ pub trait Slot<I: Item, II: ItemInstance<I>> {
     /// with the item in the slot.
     ///
     /// The button variable is necessary for changing the behavior based on user input.
+    ///
+    /// Locked slots hand the item instance straight back.
     fn transfer(&mut self, item_instance: Option<II>, _button: &str) -> Option<II> {
+        if self.locked() {
+            return item_instance;
+        }
         let res = swap((self.item_instance(), item_instance));
         self.set_modified(true);
         self.set_item_instance(&res.0);
         res.1
     }
+    /// The most of an item this slot can hold in a single stack.
+    ///
+    /// Defaults to the item's own `max_quant`, or one for unstackable items. Override it for
+    /// containers that cap stacks lower or higher than usual, like ammo pouches or display cases.
+    /// `StackLimit` can do the math for you. Slots with a limit of zero take no unstackable items.
+    fn stack_limit(&self, item: &I) -> I::Quant {
+        StackLimit::Item.of(item)
+    }
+    /// Whether the slot is locked.
+    ///
+    /// The helpers that add, remove or move items skip locked slots, and leave their contents
+    /// alone. Anything else that moves items between slots, like sorting, should do the same.
+    fn locked(&self) -> bool {
+        false
+    }
+    /// How much of the slot's stack is reserved, like for a queued crafting job.
+    ///
+    /// The helpers only take what is above this from the slot, and an unstackable item
+    /// is not taken at all while anything is reserved. See `reservation` for handing out
+    /// reservations that are released on drop.
+    fn reserved(&self) -> I::Quant {
+        I::Quant::ZERO
+    }
     /// Whether the slots contents have been modified.
     fn modified(&mut self) -> bool;
     /// Sets whether the slots contents have been modified.
@@ -0,0 +1,277 @@
+//! Moving items between inventories automatically, like hoppers and conveyor belts.
+//!
+//! A `Transport` holds `Link`s, each of which pulls items out of one inventory and pushes them
+//! into one or more others. Inventories are referred to by their index in the slice passed to
+//! `Transport::tick`, so the game keeps ownership of them.
+//!
+//! Links run in the order they were added, slots are visited from first to last, and targets
+//! are picked by a fixed rule, so the same inventories and links always give the same result.
+//! That keeps simulations deterministic, for replays and lockstep multiplayer.
+use crate::inventory_management::{add_to_inventory, available_quant_in_slot, take_from_slot};
+use crate::restrictions::{check, Action};
+use crate::traits::{Item, ItemInstance, Quantity, Slot};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::DerefMut;
+
+/// Which items a link moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter<Id> {
+    /// Every item.
+    Any,
+    /// Only items with one of these ids.
+    Only(Vec<Id>),
+    /// Every item except the ones with these ids.
+    Except(Vec<Id>),
+}
+
+impl<Id: Eq> Filter<Id> {
+    /// Whether items with this id pass the filter.
+    pub fn matches(&self, id: &Id) -> bool {
+        match self {
+            Filter::Any => true,
+            Filter::Only(ids) => ids.contains(id),
+            Filter::Except(ids) => !ids.contains(id),
+        }
+    }
+}
+
+/// How a link with several targets picks where items go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Distribution {
+    /// Fills the first target that has room, and only moves on to the next once it is full.
+    #[default]
+    Priority,
+    /// Takes turns, each transfer goes to the target after the one that got the last transfer.
+    RoundRobin,
+}
+
+/// Pulls items out of one inventory and pushes them into others.
+///
+/// Moves up to `amount` of a single item every `interval` ticks. The interval only starts
+/// after something was moved, so an idle link picks up new items on the next tick.
+#[derive(Debug, Clone)]
+pub struct Link<I: Item> {
+    pub source: usize,
+    pub targets: Vec<usize>,
+    pub filter: Filter<I::Id>,
+    pub distribution: Distribution,
+    pub amount: I::Quant,
+    pub interval: u32,
+    /// Index into `targets` of the next round robin target.
+    next_target: usize,
+    /// Ticks left until the link moves items again.
+    cooldown: u32,
+}
+
+impl<I: Item> Link<I> {
+    /// A link that moves any item, one at a time every tick, to the first target with room.
+    pub fn new(source: usize, targets: Vec<usize>) -> Self {
+        Link {
+            source,
+            targets,
+            filter: Filter::Any,
+            distribution: Distribution::Priority,
+            amount: I::Quant::ONE,
+            interval: 1,
+            next_target: 0,
+            cooldown: 0,
+        }
+    }
+}
+
+/// Items a link moved during a tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer<Id, Q> {
+    /// The index of the link in the `Transport`.
+    pub link: usize,
+    pub source: usize,
+    pub target: usize,
+    pub id: Id,
+    /// How many were moved, counting unstackable items as one.
+    pub quant: Q,
+}
+
+/// Every link between a set of inventories.
+///
+/// ```
+/// # use game_inventory::transport::{Distribution, Link, Transport};
+/// # use game_inventory::samples::{DefaultSlot, TORCH, TORCH_INST};
+/// # use game_inventory::traits::{Item, Slot};
+/// # use game_inventory::helpers::quant_in_inventory;
+/// let mut inventories = vec![
+///     vec![DefaultSlot::new(TORCH_INST.clone())],
+///     vec![DefaultSlot::new(None)],
+///     vec![DefaultSlot::new(None)],
+/// ];
+/// let mut link = Link::new(0, vec![1, 2]);
+/// link.distribution = Distribution::RoundRobin;
+/// link.amount = 2;
+/// let mut transport = Transport::new();
+/// transport.add_link(link);
+///
+/// for _ in 0..3 {
+///     transport.tick(&mut inventories);
+/// }
+/// assert_eq!(quant_in_inventory(&inventories[0], TORCH.id()), 17);
+/// assert_eq!(quant_in_inventory(&inventories[1], TORCH.id()), 4);
+/// assert_eq!(quant_in_inventory(&inventories[2], TORCH.id()), 2);
+/// ```
+pub struct Transport<I: Item> {
+    links: Vec<Link<I>>,
+}
+
+impl<I: Item> Debug for Transport<I>
+where
+    Link<I>: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Transport")
+            .field("links", &self.links)
+            .finish()
+    }
+}
+
+impl<I: Item> Clone for Transport<I>
+where
+    Link<I>: Clone,
+{
+    fn clone(&self) -> Self {
+        Transport {
+            links: self.links.clone(),
+        }
+    }
+}
+
+impl<I: Item> Default for Transport<I> {
+    fn default() -> Self {
+        Transport { links: Vec::new() }
+    }
+}
+
+impl<I: Item> Transport<I> {
+    /// Creates a transport without any links.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a link, returning its index. Links run in the order they were added.
+    pub fn add_link(&mut self, link: Link<I>) -> usize {
+        self.links.push(link);
+        self.links.len() - 1
+    }
+
+    /// The link at an index.
+    pub fn link(&self, index: usize) -> Option<&Link<I>> {
+        self.links.get(index)
+    }
+
+    /// The link at an index, to change its settings.
+    pub fn link_mut(&mut self, index: usize) -> Option<&mut Link<I>> {
+        self.links.get_mut(index)
+    }
+
+    /// Runs every link once, and returns what was moved, in order.
+    ///
+    /// Targets that are the link's own source are skipped.
+    ///
+    /// # Panics
+    /// If a link refers to an inventory that is not in `inventories`.
+    pub fn tick<II: ItemInstance<I> + Clone, S: Slot<I, II>, C: DerefMut<Target = [S]>>(
+        &mut self,
+        inventories: &mut [C],
+    ) -> Vec<Transfer<I::Id, I::Quant>> {
+        let mut transfers = Vec::new();
+        for (index, link) in self.links.iter_mut().enumerate() {
+            if link.cooldown > 0 {
+                link.cooldown -= 1;
+                continue;
+            }
+            let before = transfers.len();
+            run_link(index, link, inventories, &mut transfers);
+            if transfers.len() > before {
+                link.cooldown = link.interval.saturating_sub(1);
+            }
+        }
+        transfers
+    }
+}
+
+/// Moves a single item from the first slot in the source that any target has room for.
+fn run_link<I: Item, II: ItemInstance<I> + Clone, S: Slot<I, II>, C: DerefMut<Target = [S]>>(
+    index: usize,
+    link: &mut Link<I>,
+    inventories: &mut [C],
+    transfers: &mut Vec<Transfer<I::Id, I::Quant>>,
+) {
+    let targets: Vec<usize> = match link.distribution {
+        Distribution::Priority => link.targets.clone(),
+        Distribution::RoundRobin => {
+            let start = link.next_target.min(link.targets.len());
+            link.targets[start..]
+                .iter()
+                .chain(&link.targets[..start])
+                .copied()
+                .collect()
+        }
+    };
+    for slot in 0..inventories[link.source].len() {
+        let ii = match inventories[link.source][slot].item_instance() {
+            Some(ii) => ii,
+            None => continue,
+        };
+        let item = ii.item();
+        if !link.filter.matches(&item.id()) || check(&ii, Action::Move).is_err() {
+            continue;
+        }
+        let mut left = available_quant_in_slot(&inventories[link.source][slot]).min(link.amount);
+        if !item.stackable() {
+            left = left.min(I::Quant::ONE);
+        }
+        if left == I::Quant::ZERO {
+            continue;
+        }
+        let mut moved_any = false;
+        for &target in targets.iter().filter(|&&target| target != link.source) {
+            let moving = if item.stackable() {
+                ii.with_quant(left)
+            } else {
+                ii.clone()
+            };
+            let not_added = match add_to_inventory(&mut inventories[target], moving) {
+                Some(_) if !item.stackable() => I::Quant::ONE,
+                Some(not_added) => not_added.quant(),
+                None => I::Quant::ZERO,
+            };
+            let moved = left.saturating_sub(not_added);
+            if moved == I::Quant::ZERO {
+                continue;
+            }
+            let source = &mut inventories[link.source][slot];
+            match source.item_instance() {
+                Some(current) => take_from_slot(moved, source, current),
+                None => I::Quant::ZERO,
+            };
+            transfers.push(Transfer {
+                link: index,
+                source: link.source,
+                target,
+                id: item.id(),
+                quant: moved,
+            });
+            left = left.saturating_sub(moved);
+            moved_any = true;
+            if link.distribution == Distribution::RoundRobin {
+                let position = link.targets.iter().position(|&t| t == target);
+                link.next_target = position.map_or(0, |position| position + 1) % link.targets.len();
+                return;
+            }
+            if left == I::Quant::ZERO {
+                return;
+            }
+        }
+        if moved_any {
+            return;
+        }
+    }
+}
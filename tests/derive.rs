@@ -0,0 +1,123 @@
+#![cfg(feature = "derive")]
+use game_inventory::helpers::{add_to_inventory, combine_stack, quant_in_inventory};
+use game_inventory::restrictions::ItemFlags;
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+#[derive(Item, Debug, Clone)]
+struct Material {
+    #[item(id)]
+    id: String,
+    #[item(max_quant)]
+    max: u32,
+    #[item(stackable)]
+    stacks: bool,
+    #[item(tags)]
+    tags: Vec<String>,
+    #[item(flags)]
+    flags: ItemFlags,
+    weight: f32,
+}
+
+#[derive(ItemInstance, Clone, Debug)]
+struct Stack<I: Item> {
+    #[instance(item)]
+    item: Arc<I>,
+    #[instance(quant)]
+    quant: I::Quant,
+    quality: u8,
+}
+
+#[derive(Slot)]
+struct Cell<II> {
+    #[slot(instance)]
+    contents: Option<II>,
+    #[slot(modified)]
+    modified: bool,
+    locked: bool,
+}
+
+fn iron() -> Arc<Material> {
+    Arc::new(Material {
+        id: "iron".to_owned(),
+        max: 1000,
+        stacks: true,
+        tags: vec!["metal/iron".to_owned(), "magnetic".to_owned()],
+        flags: ItemFlags::NONE,
+        weight: 0.5,
+    })
+}
+
+#[test]
+fn derived_item() {
+    let gem = Material {
+        id: "gem".to_owned(),
+        max: 1000,
+        stacks: false,
+        tags: vec![],
+        flags: ItemFlags::NO_SELL,
+        weight: 0.1,
+    };
+    assert_eq!(gem.id(), "gem");
+    assert_eq!(gem.max_quant(), 1000);
+    assert!(!gem.stackable());
+    assert_eq!(gem.flags(), ItemFlags::NO_SELL);
+    assert!(iron().stackable());
+    assert_eq!(iron().weight, 0.5);
+    assert!(gem.tags().next().is_none());
+}
+
+#[test]
+fn derived_tags() {
+    assert_eq!(
+        iron().tags().collect::<Vec<_>>(),
+        vec!["metal/iron", "magnetic"]
+    );
+    assert!(iron().has_tag("metal"));
+    assert!(!iron().has_tag("met"));
+}
+
+#[test]
+fn derived_instance_works_with_the_helpers() {
+    let stack = Stack::new(iron(), 700);
+    assert_eq!(stack.quality, 0);
+    let res = combine_stack((Some(stack.clone()), Some(stack)))
+        .ok()
+        .unwrap();
+    assert_eq!(res.0.unwrap().quant(), 400);
+    assert_eq!(res.1.unwrap().quant(), 1000);
+}
+
+#[test]
+fn derived_slot_works_with_the_helpers() {
+    let mut inventory: Vec<Cell<Stack<Material>>> = (0..3).map(|_| Cell::new(None)).collect();
+    assert!(!inventory[0].locked);
+    assert!(add_to_inventory(&mut inventory, Stack::new(iron(), 600)).is_none());
+    assert!(add_to_inventory(&mut inventory, Stack::new(iron(), 600)).is_none());
+    assert_eq!(quant_in_inventory(&inventory, "iron".to_owned()), 1200);
+    assert!(inventory[0].modified());
+    assert!(inventory[1].modified());
+    assert!(!inventory[2].modified());
+
+    let held = inventory[1].transfer(None, "left_click");
+    assert_eq!(held.unwrap().quant(), 200);
+    assert!(inventory[1].item_instance().is_none());
+}
+
+#[test]
+fn derived_instance_keeps_its_data() {
+    let mut fine = Stack::new(iron(), 700);
+    fine.quality = 3;
+    let res = combine_stack((Some(fine.clone()), Some(fine.clone())))
+        .ok()
+        .unwrap();
+    assert_eq!(res.0.unwrap().quality, 3);
+    assert_eq!(res.1.unwrap().quality, 3);
+    assert_eq!(fine.with_item(iron()).quality, 3);
+
+    let mut inventory: Vec<Cell<Stack<Material>>> = (0..2).map(|_| Cell::new(None)).collect();
+    add_to_inventory(&mut inventory, fine);
+    add_to_inventory(&mut inventory, Stack::new(iron(), 100));
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 700);
+    assert_eq!(inventory[1].item_instance().unwrap().quality, 0);
+}
@@ -0,0 +1,226 @@
+use game_inventory::equipment::{EquipError, EquipSlot, Equipment, Modifier, SetBonus};
+use game_inventory::reservation::reserve;
+use game_inventory::samples::{DefaultItemInstance, DefaultSlot, Gear, ReservableSlot};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type GearSlot = DefaultSlot<'static, Gear<'static>, DefaultItemInstance<Gear<'static>>>;
+
+fn gear(name: &'static str, equip_slot: Option<EquipSlot>, two_handed: bool) -> GearSlot {
+    let gear = Gear {
+        name,
+        max_quantity: 1,
+        equip_slot,
+        two_handed,
+        set: None,
+        modifiers: vec![Modifier::Add("damage", 1.0)],
+    };
+    DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(gear), 1)))
+}
+
+fn name(ii: Option<DefaultItemInstance<Gear<'static>>>) -> Option<&'static str> {
+    ii.map(|ii| ii.item().id())
+}
+
+#[test]
+fn equipping_swaps_with_the_backpack() {
+    let mut backpack = vec![
+        gear("cap", Some(EquipSlot::Head), false),
+        gear("hood", Some(EquipSlot::Head), false),
+        gear("apple", None, false),
+        DefaultSlot::new(None),
+    ];
+    let mut equipment: Equipment<_, _, GearSlot> = Equipment::new();
+
+    assert_eq!(equipment.equip(&mut backpack, 0), Ok(()));
+    assert_eq!(name(equipment.get(EquipSlot::Head)), Some("cap"));
+    assert!(backpack[0].item_instance().is_none());
+
+    assert_eq!(equipment.equip(&mut backpack, 1), Ok(()));
+    assert_eq!(name(equipment.get(EquipSlot::Head)), Some("hood"));
+    assert_eq!(name(backpack[1].item_instance()), Some("cap"));
+
+    assert_eq!(
+        equipment.equip(&mut backpack, 2),
+        Err(EquipError::NotEquippable)
+    );
+    assert_eq!(
+        equipment.equip(&mut backpack, 3),
+        Err(EquipError::EmptySlot)
+    );
+    assert_eq!(
+        equipment.equip(&mut backpack, 9),
+        Err(EquipError::EmptySlot)
+    );
+
+    assert_eq!(equipment.unequip(EquipSlot::Head, &mut backpack), Ok(()));
+    assert_eq!(name(backpack[0].item_instance()), Some("hood"));
+    assert_eq!(
+        equipment.unequip(EquipSlot::Head, &mut backpack),
+        Err(EquipError::EmptySlot)
+    );
+}
+
+#[test]
+fn two_handed_items_take_up_both_hands() {
+    let mut backpack = vec![
+        gear("sword", Some(EquipSlot::MainHand), false),
+        gear("shield", Some(EquipSlot::OffHand), false),
+        gear("greatsword", Some(EquipSlot::MainHand), true),
+    ];
+    let mut equipment: Equipment<_, _, GearSlot> = Equipment::new();
+    equipment.equip(&mut backpack, 0).unwrap();
+    equipment.equip(&mut backpack, 1).unwrap();
+    backpack[0] = gear("apple", None, false);
+    backpack[1] = gear("apple", None, false);
+
+    // Both hands come off, but there is only room for one of them.
+    assert_eq!(
+        equipment.equip(&mut backpack, 2),
+        Err(EquipError::BackpackFull)
+    );
+    backpack.push(DefaultSlot::new(None));
+    assert_eq!(equipment.equip(&mut backpack, 2), Ok(()));
+    assert_eq!(name(backpack[2].item_instance()), Some("sword"));
+    assert_eq!(name(backpack[3].item_instance()), Some("shield"));
+    assert_eq!(name(equipment.get(EquipSlot::OffHand)), Some("greatsword"));
+    assert!(equipment.slot(EquipSlot::OffHand).item_instance().is_none());
+    assert_eq!(equipment.equipped().count(), 1);
+
+    // Going back to a shield takes the greatsword off too.
+    assert_eq!(equipment.equip(&mut backpack, 3), Ok(()));
+    assert_eq!(name(backpack[3].item_instance()), Some("greatsword"));
+    assert!(equipment.get(EquipSlot::MainHand).is_none());
+    assert_eq!(name(equipment.get(EquipSlot::OffHand)), Some("shield"));
+}
+
+#[test]
+fn unequipping_needs_room_in_the_backpack() {
+    let mut backpack = vec![gear("greatsword", Some(EquipSlot::MainHand), true)];
+    let mut equipment: Equipment<_, _, GearSlot> = Equipment::new();
+    equipment.equip(&mut backpack, 0).unwrap();
+    backpack[0] = gear("apple", None, false);
+
+    assert_eq!(
+        equipment.unequip(EquipSlot::OffHand, &mut backpack),
+        Err(EquipError::BackpackFull)
+    );
+    assert_eq!(name(equipment.get(EquipSlot::MainHand)), Some("greatsword"));
+
+    backpack.push(DefaultSlot::new(None));
+    assert_eq!(equipment.unequip(EquipSlot::OffHand, &mut backpack), Ok(()));
+    assert_eq!(name(backpack[1].item_instance()), Some("greatsword"));
+    assert_eq!(equipment.equipped().count(), 0);
+}
+
+#[test]
+fn reserved_items_are_not_equipped() {
+    let arrows = Gear {
+        name: "arrows",
+        max_quantity: 50,
+        equip_slot: Some(EquipSlot::OffHand),
+        two_handed: false,
+        set: None,
+        modifiers: Vec::new(),
+    };
+    let mut backpack = vec![ReservableSlot::new(Some(DefaultItemInstance::new(
+        Arc::new(arrows),
+        10,
+    )))];
+    let mut equipment: Equipment<_, _, GearSlot> = Equipment::new();
+    let reservation = reserve(&backpack, "arrows", 6).unwrap();
+    assert_eq!(equipment.equip(&mut backpack, 0), Err(EquipError::Locked));
+    assert_eq!(backpack[0].item_instance().unwrap().quant(), 10);
+    assert!(equipment.get(EquipSlot::OffHand).is_none());
+
+    drop(reservation);
+    backpack[0].locked = true;
+    assert_eq!(equipment.equip(&mut backpack, 0), Err(EquipError::Locked));
+    backpack[0].locked = false;
+    equipment.equip(&mut backpack, 0).unwrap();
+    assert_eq!(name(equipment.get(EquipSlot::OffHand)), Some("arrows"));
+}
+
+#[test]
+fn one_item_of_a_stack_is_equipped() {
+    let ring = |name| Gear {
+        name,
+        max_quantity: 10,
+        equip_slot: Some(EquipSlot::MainHand),
+        two_handed: false,
+        set: None,
+        modifiers: Vec::new(),
+    };
+    let rings = |name, quant| Some(DefaultItemInstance::new(Arc::new(ring(name)), quant));
+    let mut backpack = vec![
+        DefaultSlot::new(rings("gold", 5)),
+        DefaultSlot::new(rings("silver", 3)),
+    ];
+    let mut equipment: Equipment<_, _, GearSlot> = Equipment::new();
+    equipment.equip(&mut backpack, 0).unwrap();
+    assert_eq!(equipment.get(EquipSlot::MainHand).unwrap().quant(), 1);
+    assert_eq!(backpack[0].item_instance().unwrap().quant(), 4);
+
+    // The gold ring has nowhere to go, since the silver rings keep their slot.
+    assert_eq!(
+        equipment.equip(&mut backpack, 1),
+        Err(EquipError::BackpackFull)
+    );
+    backpack.push(DefaultSlot::new(None));
+    equipment.equip(&mut backpack, 1).unwrap();
+    assert_eq!(name(equipment.get(EquipSlot::MainHand)), Some("silver"));
+    assert_eq!(backpack[1].item_instance().unwrap().quant(), 2);
+    assert_eq!(name(backpack[2].item_instance()), Some("gold"));
+}
+
+#[test]
+fn set_bonuses_stack_by_pieces() {
+    let piece = |name, equip_slot| {
+        let gear = Gear {
+            name,
+            max_quantity: 1,
+            equip_slot: Some(equip_slot),
+            two_handed: false,
+            set: Some("dragon"),
+            modifiers: vec![
+                Modifier::Add("armor", 10.0),
+                Modifier::Multiply("speed", 0.9),
+            ],
+        };
+        DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(gear), 1)))
+    };
+    let mut backpack = vec![
+        piece("dragon helm", EquipSlot::Head),
+        piece("dragon scale", EquipSlot::Chest),
+        piece("dragon greaves", EquipSlot::Legs),
+        gear("boots", Some(EquipSlot::Feet), false),
+    ];
+    let bonuses = [
+        SetBonus {
+            set: "dragon",
+            pieces: 2,
+            modifiers: vec![Modifier::Add("armor", 5.0)],
+        },
+        SetBonus {
+            set: "dragon",
+            pieces: 3,
+            modifiers: vec![Modifier::Multiply("armor", 2.0)],
+        },
+    ];
+    let mut equipment: Equipment<_, _, GearSlot> = Equipment::new();
+    equipment.equip(&mut backpack, 3).unwrap();
+    equipment.equip(&mut backpack, 0).unwrap();
+    assert_eq!(equipment.stats(&bonuses).total(&"armor", 0.0), 10.0);
+
+    equipment.equip(&mut backpack, 1).unwrap();
+    assert_eq!(equipment.set_pieces(&"dragon"), 2);
+    assert_eq!(equipment.stats(&bonuses).total(&"armor", 0.0), 25.0);
+
+    equipment.equip(&mut backpack, 2).unwrap();
+    let stats = equipment.stats(&bonuses);
+    assert_eq!(stats.added(&"armor"), 35.0);
+    assert_eq!(stats.multiplier(&"armor"), 2.0);
+    assert_eq!(stats.total(&"armor", 0.0), 70.0);
+    assert_eq!(stats.total(&"damage", 2.0), 3.0);
+    assert!((stats.total(&"speed", 100.0) - 72.9).abs() < 0.001);
+}
@@ -0,0 +1,95 @@
+//! Only uses what is available without the `std` feature, so it can be run with
+//! `cargo test --no-default-features --test fixed_inventory`.
+use game_inventory::fixed_inventory::FixedInventory;
+use game_inventory::helpers::{
+    add_to_inventory, combine_stack, empty_quant_in_inventory, half_stack_split,
+    quant_in_inventory, remove_from_inventory, remove_from_stack, SlotError,
+};
+use game_inventory::samples::{DefaultItem, DefaultSlot, RefItemInstance};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+
+static POTION: DefaultItem<u8> = DefaultItem {
+    name: "potion",
+    max_quantity: 10,
+};
+
+static SHIELD: DefaultItem<u8> = DefaultItem {
+    name: "shield",
+    max_quantity: 0,
+};
+
+type Inst = RefItemInstance<'static, DefaultItem<'static, u8>>;
+type Hotbar = FixedInventory<
+    DefaultItem<'static, u8>,
+    Inst,
+    DefaultSlot<'static, DefaultItem<'static, u8>, Inst>,
+    4,
+>;
+
+fn potions(quant: u8) -> Option<Inst> {
+    Some(RefItemInstance::new(&POTION, quant))
+}
+
+#[test]
+fn helpers_work_on_fixed_inventories() {
+    let mut hotbar = Hotbar::new();
+    assert_eq!(empty_quant_in_inventory(&hotbar), 4);
+    assert!(add_to_inventory(&mut hotbar, RefItemInstance::new(&SHIELD, 1)).is_none());
+    assert!(add_to_inventory(&mut hotbar, potions(7).unwrap()).is_none());
+    assert!(add_to_inventory(&mut hotbar, potions(7).unwrap()).is_none());
+    assert_eq!(hotbar[1].item_instance().unwrap().quant(), 10);
+    assert_eq!(hotbar[2].item_instance().unwrap().quant(), 4);
+    assert_eq!(quant_in_inventory(&hotbar, POTION.id()), 14);
+
+    assert!(remove_from_inventory(&mut hotbar, potions(12).unwrap()).is_none());
+    assert_eq!(quant_in_inventory(&hotbar, POTION.id()), 2);
+    assert_eq!(empty_quant_in_inventory(&hotbar), 2);
+
+    let slots = hotbar.into_inner();
+    assert!(core::ptr::eq(
+        slots[0].item_instance().unwrap().item(),
+        &SHIELD
+    ));
+}
+
+#[test]
+fn slot_errors_do_not_allocate() {
+    let shield = Some(RefItemInstance::new(&SHIELD, 1));
+    let err = |res: Result<_, (SlotError, _)>| res.err().map(|(err, _)| err);
+    assert_eq!(
+        err(combine_stack((potions(3), None))),
+        Some(SlotError::MissingItems)
+    );
+    assert_eq!(
+        err(combine_stack((potions(3), shield))),
+        Some(SlotError::DifferentItems)
+    );
+    assert_eq!(
+        err(combine_stack((potions(10), potions(3)))),
+        Some(SlotError::FullStack)
+    );
+    assert_eq!(
+        err(half_stack_split((None, potions(3)))),
+        Some(SlotError::MissingSource)
+    );
+    assert_eq!(
+        err(half_stack_split((shield, None))),
+        Some(SlotError::Unstackable)
+    );
+    assert_eq!(
+        err(half_stack_split((potions(1), None))),
+        Some(SlotError::SingleItem)
+    );
+    assert_eq!(
+        err(half_stack_split((potions(4), potions(10)))),
+        Some(SlotError::FullStack)
+    );
+    assert_eq!(
+        err(remove_from_stack((potions(3), potions(10)))),
+        Some(SlotError::FullStack)
+    );
+    assert_eq!(
+        SlotError::DifferentItems.to_string(),
+        "Both items must be the same for this operation to work."
+    );
+}
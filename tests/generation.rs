@@ -0,0 +1,158 @@
+use game_inventory::generation::{
+    AffixDef, AffixKind, ItemGenerator, Rarity, Rng, Rolls, SeededRng,
+};
+use game_inventory::helpers::{add_to_inventory, can_add_item_to_inventory};
+use game_inventory::indexed_inventory::IndexedInventory;
+use game_inventory::samples::{DefaultItem, DefaultSlot, RolledItemInstance, TORCH};
+use game_inventory::traits::{ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = RolledItemInstance<DefaultItem<'static>>;
+
+fn affix(id: &'static str, kind: AffixKind, group: Option<&'static str>) -> AffixDef<&'static str> {
+    AffixDef {
+        id,
+        kind,
+        weight: 10,
+        group,
+        tiers: vec![(1, 5), (10, 20), (50, 60)],
+    }
+}
+
+fn generator() -> ItemGenerator<&'static str> {
+    let mut generator = ItemGenerator::new();
+    generator.add_rarity(Rarity {
+        id: "magic",
+        weight: 3,
+        prefixes: 1,
+        suffixes: 1,
+        tier: 0,
+    });
+    generator.add_rarity(Rarity {
+        id: "rare",
+        weight: 1,
+        prefixes: 3,
+        suffixes: 3,
+        tier: 1,
+    });
+    generator.add_affix(affix("sharp", AffixKind::Prefix, Some("damage")));
+    generator.add_affix(affix("brutal", AffixKind::Prefix, Some("damage")));
+    generator.add_affix(affix("sturdy", AffixKind::Prefix, None));
+    generator.add_affix(affix("of_haste", AffixKind::Suffix, None));
+    generator.add_affix(AffixDef {
+        weight: 0,
+        ..affix("of_nothing", AffixKind::Suffix, None)
+    });
+    generator
+}
+
+fn rolled(rolls: Rolls<&'static str>) -> Inst {
+    RolledItemInstance {
+        item: Arc::new(TORCH.clone()),
+        quantity: 1,
+        rolls: Some(rolls),
+    }
+}
+
+#[test]
+fn seeds_replay_the_same_rolls() {
+    let generator = generator();
+    let first: Vec<_> = (0..20)
+        .map(|seed| generator.roll(&mut SeededRng::new(seed)))
+        .collect();
+    let again: Vec<_> = (0..20)
+        .map(|seed| generator.roll(&mut SeededRng::new(seed)))
+        .collect();
+    assert_eq!(first, again);
+    assert!(first.iter().any(|rolls| rolls != &first[0]));
+
+    let mut rng = SeededRng::new(1);
+    let numbers: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+    assert_eq!(rng.clone().next_u64(), rng.next_u64());
+    assert_ne!(numbers[0], numbers[1]);
+    assert!((0..1000).all(|_| rng.below(7) < 7));
+}
+
+#[test]
+fn rolls_follow_the_rarity() {
+    let generator = generator();
+    let mut rng = SeededRng::new(42);
+    let mut rarities = Vec::new();
+    for _ in 0..400 {
+        let rolls = generator.roll(&mut rng).unwrap();
+        let (prefixes, suffixes, range) = match rolls.rarity {
+            "magic" => (1, 1, 1..=5),
+            _ => (2, 1, 10..=20),
+        };
+        // Only two prefixes fit next to each other, since sharp and brutal share a group.
+        assert_eq!(rolls.of_kind(AffixKind::Prefix).count(), prefixes);
+        assert_eq!(rolls.of_kind(AffixKind::Suffix).count(), suffixes);
+        assert!(rolls.get(&"of_nothing").is_none());
+        assert!(!(rolls.get(&"sharp").is_some() && rolls.get(&"brutal").is_some()));
+        assert!(rolls
+            .affixes
+            .iter()
+            .all(|affix| range.contains(&affix.value)));
+        rarities.push(rolls.rarity);
+    }
+    let magic = rarities.iter().filter(|&&rarity| rarity == "magic").count();
+    assert!((250..350).contains(&magic));
+}
+
+#[test]
+fn rarities_can_be_forced() {
+    let mut generator = generator();
+    generator.add_rarity(Rarity {
+        id: "legendary",
+        weight: 0,
+        prefixes: 1,
+        suffixes: 0,
+        tier: 5,
+    });
+    let mut rng = SeededRng::new(3);
+    let rolls = generator.roll_rarity(&"legendary", &mut rng).unwrap();
+    assert_eq!(rolls.affixes[0].tier, 2);
+    assert!((50..=60).contains(&rolls.affixes[0].value));
+    assert!(generator.roll_rarity(&"unique", &mut rng).is_none());
+    assert!(ItemGenerator::<&str>::new().roll(&mut rng).is_none());
+
+    let torch: Inst = generator
+        .generate(Arc::new(TORCH.clone()), &mut rng)
+        .unwrap();
+    assert_eq!(torch.quant(), 1);
+    assert!(torch.rolls.is_some());
+}
+
+#[test]
+fn rolled_items_never_merge() {
+    let mut rng = SeededRng::new(9);
+    let generator = generator();
+    let plain = |quant| RolledItemInstance::new(Arc::new(TORCH.clone()), quant);
+    let mut inventory: Vec<DefaultSlot<_, Inst>> = vec![
+        DefaultSlot::new(Some(rolled(generator.roll(&mut rng).unwrap()))),
+        DefaultSlot::new(Some(plain(10))),
+        DefaultSlot::new(None),
+    ];
+    let item = rolled(generator.roll(&mut rng).unwrap());
+    assert!(add_to_inventory(&mut inventory, item.clone()).is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 1);
+    assert_eq!(inventory[1].item_instance().unwrap().quant(), 10);
+    assert_eq!(inventory[2].item_instance().unwrap().rolls, item.rolls);
+    assert!(!can_add_item_to_inventory(&inventory, item.clone()));
+    // Unrolled ones still stack.
+    assert!(add_to_inventory(&mut inventory, plain(90)).is_none());
+    assert_eq!(inventory[1].item_instance().unwrap().quant(), 100);
+
+    let mut indexed = IndexedInventory::new(vec![
+        DefaultSlot::new(Some(plain(10))),
+        DefaultSlot::new(None),
+    ]);
+    assert!(indexed.can_add_item(&item));
+    assert!(indexed.add(item.clone()).is_none());
+    assert_eq!(indexed[0].item_instance().unwrap().quant(), 10);
+    assert_eq!(indexed[1].item_instance().unwrap().rolls, item.rolls);
+    assert!(!indexed.can_add_item(&item));
+    assert_eq!(indexed.add(item).unwrap().quant(), 1);
+    assert!(indexed.add(plain(90)).is_none());
+    assert_eq!(indexed.quant(TORCH.name), 101);
+}
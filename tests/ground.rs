@@ -0,0 +1,99 @@
+use game_inventory::ground::{DropId, GroundItems, UnknownDrop};
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, JUNK_INST, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+fn torches(quant: u16) -> DefaultItemInstance<DefaultItem<'static>> {
+    DefaultItemInstance::new(Arc::new(TORCH.clone()), quant)
+}
+
+#[test]
+fn only_nearby_identical_stacks_merge() {
+    let mut ground = GroundItems::new(2.0, 100);
+    let pile = ground.drop_item(torches(10), [0.0, 0.0, 0.0]);
+    let far = ground.drop_item(torches(10), [3.0, 0.0, 0.0]);
+    let junk = ground.drop_item(JUNK_INST.clone().unwrap(), [0.0, 1.0, 0.0]);
+    let sword = ground.drop_item(SWORD_INST.clone().unwrap(), [0.0, 0.0, 1.0]);
+    let other_sword = ground.drop_item(SWORD_INST.clone().unwrap(), [0.0, 0.0, 1.0]);
+    assert_ne!(pile, far);
+    assert_ne!(pile, junk);
+    assert_ne!(sword, other_sword);
+    assert_eq!(ground.len(), 5);
+
+    assert_eq!(ground.drop_item(torches(5), [1.0, 1.0, 0.0]), pile);
+    assert_eq!(ground.get(pile).unwrap().item_instance.quant(), 15);
+    assert_eq!(ground.get(far).unwrap().item_instance.quant(), 10);
+    assert_eq!(ground.near([0.0; 3], 1.0).count(), 4);
+}
+
+#[test]
+fn full_piles_overflow_into_a_new_drop() {
+    let mut ground = GroundItems::new(1.0, 100);
+    let pile = ground.drop_item(torches(90), [0.0; 3]);
+    let rest = ground.drop_item(torches(30), [0.0; 3]);
+    assert_ne!(pile, rest);
+    assert_eq!(ground.get(pile).unwrap().item_instance.quant(), 100);
+    assert_eq!(ground.get(rest).unwrap().item_instance.quant(), 20);
+
+    // Fills up the second pile, since the first one is full.
+    assert_eq!(ground.drop_item(torches(30), [0.0; 3]), rest);
+    assert_eq!(ground.get(rest).unwrap().item_instance.quant(), 50);
+}
+
+#[test]
+fn picking_up_leaves_the_remainder() {
+    let mut ground = GroundItems::new(1.0, 100);
+    let pile = ground.drop_item(torches(80), [0.0; 3]);
+    let sword = ground.drop_item(SWORD_INST.clone().unwrap(), [0.0; 3]);
+    let mut inventory = vec![DefaultSlot::new(Some(torches(50)))];
+
+    assert_eq!(
+        ground
+            .pick_up(pile, &mut inventory)
+            .unwrap()
+            .unwrap()
+            .quant(),
+        30
+    );
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 100);
+    assert_eq!(ground.get(pile).unwrap().item_instance.quant(), 30);
+
+    let left = ground.pick_up(sword, &mut inventory).unwrap().unwrap();
+    assert_eq!(left.item().id(), SWORD.id());
+    inventory.push(DefaultSlot::new(None));
+    assert!(ground.pick_up(sword, &mut inventory).unwrap().is_none());
+    assert!(ground.get(sword).is_none());
+    assert_eq!(quant_in_inventory(&inventory, SWORD.id()), 1);
+    assert_eq!(
+        ground.pick_up(sword, &mut inventory).err(),
+        Some(UnknownDrop)
+    );
+    assert_eq!(
+        ground.pick_up(DropId(99), &mut inventory).err(),
+        Some(UnknownDrop)
+    );
+}
+
+#[test]
+fn drops_despawn_after_their_lifetime() {
+    let mut ground = GroundItems::new(1.0, 10);
+    let pile = ground.drop_item(torches(10), [0.0; 3]);
+    let sword = ground.drop_item(SWORD_INST.clone().unwrap(), [0.0; 3]);
+    assert!(ground.tick(6).is_empty());
+
+    // Merging resets the age of the pile, but not of the sword.
+    ground.drop_item(torches(10), [0.0; 3]);
+    let despawned = ground.tick(4);
+    assert_eq!(despawned.len(), 1);
+    assert_eq!(despawned[0].id, sword);
+    assert_eq!(ground.get(pile).unwrap().age, 4);
+
+    ground.set_lifetime(5);
+    let despawned = ground.tick(1);
+    assert_eq!(despawned[0].item_instance.quant(), 20);
+    assert!(ground.is_empty());
+    assert!(ground.remove(pile).is_none());
+}
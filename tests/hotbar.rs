@@ -0,0 +1,107 @@
+use game_inventory::hotbar::{Binding, Hotbar, NoSuchKey};
+use game_inventory::reservation::reserve;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, ReservableSlot, JUNK, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+fn torches(quant: u16) -> Option<DefaultItemInstance<DefaultItem<'static>>> {
+    Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant))
+}
+
+#[test]
+fn selection_wraps_around() {
+    let mut hotbar: Hotbar<&str, 3> = Hotbar::new();
+    hotbar.select_prev();
+    assert_eq!(hotbar.selected(), 2);
+    hotbar.select_next();
+    assert_eq!(hotbar.selected(), 0);
+    assert_eq!(hotbar.select(7), Err(NoSuchKey));
+    assert_eq!(hotbar.selected(), 0);
+
+    assert_eq!(hotbar.bind(1, Binding::Item("torch")), Ok(None));
+    assert_eq!(
+        hotbar.bind(1, Binding::Slot(4)),
+        Ok(Some(Binding::Item("torch")))
+    );
+    assert_eq!(hotbar.binding(1), Some(&Binding::Slot(4)));
+    assert_eq!(hotbar.unbind(1), Ok(Some(Binding::Slot(4))));
+    assert!(hotbar.binding(1).is_none());
+    assert!(hotbar.binding(9).is_none());
+    assert_eq!(hotbar.bind(3, Binding::Slot(0)), Err(NoSuchKey));
+    assert_eq!(hotbar.unbind(3), Err(NoSuchKey));
+}
+
+#[test]
+fn item_bindings_follow_the_item() {
+    let mut inventory = vec![
+        DefaultSlot::new(torches(2)),
+        DefaultSlot::new(SWORD_INST.clone()),
+        DefaultSlot::new(None),
+        DefaultSlot::new(torches(1)),
+    ];
+    let mut hotbar: Hotbar<_, 2> = Hotbar::new();
+    hotbar.bind(0, Binding::Item(TORCH.id())).unwrap();
+    hotbar.bind(1, Binding::Item(JUNK.id())).unwrap();
+
+    for expected in [Some(0), Some(0), Some(3), None] {
+        assert_eq!(hotbar.resolve(0, &inventory), expected);
+        let used = hotbar.use_key(0, &mut inventory);
+        assert_eq!(used.is_some(), expected.is_some());
+    }
+    assert!(inventory[0].item_instance().is_none());
+    assert!(inventory[3].item_instance().is_none());
+    assert!(hotbar.use_key(1, &mut inventory).is_none());
+}
+
+#[test]
+fn slot_bindings_stay_on_their_slot() {
+    let mut inventory = vec![
+        DefaultSlot::new(torches(1)),
+        DefaultSlot::new(torches(5)),
+        DefaultSlot::new(SWORD_INST.clone()),
+    ];
+    let mut hotbar: Hotbar<_, 3> = Hotbar::new();
+    hotbar.bind(0, Binding::Slot(0)).unwrap();
+    hotbar.bind(1, Binding::Slot(2)).unwrap();
+    hotbar.bind(2, Binding::Slot(10)).unwrap();
+
+    assert_eq!(hotbar.use_selected(&mut inventory).unwrap().quant(), 1);
+    assert_eq!(hotbar.resolve_selected(&inventory), Some(0));
+    assert!(hotbar.use_selected(&mut inventory).is_none());
+    assert_eq!(inventory[1].item_instance().unwrap().quant(), 5);
+
+    hotbar.select(1).unwrap();
+    let sword = hotbar.use_selected(&mut inventory).unwrap();
+    assert_eq!(sword.item().id(), SWORD.id());
+    assert!(inventory[2].item_instance().is_none());
+
+    hotbar.select(2).unwrap();
+    assert!(hotbar.resolve_selected(&inventory).is_none());
+    assert!(hotbar.use_selected(&mut inventory).is_none());
+}
+
+#[test]
+fn locked_and_reserved_slots_are_skipped() {
+    let mut inventory = vec![
+        ReservableSlot::new(torches(2)),
+        ReservableSlot::new(torches(3)),
+        ReservableSlot::new(torches(4)),
+    ];
+    inventory[0].locked = true;
+    let mut hotbar: Hotbar<_, 2> = Hotbar::new();
+    hotbar.bind(0, Binding::Item(TORCH.id())).unwrap();
+    hotbar.bind(1, Binding::Slot(0)).unwrap();
+    assert_eq!(hotbar.resolve(0, &inventory), Some(1));
+    assert_eq!(hotbar.resolve(1, &inventory), None);
+    assert!(hotbar.use_key(1, &mut inventory).is_none());
+
+    let reservation = reserve(&inventory[1..2], TORCH.id(), 3).unwrap();
+    assert_eq!(hotbar.resolve(0, &inventory), Some(2));
+    assert_eq!(hotbar.use_key(0, &mut inventory).unwrap().quant(), 1);
+    assert_eq!(inventory[1].item_instance().unwrap().quant(), 3);
+    assert_eq!(inventory[2].item_instance().unwrap().quant(), 3);
+    drop(reservation);
+    assert_eq!(hotbar.resolve(0, &inventory), Some(1));
+}
@@ -0,0 +1,136 @@
+use game_inventory::helpers::{
+    add_to_inventory, empty_quant_in_inventory, inventory_contains_item_type, quant_in_inventory,
+    remove_from_inventory,
+};
+use game_inventory::indexed_inventory::IndexedInventory;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, ReservableSlot, JUNK, SWORD, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Itm = DefaultItem<'static>;
+type InvSlot = DefaultSlot<'static, Itm, Inst>;
+
+fn inst(item: &DefaultItem<'static>, quant: u16) -> Inst {
+    DefaultItemInstance::new(Arc::new(item.clone()), quant)
+}
+
+/// A tiny deterministic generator so the test does not need any dependencies.
+fn next(seed: &mut u64) -> u64 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 7;
+    *seed ^= *seed << 17;
+    *seed
+}
+
+/// `add_to_inventory` may lay out stacks differently, so only totals are compared against it.
+/// The index itself is checked against the slots it indexes.
+fn assert_matches(indexed: &IndexedInventory<Itm, Inst, InvSlot>, plain: &[InvSlot]) {
+    let slots: Vec<InvSlot> = indexed
+        .iter()
+        .map(|slot| DefaultSlot::new(slot.item_instance()))
+        .collect();
+    for item in [&TORCH, &JUNK, &SWORD] {
+        assert_eq!(
+            indexed.quant(item.id()),
+            quant_in_inventory(plain, item.id())
+        );
+        assert_eq!(
+            indexed.quant(item.id()),
+            quant_in_inventory(&slots, item.id())
+        );
+        assert_eq!(
+            indexed.contains_item_type(item.id()),
+            inventory_contains_item_type(&slots, item.id())
+        );
+    }
+    assert_eq!(indexed.empty_quant(), empty_quant_in_inventory(&slots));
+}
+
+#[test]
+fn matches_inventory_management() {
+    let mut seed = 0x2545F4914F6CDD1D;
+    let mut plain: Vec<InvSlot> = (0..40).map(|_| DefaultSlot::new(None)).collect();
+    let mut indexed = IndexedInventory::new((0..40).map(|_| DefaultSlot::new(None)).collect());
+    for _ in 0..2000 {
+        let item = [&TORCH, &JUNK, &SWORD][(next(&mut seed) % 3) as usize];
+        let quant = if item.stackable() {
+            (next(&mut seed) % 100) as u16 + 1
+        } else {
+            1
+        };
+        if next(&mut seed).is_multiple_of(2) {
+            let a = add_to_inventory(&mut plain, inst(item, quant)).map(|ii| ii.quant());
+            let b = indexed.add(inst(item, quant)).map(|ii| ii.quant());
+            assert_eq!(a, b);
+        } else {
+            let a = remove_from_inventory(&mut plain, inst(item, quant)).map(|ii| ii.quant());
+            let b = indexed.remove(inst(item, quant)).map(|ii| ii.quant());
+            assert_eq!(a, b);
+        }
+        assert_matches(&indexed, &plain);
+    }
+}
+
+#[test]
+fn slot_changes_update_the_index() {
+    let mut indexed = IndexedInventory::new(vec![
+        DefaultSlot::new(Some(inst(&TORCH, 50))),
+        DefaultSlot::new(None),
+    ]);
+    indexed.set_item_instance(1, &Some(inst(&TORCH, 20)));
+    assert_eq!(indexed.quant(TORCH.id()), 70);
+    assert_eq!(indexed.empty_quant(), 0);
+
+    let held = indexed.transfer(0, Some(inst(&JUNK, 5)), "");
+    assert_eq!(held.unwrap().quant(), 50);
+    assert_eq!(indexed.quant(TORCH.id()), 20);
+    assert_eq!(indexed.quant(JUNK.id()), 5);
+
+    indexed.update(1, |slot| slot.set_item_instance(&None));
+    assert!(!indexed.contains_item_type(TORCH.id()));
+    assert!(indexed.contains_item(&inst(&JUNK, 5)));
+    assert!(!indexed.contains_item(&inst(&JUNK, 6)));
+    assert!(indexed[1].item_instance().is_none());
+    assert_eq!(indexed.into_inner().len(), 2);
+}
+
+#[test]
+fn locked_slots_are_left_alone() {
+    let mut indexed = IndexedInventory::new(vec![
+        ReservableSlot::new(None),
+        ReservableSlot::new(Some(inst(&TORCH, 10))),
+        ReservableSlot::new(Some(inst(&TORCH, 90))),
+        ReservableSlot::new(None),
+    ]);
+    indexed.update(0, |slot| slot.locked = true);
+    indexed.update(2, |slot| slot.locked = true);
+    assert_eq!(indexed.empty_quant(), 2);
+    assert!(indexed.can_add_item(&inst(&TORCH, 190)));
+    assert!(!indexed.can_add_item(&inst(&TORCH, 191)));
+
+    assert_eq!(indexed.add(inst(&TORCH, 200)).unwrap().quant(), 10);
+    assert!(indexed[0].item_instance().is_none());
+    assert_eq!(indexed[2].item_instance().unwrap().quant(), 90);
+    assert!(!indexed.can_add_item(&inst(&SWORD, 1)));
+
+    indexed.update(0, |slot| slot.locked = false);
+    assert_eq!(indexed.empty_quant(), 1);
+    assert!(indexed.add(inst(&SWORD, 1)).is_none());
+    assert!(indexed[0].item_instance().is_some());
+}
+
+#[test]
+fn big_empty_chests_stay_fast() {
+    // Copying or walking every empty slot on each call would take minutes here.
+    let mut indexed = IndexedInventory::new((0..200_000).map(|_| DefaultSlot::new(None)).collect());
+    for _ in 0..20_000 {
+        assert!(indexed.can_add_item(&inst(&TORCH, 150)));
+        assert!(indexed.add(inst(&TORCH, 150)).is_none());
+        assert!(indexed.remove(inst(&TORCH, 150)).is_none());
+    }
+    assert_eq!(indexed.empty_quant(), 200_000);
+    assert!(indexed.can_add_item(&inst(&TORCH, 50_000)));
+}
@@ -0,0 +1,58 @@
+use game_inventory::helpers::{
+    add_to_inventory, can_add_item_to_inventory, empty_quant_in_inventory, quant_in_inventory,
+    remove_from_inventory,
+};
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, SWORD_INST, TORCH, TORCH_INST,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+type InvSlot =
+    DefaultSlot<'static, DefaultItem<'static>, DefaultItemInstance<DefaultItem<'static>>>;
+
+#[test]
+fn empty_inventories_keep_the_item() {
+    let mut inventory: Vec<InvSlot> = Vec::with_capacity(8);
+    let res = add_to_inventory(&mut inventory, TORCH_INST.clone().unwrap());
+    assert_eq!(res.unwrap().quant(), 23);
+
+    let mut inventory: [InvSlot; 0] = [];
+    assert!(add_to_inventory(&mut inventory, SWORD_INST.clone().unwrap()).is_some());
+    assert!(!can_add_item_to_inventory(
+        &inventory,
+        SWORD_INST.clone().unwrap()
+    ));
+}
+
+#[test]
+fn sub_ranges_only_touch_their_slots() {
+    let mut inventory: Vec<InvSlot> = (0..6).map(|_| DefaultSlot::new(None)).collect();
+    let (hotbar, backpack) = inventory.split_at_mut(2);
+    assert!(add_to_inventory(backpack, TORCH_INST.clone().unwrap()).is_none());
+    assert_eq!(empty_quant_in_inventory(hotbar), 2);
+    assert_eq!(quant_in_inventory(&inventory[2..], TORCH.id()), 23);
+
+    for _ in 0..2 {
+        assert!(add_to_inventory(&mut inventory[..2], SWORD_INST.clone().unwrap()).is_none());
+    }
+    assert!(add_to_inventory(&mut inventory[..2], SWORD_INST.clone().unwrap()).is_some());
+    assert_eq!(empty_quant_in_inventory(&inventory), 3);
+}
+
+#[test]
+fn vec_deques_work_through_make_contiguous() {
+    let mut inventory: VecDeque<InvSlot> = VecDeque::new();
+    inventory.push_back(DefaultSlot::new(None));
+    inventory.push_front(DefaultSlot::new(TORCH_INST.clone()));
+    add_to_inventory(inventory.make_contiguous(), TORCH_INST.clone().unwrap());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 46);
+
+    let torches = DefaultItemInstance::new(Arc::new(TORCH.clone()), 40);
+    assert!(remove_from_inventory(inventory.make_contiguous(), torches).is_none());
+    assert_eq!(
+        quant_in_inventory(inventory.make_contiguous(), TORCH.id()),
+        6
+    );
+}
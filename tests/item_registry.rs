@@ -0,0 +1,197 @@
+#![cfg(all(feature = "json", feature = "ron", feature = "toml"))]
+use game_inventory::item_registry::{ItemRegistry, RegistryError};
+use game_inventory::samples::{DataItem, DefaultItemInstance, DefaultSlot, DurableItemInstance};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct Extra {
+    #[serde(default)]
+    damage: u32,
+}
+
+fn write(name: &str, contents: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn load_every_format() {
+    let json = write(
+        "load_every_format.json",
+        r#"{ "items": [{ "id": "torch", "max_quantity": 100 }] }"#,
+    );
+    let ron = write(
+        "load_every_format.ron",
+        r#"(items: [{ "id": "sword", "stackable": false, "damage": 7 }])"#,
+    );
+    let toml = write(
+        "load_every_format.toml",
+        "[[items]]\nid = \"junk\"\nmax_quantity = 50\n",
+    );
+    let mut registry: ItemRegistry<DataItem<Extra>> = ItemRegistry::new();
+    registry.load_file(&json).unwrap();
+    registry.load_file(&ron).unwrap();
+    registry.load_file(&toml).unwrap();
+    assert_eq!(registry.len(), 3);
+    assert_eq!(registry.get(&"torch".to_owned()).unwrap().max_quant(), 100);
+    assert_eq!(registry.get(&"sword".to_owned()).unwrap().extra.damage, 7);
+    assert!(!registry.get(&"sword".to_owned()).unwrap().stackable());
+    assert_eq!(registry.get(&"junk".to_owned()).unwrap().max_quant(), 50);
+}
+
+#[test]
+fn duplicates_across_files() {
+    let a = write(
+        "duplicates_across_files_a.json",
+        r#"{ "items": [{ "id": "torch", "max_quantity": 100 }] }"#,
+    );
+    let b = write(
+        "duplicates_across_files_b.toml",
+        "[[items]]\nid = \"junk\"\nmax_quantity = 50\n[[items]]\nid = \"torch\"\nmax_quantity = 10\n",
+    );
+    let mut registry: ItemRegistry<DataItem> = ItemRegistry::new();
+    registry.load_file(&a).unwrap();
+    assert!(matches!(
+        registry.load_file(&b),
+        Err(RegistryError::DuplicateId(id)) if id == "torch"
+    ));
+    assert_eq!(registry.len(), 1);
+    assert!(!registry.contains(&"junk".to_owned()));
+}
+
+#[test]
+fn bad_files() {
+    let mut registry: ItemRegistry<DataItem> = ItemRegistry::new();
+    let unknown = write("bad_files.txt", "");
+    assert!(matches!(
+        registry.load_file(&unknown),
+        Err(RegistryError::UnknownFormat(_))
+    ));
+    let missing = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("bad_files_missing.json");
+    assert!(matches!(
+        registry.load_file(&missing),
+        Err(RegistryError::Io(..))
+    ));
+    let malformed = write("bad_files.json", r#"{ "items": [{ "id": 5 }] }"#);
+    assert!(matches!(
+        registry.load_file(&malformed),
+        Err(RegistryError::Parse(_))
+    ));
+    assert!(registry.is_empty());
+}
+
+#[test]
+fn hot_reload() {
+    let path = write(
+        "hot_reload.json",
+        r#"{ "items": [{ "id": "torch", "max_quantity": 100 }, { "id": "junk", "max_quantity": 100 }] }"#,
+    );
+    let mut registry: ItemRegistry<DataItem> = ItemRegistry::new();
+    registry.load_file(&path).unwrap();
+    let mut inventory = vec![
+        DefaultSlot::new(Some(DefaultItemInstance::new(
+            registry.get(&"torch".to_owned()).unwrap(),
+            80,
+        ))),
+        DefaultSlot::new(Some(DefaultItemInstance::new(
+            registry.get(&"junk".to_owned()).unwrap(),
+            3,
+        ))),
+    ];
+
+    write(
+        "hot_reload.json",
+        r#"{ "items": [{ "id": "torch", "max_quantity": 64 }] }"#,
+    );
+    let removed = registry.reload().unwrap();
+    assert_eq!(removed, vec!["junk".to_owned()]);
+    // There is no room for the torches above the new stack size.
+    let over = registry.rebind_inventory(&mut inventory);
+    assert_eq!(over.len(), 1);
+    assert_eq!(over[0].quant(), 16);
+    let torch = inventory[0].item_instance().unwrap();
+    assert_eq!(torch.item().max_quant(), 64);
+    assert_eq!(torch.quant(), 64);
+    // Removed items keep their last definition.
+    assert_eq!(inventory[1].item_instance().unwrap().item().id(), "junk");
+
+    // A broken reload leaves the current definitions in place.
+    write(
+        "hot_reload.json",
+        r#"{ "items": [{ "id": "torch", "max_quantity": 1 }] }"#,
+    );
+    assert!(matches!(
+        registry.reload(),
+        Err(RegistryError::BadStackSize(_))
+    ));
+    assert_eq!(registry.get(&"torch".to_owned()).unwrap().max_quant(), 64);
+}
+
+#[test]
+fn rebinding_keeps_instance_data() {
+    let torch: DataItem = DataItem {
+        id: "torch".to_owned(),
+        stackable: true,
+        max_quantity: 100,
+        extra: (),
+    };
+    let mut registry = ItemRegistry::from_items([torch.clone()]).unwrap();
+    let mut worn = DurableItemInstance::new(registry.get(&torch.id).unwrap(), 30);
+    worn.condition = 0.5;
+    let mut inventory = vec![DefaultSlot::new(Some(worn.clone())), DefaultSlot::new(None)];
+
+    registry
+        .replace_all([DataItem {
+            max_quantity: 20,
+            ..torch
+        }])
+        .unwrap();
+    assert_eq!(registry.rebind(worn).condition, 0.5);
+    assert!(registry.rebind_inventory(&mut inventory).is_empty());
+    for (slot, quant) in inventory.iter().zip([20, 10]) {
+        let ii = slot.item_instance().unwrap();
+        assert_eq!(ii.quant(), quant);
+        assert_eq!(ii.condition, 0.5);
+        assert_eq!(ii.item().max_quant(), 20);
+    }
+}
+
+#[test]
+fn registered_items_survive_a_reload() {
+    let sword: DataItem = DataItem {
+        id: "sword".to_owned(),
+        stackable: false,
+        max_quantity: 1,
+        extra: (),
+    };
+    let mut registry = ItemRegistry::from_items([sword.clone()]).unwrap();
+    assert!(registry.reload().unwrap().is_empty());
+    assert!(registry.contains(&sword.id));
+
+    let path = write(
+        "registered_items_survive_a_reload.json",
+        r#"{ "items": [{ "id": "torch", "max_quantity": 100 }] }"#,
+    );
+    registry.load_file(&path).unwrap();
+    write(
+        "registered_items_survive_a_reload.json",
+        r#"{ "items": [{ "id": "junk", "max_quantity": 100 }] }"#,
+    );
+    assert_eq!(registry.reload().unwrap(), vec!["torch".to_owned()]);
+    assert!(registry.contains(&sword.id));
+    assert!(registry.contains(&"junk".to_owned()));
+
+    // Files can not redefine items that were registered another way.
+    write(
+        "registered_items_survive_a_reload.json",
+        r#"{ "items": [{ "id": "sword", "stackable": false }] }"#,
+    );
+    assert!(matches!(
+        registry.reload(),
+        Err(RegistryError::DuplicateId(id)) if id == "sword"
+    ));
+    assert!(registry.contains(&"junk".to_owned()));
+}
@@ -0,0 +1,171 @@
+use game_inventory::helpers::{add_to_inventory, remove_from_inventory};
+use game_inventory::journal::Journal;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, JUNK_INST, TORCH, TORCH_INST,
+};
+use game_inventory::snapshot::Snapshot;
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+fn clock() -> u64 {
+    42
+}
+
+#[test]
+fn keeps_modified_flags() {
+    let mut inventory = vec![
+        DefaultSlot::new(TORCH_INST.clone()),
+        DefaultSlot::new(JUNK_INST.clone()),
+        DefaultSlot::new(None),
+    ];
+    inventory[0].modified = true;
+    let mut journal: Journal<DefaultItem, DefaultItemInstance<DefaultItem>> =
+        Journal::with_clock(clock);
+    journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+        add_to_inventory(inventory, JUNK_INST.clone().unwrap())
+    });
+    assert!(inventory[0].modified);
+    assert!(inventory[1].modified);
+    assert!(inventory[2].modified);
+    inventory[1].modified = false;
+    journal.record(&mut inventory, "player", "nothing", |_| ());
+    assert!(!inventory[1].modified);
+    assert_eq!(journal.len(), 2);
+    assert!(journal.entries().iter().all(|entry| entry.timestamp == 42));
+}
+
+#[test]
+fn replay_reconstructs_every_state() {
+    let mut inventory = vec![
+        DefaultSlot::new(TORCH_INST.clone()),
+        DefaultSlot::new(None),
+        DefaultSlot::new(JUNK_INST.clone()),
+    ];
+    let initial = Snapshot::take(&inventory);
+    let mut journal = Journal::new();
+    let mut states = vec![];
+    for quant in [50, 90, 10, 200] {
+        journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+            add_to_inventory(
+                inventory,
+                DefaultItemInstance::new(Arc::new(TORCH.clone()), quant),
+            )
+        });
+        states.push((journal.len(), Snapshot::take(&inventory)));
+        journal.record(
+            &mut inventory,
+            "npc",
+            "remove_from_inventory",
+            |inventory| {
+                remove_from_inventory(
+                    inventory,
+                    DefaultItemInstance::new(Arc::new(TORCH.clone()), quant / 2),
+                )
+            },
+        );
+        states.push((journal.len(), Snapshot::take(&inventory)));
+    }
+    for (len, state) in states {
+        let replayed = journal.replay_to(&initial, len);
+        assert_eq!(replayed.len(), state.len());
+        for (a, b) in replayed.iter().zip(state.iter()) {
+            assert_eq!(
+                a.as_ref().map(|a| a.item().id()),
+                b.as_ref().map(|b| b.item().id())
+            );
+            assert_eq!(a.as_ref().map(|a| a.quant()), b.as_ref().map(|b| b.quant()));
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn write_and_read() {
+    use game_inventory::samples::DataItem;
+    use std::fs::OpenOptions;
+    use std::io::BufReader;
+
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("journal.jsonl");
+    let _ = std::fs::remove_file(&path);
+    let torch: Arc<DataItem> = Arc::new(DataItem {
+        id: "torch".to_owned(),
+        stackable: true,
+        max_quantity: 100,
+        extra: (),
+    });
+    let mut inventory = vec![DefaultSlot::new(None), DefaultSlot::new(None)];
+    let mut journal = Journal::new();
+    for _ in 0..3 {
+        journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+            add_to_inventory(inventory, DefaultItemInstance::new(torch.clone(), 40))
+        });
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .unwrap();
+        journal.write_new(&mut file).unwrap();
+    }
+    assert_eq!(journal.len(), 4);
+
+    let read: Journal<DataItem, DefaultItemInstance<DataItem>> =
+        Journal::read(BufReader::new(std::fs::File::open(&path).unwrap())).unwrap();
+    assert_eq!(read.len(), 4);
+    assert_eq!(read.for_item("torch".to_owned()).count(), 4);
+    let replayed = read.replay(&[None, None]);
+    assert_eq!(replayed[0].as_ref().unwrap().quant(), 100);
+    assert_eq!(replayed[1].as_ref().unwrap().quant(), 20);
+
+    let mut read: Journal<DataItem, DefaultItemInstance<DataItem>> =
+        Journal::read_with_clock(BufReader::new(std::fs::File::open(&path).unwrap()), clock)
+            .unwrap();
+    read.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+        add_to_inventory(inventory, DefaultItemInstance::new(torch.clone(), 1))
+    });
+    assert_eq!(read.entries()[4].timestamp, 42);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn failed_writes_are_retried_without_duplicates() {
+    use std::io::Write;
+
+    /// Accepts a number of writes, then fails until it is given more.
+    struct Flaky {
+        written: Vec<u8>,
+        writes_left: usize,
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.writes_left == 0 {
+                return Err(std::io::Error::other("disk full"));
+            }
+            self.writes_left -= 1;
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut inventory = vec![DefaultSlot::new(None), DefaultSlot::new(None)];
+    let mut journal: Journal<DefaultItem, DefaultItemInstance<DefaultItem>> = Journal::new();
+    for _ in 0..3 {
+        journal.record(&mut inventory, "player", "add_to_inventory", |inventory| {
+            add_to_inventory(inventory, TORCH_INST.clone().unwrap())
+        });
+    }
+    let mut writer = Flaky {
+        written: Vec::new(),
+        writes_left: 2,
+    };
+    assert!(journal.write_new(&mut writer).is_err());
+    writer.writes_left = 10;
+    journal.write_new(&mut writer).unwrap();
+    journal.write_new(&mut writer).unwrap();
+    let lines = String::from_utf8(writer.written).unwrap();
+    assert_eq!(lines.lines().count(), 3);
+}
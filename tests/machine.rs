@@ -0,0 +1,181 @@
+use game_inventory::machine::{Machine, MachineProgress, Recipe, RecipeBook};
+use game_inventory::reservation::reserve;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, ReservableSlot, JUNK, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Smelter =
+    Machine<DefaultItem<'static>, Inst, DefaultSlot<'static, DefaultItem<'static>, Inst>>;
+
+fn torches(quant: u16) -> Option<Inst> {
+    Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant))
+}
+
+fn junk(quant: u16) -> Option<Inst> {
+    Some(DefaultItemInstance::new(Arc::new(JUNK.clone()), quant))
+}
+
+/// Junk smelts into torches, torches burn.
+fn book() -> RecipeBook<DefaultItem<'static>, Inst> {
+    let mut book = RecipeBook::new();
+    book.add_recipe(Recipe {
+        input: JUNK.id(),
+        input_quant: 2,
+        output: torches(30).unwrap(),
+        ticks: 5,
+    });
+    book.add_recipe(Recipe {
+        input: SWORD.id(),
+        input_quant: 1,
+        output: junk(1).unwrap(),
+        ticks: 3,
+    });
+    book.add_fuel(TORCH.id(), 100);
+    book
+}
+
+fn quant(slot: &DefaultSlot<'static, DefaultItem<'static>, Inst>) -> u16 {
+    slot.item_instance().map_or(0, |ii| ii.quant())
+}
+
+#[test]
+fn pauses_while_the_output_is_full() {
+    let book = book();
+    let mut smelter = Smelter::new();
+    smelter.input.set_item_instance(&junk(10));
+    smelter.fuel.set_item_instance(&torches(1));
+
+    assert_eq!(smelter.tick(&book, 15), 3);
+    assert_eq!(quant(&smelter.output), 90);
+    assert_eq!(quant(&smelter.input), 4);
+    assert!(smelter.fuel.item_instance().is_none());
+    assert!(smelter.is_burning());
+
+    // 30 more torches do not fit onto the 90 in the output.
+    assert_eq!(smelter.tick(&book, 20), 0);
+    assert_eq!(smelter.progress().elapsed, 0);
+    assert_eq!(quant(&smelter.input), 4);
+    assert_eq!(smelter.progress().burn_left, 65);
+
+    smelter.output.set_item_instance(&torches(10));
+    assert_eq!(smelter.tick(&book, 5), 1);
+    assert_eq!(quant(&smelter.output), 40);
+}
+
+#[test]
+fn only_takes_fuel_when_needed() {
+    let book = book();
+    let mut smelter = Smelter::new();
+    smelter.fuel.set_item_instance(&torches(2));
+    assert_eq!(smelter.tick(&book, 10), 0);
+    assert_eq!(quant(&smelter.fuel), 2);
+
+    smelter.input.set_item_instance(&junk(1));
+    assert_eq!(smelter.tick(&book, 10), 0);
+    assert_eq!(quant(&smelter.fuel), 2);
+
+    smelter.input.set_item_instance(&junk(2));
+    assert_eq!(smelter.tick(&book, 10), 1);
+    assert_eq!(quant(&smelter.fuel), 1);
+    assert_eq!(smelter.progress().burn_time, 100);
+    assert_eq!(smelter.progress().burn_left, 90);
+
+    // Burning fuel keeps burning with nothing to work on.
+    assert_eq!(smelter.tick(&book, 95), 0);
+    assert!(!smelter.is_burning());
+    assert_eq!(quant(&smelter.fuel), 1);
+}
+
+#[test]
+fn locked_and_reserved_items_are_not_used() {
+    let book = book();
+    let mut smelter: Machine<_, _, ReservableSlot<_, _>> = Machine::new();
+    smelter.input.set_item_instance(&junk(3));
+    smelter.fuel.set_item_instance(&torches(1));
+    let reservation = reserve(std::slice::from_ref(&smelter.input), JUNK.id(), 2).unwrap();
+    assert_eq!(smelter.tick(&book, 10), 0);
+    assert_eq!(smelter.input.item_instance().unwrap().quant(), 3);
+    assert_eq!(smelter.fuel.item_instance().unwrap().quant(), 1);
+
+    drop(reservation);
+    smelter.fuel.locked = true;
+    assert_eq!(smelter.tick(&book, 10), 0);
+    assert_eq!(smelter.fuel.item_instance().unwrap().quant(), 1);
+
+    smelter.fuel.locked = false;
+    smelter.output.locked = true;
+    assert_eq!(smelter.tick(&book, 10), 0);
+    assert!(smelter.output.item_instance().is_none());
+
+    smelter.output.locked = false;
+    assert_eq!(smelter.tick(&book, 5), 1);
+    assert_eq!(smelter.input.item_instance().unwrap().quant(), 1);
+    assert!(smelter.fuel.item_instance().is_none());
+}
+
+#[test]
+fn changing_the_input_starts_over() {
+    let book = book();
+    let mut smelter = Smelter::new();
+    smelter.input.set_item_instance(&junk(2));
+    smelter.fuel.set_item_instance(&torches(1));
+    smelter.tick(&book, 4);
+    assert_eq!(smelter.progress().recipe, Some(JUNK.id()));
+    assert_eq!(smelter.progress().elapsed, 4);
+
+    smelter.input.set_item_instance(&SWORD_INST.clone());
+    smelter.tick(&book, 2);
+    assert_eq!(smelter.progress().recipe, Some(SWORD.id()));
+    assert_eq!(smelter.progress().elapsed, 2);
+
+    // The junk would stack onto torches, so the sword waits.
+    smelter.output.set_item_instance(&torches(1));
+    assert_eq!(smelter.tick(&book, 5), 0);
+    smelter.output.set_item_instance(&None);
+    assert_eq!(smelter.tick(&book, 1), 1);
+    assert!(smelter.input.item_instance().is_none());
+    assert_eq!(quant(&smelter.output), 1);
+    assert_eq!(smelter.progress().elapsed, 0);
+}
+
+#[test]
+fn progress_can_be_restored() {
+    let book = book();
+    let mut smelter = Smelter::new();
+    smelter.input.set_item_instance(&junk(2));
+    smelter.fuel.set_item_instance(&torches(1));
+    smelter.tick(&book, 3);
+    let saved = smelter.progress().clone();
+    assert_eq!(
+        saved,
+        MachineProgress {
+            recipe: Some(JUNK.id()),
+            elapsed: 3,
+            burn_left: 97,
+            burn_time: 100,
+        }
+    );
+
+    let mut loaded = Smelter::new();
+    loaded.input.set_item_instance(&junk(2));
+    loaded.restore(saved);
+    assert_eq!(loaded.tick(&book, 2), 1);
+    assert_eq!(quant(&loaded.output), 30);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn progress_survives_serialization() {
+    let progress = MachineProgress {
+        recipe: Some("junk".to_string()),
+        elapsed: 12,
+        burn_left: 40,
+        burn_time: 80,
+    };
+    let json = serde_json::to_string(&progress).unwrap();
+    let loaded: MachineProgress<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, progress);
+}
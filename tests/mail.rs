@@ -0,0 +1,136 @@
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::mail::{MailError, ParcelId, PostOffice};
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, JUNK, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Inventory = Vec<DefaultSlot<'static, DefaultItem<'static>, Inst>>;
+
+fn stack(item: &DefaultItem<'static>, quant: u16) -> Option<Inst> {
+    Some(DefaultItemInstance::new(Arc::new(item.clone()), quant))
+}
+
+fn inventory(slots: Vec<Option<Inst>>) -> Inventory {
+    slots.into_iter().map(DefaultSlot::new).collect()
+}
+
+#[test]
+fn attachments_are_checked_first() {
+    let mut post: PostOffice<&str, Inst> = PostOffice::new(100);
+    let mut alice = inventory(vec![stack(&TORCH, 30), SWORD_INST.clone(), None]);
+    assert_eq!(
+        post.send("alice", "bob", &mut alice, &[], 0),
+        Err(MailError::Empty)
+    );
+    assert_eq!(
+        post.send("alice", "bob", &mut alice, &[(1, 1), (0, 31)], 0),
+        Err(MailError::NotEnough(0))
+    );
+    assert_eq!(
+        post.send("alice", "bob", &mut alice, &[(0, 10), (0, 10)], 0),
+        Err(MailError::NotEnough(0))
+    );
+    assert_eq!(
+        post.send("alice", "bob", &mut alice, &[(2, 1)], 0),
+        Err(MailError::NotEnough(2))
+    );
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 30);
+    assert_eq!(quant_in_inventory(&alice, SWORD.id()), 1);
+    assert!(post.is_empty());
+
+    let id = post
+        .send("alice", "bob", &mut alice, &[(1, 1), (0, 30)], 5)
+        .unwrap();
+    assert!(alice.iter().all(|slot| slot.item_instance().is_none()));
+    let parcel = post.get(id).unwrap();
+    assert_eq!(parcel.items.len(), 2);
+    assert_eq!(parcel.expires_at, Some(105));
+}
+
+#[test]
+fn partial_claims_keep_the_rest() {
+    let mut post = PostOffice::new(100);
+    let mut alice = inventory(vec![stack(&TORCH, 80), stack(&JUNK, 50)]);
+    let id = post
+        .send("alice", "bob", &mut alice, &[(0, 80), (1, 50)], 0)
+        .unwrap();
+
+    let mut bob = inventory(vec![stack(&TORCH, 40), None]);
+    assert_eq!(
+        post.claim(&"alice", id, &mut bob),
+        Err(MailError::NotRecipient)
+    );
+    assert_eq!(post.claim(&"bob", id, &mut bob), Ok(1));
+    assert_eq!(quant_in_inventory(&bob, TORCH.id()), 120);
+    assert_eq!(post.get(id).unwrap().items[0].item().id(), JUNK.id());
+
+    bob.push(DefaultSlot::new(None));
+    assert_eq!(post.claim(&"bob", id, &mut bob), Ok(0));
+    assert_eq!(quant_in_inventory(&bob, JUNK.id()), 50);
+    assert_eq!(
+        post.claim(&"bob", id, &mut bob),
+        Err(MailError::NoSuchParcel)
+    );
+}
+
+#[test]
+fn unclaimed_parcels_go_back() {
+    let mut post = PostOffice::new(100);
+    let mut alice = inventory(vec![stack(&TORCH, 10), stack(&JUNK, 10)]);
+    let first = post
+        .send("alice", "bob", &mut alice, &[(0, 10)], 0)
+        .unwrap();
+    let second = post
+        .send("alice", "bob", &mut alice, &[(1, 10)], 50)
+        .unwrap();
+
+    assert!(post.expire(99).is_empty());
+    assert_eq!(post.expire(120), vec![first]);
+    assert_eq!(post.inbox(&"bob").count(), 1);
+    let returned = post.get(first).unwrap();
+    assert!(returned.returned);
+    assert_eq!(returned.recipient, "alice");
+
+    // Returned parcels wait for the sender forever.
+    assert_eq!(post.expire(1000), vec![second]);
+    assert!(post.expire(u64::MAX).is_empty());
+    assert_eq!(post.claim(&"alice", first, &mut alice), Ok(0));
+    assert_eq!(post.claim(&"alice", second, &mut alice), Ok(0));
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 10);
+    assert_eq!(quant_in_inventory(&alice, JUNK.id()), 10);
+    assert_ne!(first, ParcelId(second.0));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn file_store_keeps_the_post_office() {
+    use game_inventory::mail::{FileStore, MailStore};
+    use game_inventory::samples::DataItem;
+
+    type DataInst = DefaultItemInstance<DataItem>;
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("mail.json");
+    let _ = std::fs::remove_file(&path);
+    let mut store = FileStore::new(&path);
+    let empty: Option<PostOffice<u32, DataInst>> = store.load().unwrap();
+    assert!(empty.is_none());
+
+    let torch = Arc::new(DataItem {
+        id: "torch".to_owned(),
+        stackable: true,
+        max_quantity: 100,
+        extra: (),
+    });
+    let mut alice = vec![DefaultSlot::new(Some(DefaultItemInstance::new(torch, 10)))];
+    let mut post = PostOffice::new(100);
+    let id = post.send(1, 2, &mut alice, &[(0, 4)], 0).unwrap();
+    store.save(&post).unwrap();
+
+    let mut loaded: PostOffice<u32, DataInst> = store.load().unwrap().unwrap();
+    let mut bob = vec![DefaultSlot::new(None)];
+    assert_eq!(loaded.claim(&2, id, &mut bob), Ok(0));
+    assert_eq!(quant_in_inventory(&bob, "torch".to_owned()), 4);
+    assert_ne!(loaded.send(1, 2, &mut alice, &[(0, 1)], 0), Ok(id));
+}
@@ -0,0 +1,194 @@
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::market::{Market, MarketError, MarketEvent, OrderId, OrderSide};
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, JUNK, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Inventory = Vec<DefaultSlot<'static, DefaultItem<'static>, Inst>>;
+
+fn stack(item: &DefaultItem<'static>, quant: u16) -> Option<Inst> {
+    Some(DefaultItemInstance::new(Arc::new(item.clone()), quant))
+}
+
+fn inventory(slots: Vec<Option<Inst>>) -> Inventory {
+    slots.into_iter().map(DefaultSlot::new).collect()
+}
+
+#[test]
+fn best_price_then_oldest_first() {
+    let mut market = Market::new(0);
+    let mut seller = inventory(vec![
+        stack(&TORCH, 10),
+        stack(&TORCH, 10),
+        stack(&TORCH, 10),
+    ]);
+    let expensive = market.sell("a", &mut seller, 0, 10, 9).unwrap();
+    let first = market.sell("b", &mut seller, 1, 10, 7).unwrap();
+    let second = market.sell("c", &mut seller, 2, 10, 7).unwrap();
+    assert!(seller.iter().all(|slot| slot.item_instance().is_none()));
+
+    let mut wallet = 200;
+    market.buy("d", &mut wallet, TORCH.id(), 15, 8).unwrap();
+    assert_eq!(wallet, 80);
+    assert!(market.order(first).is_none());
+    assert_eq!(market.order(second).unwrap().quant, 5);
+    assert_eq!(market.order(expensive).unwrap().quant, 10);
+    assert_eq!(market.withdraw(&"b"), 70);
+    assert_eq!(market.withdraw(&"c"), 35);
+    // Paid 7 instead of 8 for all 15.
+    assert_eq!(market.withdraw(&"d"), 15);
+}
+
+#[test]
+fn partial_fills_rest_in_the_book() {
+    let mut market = Market::new(1000);
+    let mut wallet = 1000;
+    let buy = market.buy("bob", &mut wallet, JUNK.id(), 30, 20).unwrap();
+    assert_eq!(wallet, 400);
+
+    let mut alice = inventory(vec![stack(&JUNK, 50)]);
+    let sell = market.sell("alice", &mut alice, 0, 12, 15).unwrap();
+    // Sold at the buyer's price, since the buy order was there first.
+    assert!(market.order(sell).is_none());
+    assert_eq!(market.order(buy).unwrap().quant, 18);
+    assert_eq!(quant_in_inventory(&alice, JUNK.id()), 38);
+    assert_eq!(market.withdraw(&"alice"), 216);
+    assert_eq!(market.fees(), 24);
+    assert_eq!(market.account(&"bob").unwrap().items[0].quant(), 12);
+
+    // Too expensive for the rest of the buy order.
+    let sell = market.sell("alice", &mut alice, 0, 38, 21).unwrap();
+    assert_eq!(market.order(sell).unwrap().quant, 38);
+    assert_eq!(
+        market
+            .orders(&JUNK.id())
+            .map(|order| order.side)
+            .collect::<Vec<_>>(),
+        vec![OrderSide::Buy, OrderSide::Sell]
+    );
+}
+
+#[test]
+fn fees_never_exceed_the_sale() {
+    let mut market = Market::new(20_000);
+    let mut wallet = 100;
+    market.buy("bob", &mut wallet, JUNK.id(), 10, 10).unwrap();
+    let mut alice = inventory(vec![stack(&JUNK, 10)]);
+    market.sell("alice", &mut alice, 0, 10, 10).unwrap();
+    assert_eq!(market.fees(), 100);
+    assert_eq!(market.withdraw(&"alice"), 0);
+    assert_eq!(market.account(&"bob").unwrap().items[0].quant(), 10);
+}
+
+#[test]
+fn cancelling_returns_the_escrow() {
+    let mut market = Market::new(0);
+    let mut wallet = 100;
+    let buy = market.buy("bob", &mut wallet, TORCH.id(), 10, 10).unwrap();
+    let mut alice = inventory(vec![stack(&TORCH, 40), None]);
+    let sell = market.sell("alice", &mut alice, 0, 30, 50).unwrap();
+
+    assert_eq!(market.cancel(&"alice", buy), Err(MarketError::NotOwner));
+    market.cancel(&"bob", buy).unwrap();
+    market.cancel(&"alice", sell).unwrap();
+    assert_eq!(market.cancel(&"alice", sell), Err(MarketError::NoSuchOrder));
+    assert_eq!(market.withdraw(&"bob"), 100);
+    assert_eq!(market.collect(&"alice", &mut alice), 0);
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 40);
+    assert!(market.orders(&TORCH.id()).next().is_none());
+}
+
+#[test]
+fn orders_are_checked() {
+    let mut market = Market::new(0);
+    let mut alice = inventory(vec![stack(&TORCH, 10), None]);
+    let mut wallet = 50;
+    assert_eq!(
+        market.sell("alice", &mut alice, 0, 11, 1),
+        Err(MarketError::NotEnough)
+    );
+    assert_eq!(
+        market.sell("alice", &mut alice, 1, 1, 1),
+        Err(MarketError::NotEnough)
+    );
+    assert_eq!(
+        market.sell("alice", &mut alice, 0, 0, 1),
+        Err(MarketError::ZeroQuantity)
+    );
+    assert_eq!(
+        market.buy("bob", &mut wallet, TORCH.id(), 6, 10),
+        Err(MarketError::InsufficientFunds)
+    );
+    assert_eq!(
+        market.buy("bob", &mut wallet, TORCH.id(), 2, u64::MAX),
+        Err(MarketError::Overflow)
+    );
+    assert_eq!(wallet, 50);
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 10);
+    assert!(market.history().is_empty());
+}
+
+#[test]
+fn unstackable_items_trade_one_at_a_time() {
+    let mut market = Market::new(0);
+    let mut alice = inventory(vec![SWORD_INST.clone(), SWORD_INST.clone()]);
+    market.sell("alice", &mut alice, 0, 5, 100).unwrap();
+    market.sell("alice", &mut alice, 1, 1, 100).unwrap();
+    assert!(market.orders(&SWORD.id()).all(|order| order.quant == 1));
+
+    let mut wallet = 300;
+    market.buy("bob", &mut wallet, SWORD.id(), 3, 100).unwrap();
+    let mut bob = inventory(vec![None]);
+    assert_eq!(market.collect(&"bob", &mut bob), 1);
+    bob.push(DefaultSlot::new(None));
+    assert_eq!(market.collect(&"bob", &mut bob), 0);
+    assert_eq!(quant_in_inventory(&bob, SWORD.id()), 2);
+    assert_eq!(market.orders(&SWORD.id()).next().unwrap().quant, 1);
+}
+
+#[test]
+fn history_records_every_step() {
+    let mut market = Market::new(500);
+    let mut alice = inventory(vec![stack(&TORCH, 10)]);
+    let mut wallet = 100;
+    market.sell("alice", &mut alice, 0, 10, 4).unwrap();
+    market.buy("bob", &mut wallet, TORCH.id(), 10, 4).unwrap();
+    market.withdraw(&"alice");
+    let mut bob = inventory(vec![None]);
+    market.collect(&"bob", &mut bob);
+
+    let history = market.history();
+    assert_eq!(history.len(), 5);
+    assert!(matches!(history[0], MarketEvent::Placed(ref order) if order.id == OrderId(0)));
+    assert_eq!(
+        history[2],
+        MarketEvent::Filled {
+            buy: OrderId(1),
+            sell: OrderId(0),
+            buyer: "bob",
+            seller: "alice",
+            item: TORCH.id(),
+            quant: 10,
+            price: 4,
+            fee: 2,
+        }
+    );
+    assert_eq!(
+        history[3],
+        MarketEvent::Withdrawn {
+            trader: "alice",
+            money: 38
+        }
+    );
+    assert_eq!(
+        history[4],
+        MarketEvent::Collected {
+            trader: "bob",
+            item: TORCH.id(),
+            quant: 10
+        }
+    );
+}
@@ -0,0 +1,95 @@
+use game_inventory::fixed_inventory::FixedInventory;
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::overflow::{DropInWorld, OverflowPolicy, Partial, Reject, Spill, WithPolicy};
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::shared_inventory::SharedInventory;
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+fn torches(quant: u16) -> DefaultItemInstance<DefaultItem<'static>> {
+    DefaultItemInstance::new(Arc::new(TORCH.clone()), quant)
+}
+
+fn slot(
+    quant: u16,
+) -> DefaultSlot<'static, DefaultItem<'static>, DefaultItemInstance<DefaultItem<'static>>> {
+    DefaultSlot::new(Some(torches(quant)))
+}
+
+#[test]
+fn reject_is_all_or_nothing() {
+    let mut inventory = vec![slot(60), DefaultSlot::new(SWORD_INST.clone())];
+    let left = Reject.add(&mut inventory, torches(41)).unwrap();
+    assert_eq!(left.quant(), 41);
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 60);
+    assert!(!inventory[0].modified);
+
+    assert!(Reject.add(&mut inventory, torches(40)).is_none());
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 100);
+    assert!(Reject
+        .add(&mut inventory, SWORD_INST.clone().unwrap())
+        .is_some());
+}
+
+#[test]
+fn partial_adds_what_fits() {
+    let mut inventory = vec![slot(60)];
+    assert_eq!(
+        Partial.add(&mut inventory, torches(50)).unwrap().quant(),
+        10
+    );
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 100);
+}
+
+#[test]
+fn spill_goes_to_the_second_inventory() {
+    let mut backpack = vec![slot(90)];
+    let mut chest = vec![slot(95), DefaultSlot::new(None)];
+    assert!(Spill(&mut chest)
+        .add(&mut backpack, SWORD_INST.clone().unwrap())
+        .is_none());
+    assert_eq!(quant_in_inventory(&chest, SWORD.id()), 1);
+
+    let left = Spill(&mut chest).add(&mut backpack, torches(20)).unwrap();
+    assert_eq!(left.quant(), 5);
+    assert_eq!(quant_in_inventory(&backpack, TORCH.id()), 100);
+    assert_eq!(quant_in_inventory(&chest, TORCH.id()), 100);
+}
+
+#[test]
+fn drop_in_world_hands_over_the_leftover() {
+    let mut inventory = vec![slot(99)];
+    let mut dropped = vec![];
+    let mut policy = DropInWorld(|ii: DefaultItemInstance<DefaultItem>| dropped.push(ii.quant()));
+    assert!(policy.add(&mut inventory, torches(1)).is_none());
+    assert!(policy.add(&mut inventory, torches(7)).is_none());
+    assert!(policy.add(&mut inventory, torches(3)).is_none());
+    assert_eq!(dropped, vec![7, 3]);
+}
+
+#[test]
+fn inventories_can_keep_a_policy() {
+    let mut belt = WithPolicy::new(
+        FixedInventory::<_, _, DefaultSlot<_, _>, 1>::from([slot(80)]),
+        Partial,
+    );
+    assert_eq!(belt.add(torches(30)).unwrap().quant(), 10);
+
+    let mut floor = vec![];
+    let mut pack = WithPolicy::new(vec![slot(80)], DropInWorld(|ii| floor.push(ii)));
+    assert!(pack.add(torches(30)).is_none());
+    assert!(pack.add(SWORD_INST.clone().unwrap()).is_none());
+    assert_eq!(quant_in_inventory(&pack.inventory, TORCH.id()), 100);
+    drop(pack);
+    assert_eq!(floor.len(), 2);
+}
+
+#[test]
+fn shared_inventories_take_a_policy_per_call() {
+    let chest = SharedInventory::new(vec![slot(80)]);
+    assert!(chest.add_with(torches(30), &mut Reject).is_some());
+    assert!(chest.add_with(torches(20), &mut Reject).is_none());
+    assert_eq!(chest.quant(TORCH.id()), 100);
+}
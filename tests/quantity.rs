@@ -0,0 +1,105 @@
+use game_inventory::helpers::{
+    add_to_inventory, available_quant_in_inventory, can_add_item_to_inventory, combine_stack,
+    half_stack_split, quant_in_inventory, remove_from_inventory, remove_from_stack,
+};
+use game_inventory::indexed_inventory::IndexedInventory;
+use game_inventory::samples::{DefaultItem, DefaultItemInstance, DefaultSlot};
+use game_inventory::snapshot::Snapshot;
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+static GEM: DefaultItem<u8> = DefaultItem {
+    name: "gem",
+    max_quantity: 250,
+};
+
+static ORE: DefaultItem<u64> = DefaultItem {
+    name: "ore",
+    max_quantity: 5_000_000,
+};
+
+fn gems(quant: u8) -> Option<DefaultItemInstance<DefaultItem<'static, u8>>> {
+    Some(DefaultItemInstance::new(Arc::new(GEM.clone()), quant))
+}
+
+fn ore(quant: u64) -> DefaultItemInstance<DefaultItem<'static, u64>> {
+    DefaultItemInstance::new(Arc::new(ORE.clone()), quant)
+}
+
+#[test]
+fn combine_near_the_limit_of_u8() {
+    let res = combine_stack((gems(200), gems(100))).ok().unwrap();
+    assert_eq!(res.0.unwrap().quant(), 50);
+    assert_eq!(res.1.unwrap().quant(), 250);
+
+    let res = combine_stack((gems(150), gems(100))).ok().unwrap();
+    assert!(res.0.is_none());
+    assert_eq!(res.1.unwrap().quant(), 250);
+}
+
+#[test]
+fn split_and_remove_near_the_limit_of_u8() {
+    // Only moves as many as fit, so the other stack never goes past 250, let alone 255.
+    let res = half_stack_split((gems(100), gems(230))).ok().unwrap();
+    assert_eq!(res.0.unwrap().quant(), 80);
+    assert_eq!(res.1.unwrap().quant(), 250);
+    let res = half_stack_split((gems(101), gems(100))).ok().unwrap();
+    assert_eq!(res.0.unwrap().quant(), 50);
+    assert_eq!(res.1.unwrap().quant(), 151);
+
+    let res = remove_from_stack((gems(1), gems(249))).ok().unwrap();
+    assert!(res.0.is_none());
+    assert_eq!(res.1.unwrap().quant(), 250);
+    assert!(remove_from_stack((gems(5), gems(250))).is_err());
+}
+
+#[test]
+fn totals_are_not_limited_to_the_quantity_type() {
+    let inventory = vec![
+        DefaultSlot::new(gems(250)),
+        DefaultSlot::new(gems(250)),
+        DefaultSlot::new(gems(3)),
+    ];
+    assert_eq!(quant_in_inventory(&inventory, GEM.id()), 503);
+    assert_eq!(available_quant_in_inventory(&inventory, GEM.id()), 503);
+    assert_eq!(IndexedInventory::new(inventory).quant(GEM.id()), 503);
+}
+
+#[test]
+fn snapshot_totals_are_not_limited_to_the_quantity_type() {
+    let mut inventory = vec![DefaultSlot::new(gems(250)), DefaultSlot::new(None)];
+    let before = Snapshot::take(&inventory);
+    inventory[1].set_item_instance(&gems(250));
+    let diff = before.diff(&Snapshot::take(&inventory));
+    assert_eq!(diff.quant_delta(GEM.id()).after, 500);
+    assert_eq!(diff.gained().collect::<Vec<_>>(), vec![(&GEM.id(), 250)]);
+}
+
+#[test]
+fn millions_of_ore() {
+    let mut inventory: Vec<_> = (0..4).map(|_| DefaultSlot::new(None)).collect();
+    assert!(can_add_item_to_inventory(&inventory, ore(3_000_000)));
+    assert!(add_to_inventory(&mut inventory, ore(3_000_000)).is_none());
+    assert!(add_to_inventory(&mut inventory, ore(3_000_000)).is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 5_000_000);
+    assert_eq!(inventory[1].item_instance().unwrap().quant(), 1_000_000);
+    assert_eq!(quant_in_inventory(&inventory, ORE.id()), 6_000_000);
+
+    let left = remove_from_inventory(&mut inventory, ore(7_000_000)).unwrap();
+    assert_eq!(left.quant(), 1_000_000);
+    assert_eq!(quant_in_inventory(&inventory, ORE.id()), 0);
+
+    let mut indexed = IndexedInventory::new(inventory);
+    assert!(indexed.add(ore(12_000_000)).is_none());
+    assert_eq!(indexed.quant(ORE.id()), 12_000_000);
+    assert!(indexed.can_add_item(&ore(8_000_000)));
+    assert!(!indexed.can_add_item(&ore(8_000_001)));
+}
+
+#[test]
+fn filling_a_stack_exactly_leaves_nothing_over() {
+    let mut inventory = vec![DefaultSlot::new(gems(200)), DefaultSlot::new(None)];
+    assert!(add_to_inventory(&mut inventory, gems(50).unwrap()).is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 250);
+    assert!(inventory[1].item_instance().is_none());
+}
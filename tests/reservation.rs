@@ -0,0 +1,170 @@
+use game_inventory::helpers::{
+    add_to_inventory, available_quant_in_inventory, can_add_item_to_inventory, quant_in_inventory,
+    remove_from_inventory,
+};
+use game_inventory::indexed_inventory::IndexedInventory;
+use game_inventory::mail::{MailError, PostOffice};
+use game_inventory::market::{Market, MarketError};
+use game_inventory::reservation::reserve;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, ReservableSlot, SWORD, SWORD_INST, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use game_inventory::transport::{Link, Transport};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Inventory = Vec<ReservableSlot<'static, DefaultItem<'static>, Inst>>;
+
+fn torches(quant: u16) -> Inst {
+    DefaultItemInstance::new(Arc::new(TORCH.clone()), quant)
+}
+
+fn inventory(slots: Vec<Option<Inst>>) -> Inventory {
+    slots.into_iter().map(ReservableSlot::new).collect()
+}
+
+#[test]
+fn locked_slots_are_left_alone() {
+    let mut inventory = inventory(vec![Some(torches(10)), Some(torches(10)), None]);
+    inventory[0].locked = true;
+    inventory[2].locked = true;
+
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 20);
+    assert_eq!(available_quant_in_inventory(&inventory, TORCH.id()), 10);
+    assert_eq!(
+        remove_from_inventory(&mut inventory, torches(15))
+            .unwrap()
+            .quant(),
+        5
+    );
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 10);
+
+    assert!(!can_add_item_to_inventory(&inventory, torches(101)));
+    assert_eq!(
+        add_to_inventory(&mut inventory, torches(101))
+            .unwrap()
+            .quant(),
+        1
+    );
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 10);
+    assert!(inventory[2].item_instance().is_none());
+    assert!(inventory[0].transfer(None, "left_click").is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 10);
+}
+
+#[test]
+fn reservations_are_released_on_drop() {
+    let inventory = inventory(vec![Some(torches(5)), SWORD_INST.clone(), Some(torches(5))]);
+    let first = reserve(&inventory, TORCH.id(), 7).unwrap();
+    assert_eq!(first.quant(), 7);
+    assert_eq!(first.slots().collect::<Vec<_>>(), vec![(0, 5), (2, 2)]);
+    let sword = reserve(&inventory, SWORD.id(), 1).unwrap();
+    assert!(reserve(&inventory, TORCH.id(), 4).is_none());
+    assert!(reserve(&inventory, SWORD.id(), 1).is_none());
+    // Failed reservations do not hold on to anything.
+    assert_eq!(available_quant_in_inventory(&inventory, TORCH.id()), 3);
+
+    drop(first);
+    drop(sword);
+    assert_eq!(available_quant_in_inventory(&inventory, TORCH.id()), 10);
+    assert_eq!(available_quant_in_inventory(&inventory, SWORD.id()), 1);
+    assert!(inventory.iter().all(|slot| slot.ledger.is_empty()));
+}
+
+#[test]
+fn consuming_takes_the_reserved_items() {
+    let mut inventory = inventory(vec![Some(torches(5)), SWORD_INST.clone(), Some(torches(5))]);
+    let job = reserve(&inventory, TORCH.id(), 7).unwrap();
+    let sword = reserve(&inventory, SWORD.id(), 1).unwrap();
+    assert!(remove_from_inventory(
+        &mut inventory,
+        DefaultItemInstance::new(Arc::new(SWORD.clone()), 1)
+    )
+    .is_some());
+
+    assert_eq!(job.consume(&mut inventory), 0);
+    assert!(inventory[0].item_instance().is_none());
+    assert_eq!(inventory[2].item_instance().unwrap().quant(), 3);
+    assert_eq!(sword.consume(&mut inventory), 0);
+    assert!(inventory[1].item_instance().is_none());
+
+    // Items taken out from under a reservation are reported as missing.
+    let job = reserve(&inventory, TORCH.id(), 3).unwrap();
+    inventory[2].set_item_instance(&Some(torches(1)));
+    assert_eq!(job.consume(&mut inventory), 2);
+    assert!(inventory[2].item_instance().is_none());
+}
+
+#[test]
+fn transport_moves_only_available_items() {
+    let mut inventories = vec![
+        inventory(vec![Some(torches(10)), Some(torches(10))]),
+        inventory(vec![None, None]),
+    ];
+    inventories[0][1].locked = true;
+    let job = reserve(&inventories[0], TORCH.id(), 6).unwrap();
+    let mut link = Link::new(0, vec![1]);
+    link.amount = 50;
+    let mut transport = Transport::new();
+    transport.add_link(link);
+
+    transport.tick(&mut inventories);
+    assert_eq!(quant_in_inventory(&inventories[0], TORCH.id()), 16);
+    assert_eq!(quant_in_inventory(&inventories[1], TORCH.id()), 4);
+    drop(job);
+    transport.tick(&mut inventories);
+    assert_eq!(quant_in_inventory(&inventories[0], TORCH.id()), 10);
+    assert_eq!(quant_in_inventory(&inventories[1], TORCH.id()), 10);
+}
+
+#[test]
+fn indexed_inventories_respect_locks_and_reservations() {
+    let mut slots = inventory(vec![Some(torches(90)), Some(torches(50)), None, None]);
+    slots[0].locked = true;
+    slots[2].locked = true;
+    let job = reserve(&slots, TORCH.id(), 20).unwrap();
+    let mut inventory = IndexedInventory::new(slots);
+
+    assert_eq!(inventory.quant(TORCH.id()), 140);
+    assert_eq!(inventory.remove(torches(40)).unwrap().quant(), 10);
+    assert_eq!(inventory[1].item_instance().unwrap().quant(), 20);
+    assert!(!inventory.can_add_item(&torches(181)));
+    assert_eq!(inventory.add(torches(181)).unwrap().quant(), 1);
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 90);
+    assert!(inventory[2].item_instance().is_none());
+    assert!(inventory.add(SWORD_INST.clone().unwrap()).is_some());
+
+    drop(job);
+    assert!(inventory.remove(torches(200)).is_none());
+}
+
+#[test]
+fn reserved_items_can_not_be_sold_or_mailed() {
+    let mut inventory = inventory(vec![Some(torches(10)), SWORD_INST.clone()]);
+    let job = reserve(&inventory, TORCH.id(), 6).unwrap();
+    let sword = reserve(&inventory, SWORD.id(), 1).unwrap();
+
+    let mut market = Market::new(0);
+    assert_eq!(
+        market.sell("alice", &mut inventory, 0, 5, 1),
+        Err(MarketError::NotEnough)
+    );
+    assert_eq!(
+        market.sell("alice", &mut inventory, 1, 1, 1),
+        Err(MarketError::NotEnough)
+    );
+    let mut post = PostOffice::new(100);
+    assert_eq!(
+        post.send("alice", "bob", &mut inventory, &[(1, 1)], 0),
+        Err(MailError::NotEnough(1))
+    );
+    market.sell("alice", &mut inventory, 0, 4, 1).unwrap();
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 6);
+
+    drop(sword);
+    post.send("alice", "bob", &mut inventory, &[(1, 1)], 0)
+        .unwrap();
+    assert!(inventory[1].item_instance().is_none());
+    drop(job);
+}
@@ -0,0 +1,222 @@
+use game_inventory::ground::{DropError, GroundItems};
+use game_inventory::helpers::{
+    add_to_inventory, half_stack_split, quant_in_inventory, remove_from_inventory,
+};
+use game_inventory::mail::{MailError, PostOffice};
+use game_inventory::market::{Market, MarketError};
+use game_inventory::restrictions::{check, destroy_on_logout, Action, ItemFlags, Restricted};
+use game_inventory::samples::{
+    DefaultSlot, FlaggedItem, FlaggedItemInstance, EVENT_TOKEN, QUEST_MAP,
+};
+use game_inventory::shared_inventory::SharedInventory;
+use game_inventory::trade::{Side, TradeError, TradeSession};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use game_inventory::transport::{Link, Transport};
+use std::sync::Arc;
+
+type Inst = FlaggedItemInstance<FlaggedItem<'static>>;
+type Inventory = Vec<DefaultSlot<'static, FlaggedItem<'static>, Inst>>;
+
+static RING: FlaggedItem = FlaggedItem {
+    name: "ring",
+    max_quantity: 0,
+    flags: ItemFlags::NONE,
+};
+
+static LANTERN: FlaggedItem = FlaggedItem {
+    name: "lantern",
+    max_quantity: 100,
+    flags: ItemFlags::NONE,
+};
+
+fn stack(item: &FlaggedItem<'static>, quant: u16) -> Inst {
+    FlaggedItemInstance::new(Arc::new(item.clone()), quant)
+}
+
+fn bound(item: &FlaggedItem<'static>) -> Inst {
+    let mut ii = stack(item, 0);
+    ii.flags = ItemFlags::BOUND;
+    ii
+}
+
+fn inventory(slots: Vec<Option<Inst>>) -> Inventory {
+    slots.into_iter().map(DefaultSlot::new).collect()
+}
+
+#[test]
+fn flags_forbid_their_actions() {
+    let tokens = stack(&EVENT_TOKEN, 3);
+    assert!(check(&tokens, Action::Trade).is_ok());
+    assert!(check(&tokens, Action::Mail).is_ok());
+    assert!(check(&tokens, Action::Drop).is_ok());
+    assert_eq!(
+        check(&tokens, Action::Sell),
+        Err(Restricted {
+            action: Action::Sell,
+            flags: ItemFlags::NO_SELL
+        })
+    );
+    let map = stack(&QUEST_MAP, 0);
+    assert_eq!(
+        check(&map, Action::Drop).unwrap_err().flags,
+        ItemFlags::BOUND | ItemFlags::NO_DROP
+    );
+    assert!(ItemFlags::BOUND
+        .union(ItemFlags::NO_DROP)
+        .contains(ItemFlags::NO_DROP));
+    assert!(!ItemFlags::NO_DROP.contains(ItemFlags::BOUND));
+    // Instance flags add to the item's.
+    let mut ring = bound(&RING);
+    assert!(check(&ring, Action::Trade).is_err());
+    ring.flags = ItemFlags::NONE;
+    assert!(check(&ring, Action::Trade).is_ok());
+}
+
+#[test]
+fn trades_skip_bound_instances() {
+    let mut alice = inventory(vec![Some(bound(&RING)), Some(stack(&RING, 0)), None]);
+    let mut bob = inventory(vec![None, None]);
+    let mut trade = TradeSession::new();
+    assert_eq!(
+        trade.offer(Side::Left, &alice, 0, 1),
+        Err(TradeError::Restricted(Side::Left, ItemFlags::BOUND))
+    );
+    trade.offer(Side::Left, &alice, 1, 1).unwrap();
+    // The unbound ring is already offered.
+    assert_eq!(
+        trade.offer(Side::Left, &alice, 1, 1),
+        Err(TradeError::MissingItems(Side::Left))
+    );
+    trade.lock(Side::Left);
+    trade.lock(Side::Right);
+    trade.confirm(Side::Left).unwrap();
+    trade.confirm(Side::Right).unwrap();
+    trade.execute(&mut alice, &mut bob).unwrap();
+
+    assert_eq!(alice[0].item_instance().unwrap().flags, ItemFlags::BOUND);
+    assert!(alice[1].item_instance().is_none());
+    assert_eq!(quant_in_inventory(&bob, RING.id()), 1);
+}
+
+#[test]
+fn restricted_items_are_not_sold_or_mailed() {
+    let mut inventory = inventory(vec![
+        Some(stack(&EVENT_TOKEN, 10)),
+        Some(stack(&QUEST_MAP, 0)),
+        Some(stack(&LANTERN, 10)),
+    ]);
+    let mut market = Market::new(0);
+    assert_eq!(
+        market.sell("alice", &mut inventory, 0, 5, 1),
+        Err(MarketError::Restricted(ItemFlags::NO_SELL))
+    );
+    assert_eq!(
+        market.sell("alice", &mut inventory, 1, 1, 1),
+        Err(MarketError::Restricted(ItemFlags::BOUND))
+    );
+
+    let mut post = PostOffice::new(100);
+    assert_eq!(
+        post.send("alice", "bob", &mut inventory, &[(2, 5), (1, 1)], 0),
+        Err(MailError::Restricted(1, ItemFlags::BOUND))
+    );
+    assert_eq!(quant_in_inventory(&inventory, LANTERN.id()), 10);
+    post.send("alice", "bob", &mut inventory, &[(0, 4), (2, 5)], 0)
+        .unwrap();
+    assert_eq!(quant_in_inventory(&inventory, EVENT_TOKEN.id()), 6);
+}
+
+#[test]
+fn no_drop_items_stay_in_the_inventory() {
+    let mut inventory = inventory(vec![
+        Some(stack(&QUEST_MAP, 0)),
+        Some(stack(&EVENT_TOKEN, 5)),
+    ]);
+    let mut ground = GroundItems::new(1.0, 600);
+    assert_eq!(
+        ground.drop_from(&mut inventory, 0, [0.0; 3]),
+        Err(DropError::Restricted(ItemFlags::BOUND | ItemFlags::NO_DROP))
+    );
+    assert_eq!(
+        ground.drop_from(&mut inventory, 2, [0.0; 3]),
+        Err(DropError::EmptySlot)
+    );
+    let id = ground.drop_from(&mut inventory, 1, [0.0; 3]).unwrap();
+    assert_eq!(ground.get(id).unwrap().item_instance.quant(), 5);
+    assert!(inventory[1].item_instance().is_none());
+    assert_eq!(ground.len(), 1);
+}
+
+#[test]
+fn logging_out_destroys_flagged_items() {
+    let mut inventory = inventory(vec![
+        Some(stack(&EVENT_TOKEN, 5)),
+        Some(stack(&QUEST_MAP, 0)),
+        None,
+        Some(stack(&EVENT_TOKEN, 50)),
+    ]);
+    let mut ring = stack(&RING, 0);
+    ring.flags = ItemFlags::DESTROY_ON_LOGOUT;
+    inventory[2].set_item_instance(&Some(ring));
+
+    assert_eq!(destroy_on_logout(&mut inventory), 3);
+    assert_eq!(quant_in_inventory(&inventory, EVENT_TOKEN.id()), 0);
+    assert_eq!(quant_in_inventory(&inventory, QUEST_MAP.id()), 1);
+    assert_eq!(destroy_on_logout(&mut inventory), 0);
+}
+
+#[test]
+fn bound_items_do_not_move_between_inventories() {
+    let backpack = SharedInventory::new(inventory(vec![
+        Some(bound(&RING)),
+        Some(stack(&RING, 0)),
+        Some(stack(&QUEST_MAP, 0)),
+    ]));
+    let chest = SharedInventory::new(inventory(vec![None, None, None]));
+    assert!(backpack.move_to(&chest, stack(&RING, 1)).is_none());
+    assert_eq!(
+        backpack.move_to(&chest, stack(&RING, 1)).unwrap().quant(),
+        1
+    );
+    assert!(backpack.move_to(&chest, stack(&QUEST_MAP, 1)).is_some());
+    assert_eq!(chest.quant(RING.id()), 1);
+    assert_eq!(chest.quant(QUEST_MAP.id()), 0);
+
+    let mut inventories = vec![
+        inventory(vec![Some(stack(&QUEST_MAP, 0)), Some(stack(&LANTERN, 2))]),
+        inventory(vec![None, None]),
+    ];
+    let mut transport = Transport::new();
+    let mut link = Link::new(0, vec![1]);
+    link.amount = 10;
+    transport.add_link(link);
+    for _ in 0..3 {
+        transport.tick(&mut inventories);
+    }
+    assert_eq!(quant_in_inventory(&inventories[0], QUEST_MAP.id()), 1);
+    assert_eq!(quant_in_inventory(&inventories[1], LANTERN.id()), 2);
+    assert_eq!(quant_in_inventory(&inventories[1], QUEST_MAP.id()), 0);
+}
+
+#[test]
+fn instance_flags_survive_splits_and_merges() {
+    let bound_lanterns = |quant| {
+        let mut lanterns = stack(&LANTERN, quant);
+        lanterns.flags = ItemFlags::BOUND;
+        lanterns
+    };
+    let mut inventory = inventory(vec![Some(bound_lanterns(10)), None, None]);
+    // Unbound lanterns keep a stack of their own, bound ones merge with the bound stack.
+    assert!(add_to_inventory(&mut inventory, stack(&LANTERN, 5)).is_none());
+    assert!(add_to_inventory(&mut inventory, bound_lanterns(5)).is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 15);
+    assert_eq!(inventory[1].item_instance().unwrap().flags, ItemFlags::NONE);
+
+    assert!(remove_from_inventory(&mut inventory, stack(&LANTERN, 3)).is_none());
+    let (rest, half) =
+        half_stack_split((inventory[0].item_instance(), inventory[2].item_instance())).unwrap();
+    assert_eq!(rest.as_ref().unwrap().quant(), 6);
+    assert_eq!(rest.unwrap().flags, ItemFlags::BOUND);
+    assert_eq!(half.unwrap().flags, ItemFlags::BOUND);
+    assert!(check(&inventory[0].item_instance().unwrap(), Action::Trade).is_err());
+}
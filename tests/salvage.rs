@@ -0,0 +1,180 @@
+use game_inventory::generation::SeededRng;
+use game_inventory::helpers::{add_to_inventory, quant_in_inventory, remove_from_inventory};
+use game_inventory::salvage::{Component, SalvageError, SalvageTables};
+use game_inventory::samples::{
+    DefaultItem, DurableItemInstance, ReservableSlot, JUNK, SWORD, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DurableItemInstance<DefaultItem<'static>>;
+
+fn stack(item: &DefaultItem<'static>, quant: u16) -> Inst {
+    DurableItemInstance::new(Arc::new(item.clone()), quant)
+}
+
+fn sword(condition: f32) -> Option<Inst> {
+    let mut sword = stack(&SWORD, 0);
+    sword.condition = condition;
+    Some(sword)
+}
+
+fn tables() -> SalvageTables<DefaultItem<'static>, Inst> {
+    let mut tables = SalvageTables::new();
+    tables.add_table(
+        SWORD.id(),
+        vec![
+            Component {
+                scaled: true,
+                ..Component::fixed(stack(&JUNK, 10))
+            },
+            Component::random(stack(&TORCH, 4), 2, 100),
+        ],
+    );
+    tables.add_table(TORCH.id(), vec![Component::fixed(stack(&JUNK, 1))]);
+    tables
+}
+
+#[test]
+fn salvaging_replaces_the_item_with_its_components() {
+    let tables = tables();
+    let mut rng = SeededRng::new(5);
+    let mut inventory = vec![
+        ReservableSlot::new(sword(1.0)),
+        ReservableSlot::new(Some(stack(&TORCH, 3))),
+    ];
+    let results = tables.salvage(&mut inventory, 0, &mut rng).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!((2..=4).contains(&results[1].quant()));
+    // The junk takes the sword's slot and the torches stack onto the others.
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 10);
+    assert_eq!(
+        quant_in_inventory(&inventory, TORCH.id()),
+        3 + results[1].quant() as u128
+    );
+
+    tables.salvage(&mut inventory, 1, &mut rng).unwrap();
+    assert_eq!(quant_in_inventory(&inventory, JUNK.id()), 11);
+    assert_eq!(
+        quant_in_inventory(&inventory, TORCH.id()),
+        2 + results[1].quant() as u128
+    );
+    assert_eq!(
+        tables.salvage(&mut inventory, 0, &mut rng).unwrap_err(),
+        SalvageError::NotSalvageable
+    );
+}
+
+#[test]
+fn worn_items_yield_less() {
+    let tables = tables();
+    let mut rng = SeededRng::new(5);
+    let junk = |ii: &Inst| {
+        let results = tables.roll(ii, &mut SeededRng::new(5)).unwrap();
+        results
+            .iter()
+            .find(|result| result.item().id() == JUNK.id())
+            .map(|result| result.quant())
+    };
+    assert_eq!(junk(&sword(1.0).unwrap()), Some(10));
+    assert_eq!(junk(&sword(0.55).unwrap()), Some(5));
+    assert_eq!(junk(&sword(0.05).unwrap()), None);
+    // Only scaled components wear down.
+    let broken = tables.roll(&sword(0.0).unwrap(), &mut rng).unwrap();
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].item().id(), TORCH.id());
+    assert!(tables.roll(&stack(&JUNK, 1), &mut rng).is_none());
+}
+
+#[test]
+fn random_components_can_miss() {
+    let mut tables = SalvageTables::new();
+    tables.add_table(SWORD.id(), vec![Component::random(stack(&TORCH, 1), 1, 25)]);
+    let mut rng = SeededRng::new(11);
+    let hits = (0..400)
+        .filter(|_| {
+            !tables
+                .roll(&sword(1.0).unwrap(), &mut rng)
+                .unwrap()
+                .is_empty()
+        })
+        .count();
+    assert!((60..140).contains(&hits));
+}
+
+#[test]
+fn salvaging_is_refused_when_the_results_do_not_fit() {
+    let tables = tables();
+    let mut rng = SeededRng::new(5);
+    let mut inventory = vec![
+        ReservableSlot::new(sword(1.0)),
+        ReservableSlot::new(Some(stack(&JUNK, 100))),
+    ];
+    // The junk fits where the sword was, but the torches have nowhere to go.
+    assert_eq!(
+        tables.salvage(&mut inventory, 0, &mut rng).unwrap_err(),
+        SalvageError::NoSpace
+    );
+    assert!(inventory[0].item_instance().is_some());
+    assert_eq!(quant_in_inventory(&inventory, JUNK.id()), 100);
+
+    inventory.push(ReservableSlot::new(None));
+    inventory[0].locked = true;
+    assert_eq!(
+        tables.salvage(&mut inventory, 0, &mut rng).unwrap_err(),
+        SalvageError::Locked
+    );
+    assert_eq!(
+        tables.salvage(&mut inventory, 2, &mut rng).unwrap_err(),
+        SalvageError::EmptySlot
+    );
+    inventory[0].locked = false;
+    assert!(tables.salvage(&mut inventory, 0, &mut rng).is_ok());
+    assert_eq!(quant_in_inventory(&inventory, SWORD.id()), 0);
+}
+
+#[test]
+fn worn_stacks_stay_worn_when_split_and_merged() {
+    let mut tables = SalvageTables::new();
+    tables.add_table(
+        TORCH.id(),
+        vec![Component {
+            scaled: true,
+            ..Component::fixed(stack(&JUNK, 8))
+        }],
+    );
+    let worn = |quant| {
+        let mut torches = stack(&TORCH, quant);
+        torches.condition = 0.25;
+        torches
+    };
+    let mut inventory = vec![
+        ReservableSlot::new(Some(worn(10))),
+        ReservableSlot::new(Some(stack(&TORCH, 5))),
+        ReservableSlot::new(None),
+    ];
+    assert!(add_to_inventory(&mut inventory, worn(20)).is_none());
+    assert!(remove_from_inventory(&mut inventory, stack(&TORCH, 4)).is_none());
+    let torches = inventory[0].item_instance().unwrap();
+    assert_eq!(torches.quant(), 26);
+    assert_eq!(torches.condition, 0.25);
+    assert_eq!(inventory[1].item_instance().unwrap().condition, 1.0);
+
+    let results = tables
+        .salvage(&mut inventory, 0, &mut SeededRng::new(1))
+        .unwrap();
+    assert_eq!(results[0].quant(), 2);
+    assert_eq!(inventory[0].item_instance().unwrap().condition, 0.25);
+}
+
+#[test]
+fn components_keep_their_instance_data() {
+    let mut scrap = stack(&JUNK, 6);
+    scrap.condition = 0.5;
+    let mut tables = SalvageTables::new();
+    tables.add_table(SWORD.id(), vec![Component::random(scrap, 2, 100)]);
+    let results = tables
+        .roll(&sword(1.0).unwrap(), &mut SeededRng::new(3))
+        .unwrap();
+    assert_eq!(results[0].condition, 0.5);
+}
@@ -4,9 +4,9 @@ use game_inventory::traits::Slot;
 #[test]
 fn set() {
     let mut slot = DefaultSlot::new(None);
-    assert_eq!(slot.modified, false);
+    assert!(!slot.modified);
     slot.set_item_instance(&TORCH_INST);
-    assert_eq!(slot.modified, true);
+    assert!(slot.modified);
 }
 #[test]
 fn swap() {
@@ -15,7 +15,7 @@ fn swap() {
         DefaultItem<'static>,
         DefaultItemInstance<DefaultItem<'static>>,
     >>::new(None);
-    assert_eq!(slot.modified, false);
+    assert!(!slot.modified);
     slot.transfer(TORCH_INST.clone(), "");
-    assert_eq!(slot.modified, true);
+    assert!(slot.modified);
 }
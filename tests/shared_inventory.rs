@@ -0,0 +1,176 @@
+use game_inventory::helpers::StackLimit;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, DurableItemInstance, LimitedSlot, SWORD, TORCH,
+};
+use game_inventory::shared_inventory::SharedInventory;
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+use std::thread;
+
+type Itm = DefaultItem<'static>;
+type Inst = DefaultItemInstance<Itm>;
+type Chest = SharedInventory<Itm, Inst, DefaultSlot<'static, Itm, Inst>>;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self, max: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % max
+    }
+}
+
+fn chest() -> Chest {
+    SharedInventory::new((0..40).map(|_| DefaultSlot::new(None)).collect())
+}
+
+fn quant_of(ii: Option<Inst>) -> i64 {
+    match ii {
+        Some(ii) => ii.quant() as i64,
+        None => 0,
+    }
+}
+
+/// Runs random operations on two chests and returns how many torches and swords
+/// were added to and removed from them in total.
+fn churn(a: Chest, b: Chest, seed: u64) -> (i64, i64) {
+    let mut rng = XorShift(seed);
+    let torch = Arc::new(TORCH.clone());
+    let sword = Arc::new(SWORD.clone());
+    let (mut torches, mut swords) = (0, 0);
+    for _ in 0..2000 {
+        let (here, there) = if rng.next(2) == 0 { (&a, &b) } else { (&b, &a) };
+        let quant = rng.next(100) as u16 + 1;
+        match rng.next(6) {
+            0 => {
+                let left = here.add(Inst::new(torch.clone(), quant));
+                torches += quant as i64 - quant_of(left);
+            }
+            1 => {
+                let left = here.remove(Inst::new(torch.clone(), quant));
+                torches -= quant as i64 - quant_of(left);
+            }
+            2 => {
+                here.move_to(there, Inst::new(torch.clone(), quant));
+            }
+            3 => {
+                if here.add(Inst::new(sword.clone(), 1)).is_none() {
+                    swords += 1;
+                }
+            }
+            4 => {
+                let left = here.remove(Inst::new(sword.clone(), 2));
+                swords -= 2 - quant_of(left);
+            }
+            _ => {
+                here.move_to(there, Inst::new(sword.clone(), 3));
+            }
+        }
+    }
+    (torches, swords)
+}
+
+#[test]
+fn items_are_conserved_under_contention() {
+    let (a, b) = (chest(), chest());
+    let seeds = [3, 5, 7, 11, 13, 17, 19, 23];
+    let (torches, swords) = thread::scope(|scope| {
+        let reader = {
+            let (a, b) = (a.clone(), b.clone());
+            scope.spawn(move || {
+                for _ in 0..2000 {
+                    a.read(|slots| assert_eq!(slots.len(), 40));
+                    b.quant(TORCH.id());
+                    a.empty_quant();
+                }
+            })
+        };
+        let workers: Vec<_> = seeds
+            .iter()
+            .map(|&seed| {
+                let (a, b) = (a.clone(), b.clone());
+                scope.spawn(move || churn(a, b, seed))
+            })
+            .collect();
+        reader.join().unwrap();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .fold((0, 0), |total, res| (total.0 + res.0, total.1 + res.1))
+    });
+
+    assert_eq!(
+        a.quant(TORCH.id()) as i64 + b.quant(TORCH.id()) as i64,
+        torches
+    );
+    assert_eq!(
+        a.quant(SWORD.id()) as i64 + b.quant(SWORD.id()) as i64,
+        swords
+    );
+    for chest in [&a, &b] {
+        chest.read(|slots| {
+            for ii in slots.iter().filter_map(|slot| slot.item_instance()) {
+                if ii.item().stackable() {
+                    assert!(ii.quant() > 0 && ii.quant() <= ii.item().max_quant());
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn moving_unstackable_items_keeps_the_instances() {
+    let a = chest();
+    let b = SharedInventory::new(vec![DefaultSlot::new(None)]);
+    let sword = Arc::new(SWORD.clone());
+    a.add(Inst::new(sword.clone(), 7));
+    a.add(Inst::new(sword.clone(), 9));
+    let first = a.read(|slots| slots[0].item_instance().unwrap().quant());
+    let left = a.move_to(&b, Inst::new(sword, 2));
+    assert_eq!(left.unwrap().quant(), 1);
+    assert_eq!(
+        b.read(|slots| slots[0].item_instance().unwrap().quant()),
+        first
+    );
+    assert_eq!(a.quant(SWORD.id()), 1);
+}
+
+#[test]
+fn moves_never_lose_items() {
+    let torches = |quant| Some(Inst::new(Arc::new(TORCH.clone()), quant));
+    let pouch = |ii| LimitedSlot::with_limit(ii, StackLimit::Cap(20));
+    let a = SharedInventory::new(vec![pouch(torches(20)), pouch(torches(15))]);
+    let b = SharedInventory::new(vec![LimitedSlot::with_limit(None, StackLimit::Cap(5))]);
+    let left = a.move_to(&b, Inst::new(Arc::new(TORCH.clone()), 40));
+    assert_eq!(left.unwrap().quant(), 35);
+    assert_eq!(a.quant(TORCH.id()), 30);
+    assert_eq!(b.quant(TORCH.id()), 5);
+
+    // Moving into the same inventory moves nothing.
+    let left = a.move_to(&a.clone(), Inst::new(Arc::new(TORCH.clone()), 10));
+    assert_eq!(left.unwrap().quant(), 10);
+    assert_eq!(a.quant(TORCH.id()), 30);
+}
+
+#[test]
+fn stacks_that_do_not_fit_are_skipped() {
+    let torches = |quant, condition| {
+        let mut torches = DurableItemInstance::new(Arc::new(TORCH.clone()), quant);
+        torches.condition = condition;
+        Some(torches)
+    };
+    let a = SharedInventory::new(vec![
+        DefaultSlot::new(torches(10, 0.5)),
+        DefaultSlot::new(torches(10, 1.0)),
+    ]);
+    let b = SharedInventory::new(vec![DefaultSlot::new(torches(90, 1.0))]);
+    // The worn torches do not stack with the ones in `b`, but the new ones do.
+    assert!(a.move_to(&b, torches(8, 1.0).unwrap()).is_none());
+    a.read(|slots| {
+        assert_eq!(slots[0].item_instance().unwrap().quant(), 10);
+        assert_eq!(slots[1].item_instance().unwrap().quant(), 2);
+    });
+    assert_eq!(b.quant(TORCH.id()), 98);
+}
@@ -0,0 +1,86 @@
+use game_inventory::helpers::{add_to_inventory, remove_from_inventory};
+use game_inventory::samples::{
+    DefaultItemInstance, DefaultSlot, JUNK, JUNK_INST, SWORD, SWORD_INST, TORCH,
+    TORCH_FULL_STACK_INST, TORCH_INST,
+};
+use game_inventory::snapshot::{QuantDelta, Snapshot};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+#[test]
+fn add_to_inventory_changes() {
+    let mut inventory = vec![
+        DefaultSlot::new(TORCH_FULL_STACK_INST.clone()),
+        DefaultSlot::new(Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), 90))),
+        DefaultSlot::new(SWORD_INST.clone()),
+        DefaultSlot::new(None),
+    ];
+    let before = Snapshot::take(&inventory);
+    add_to_inventory(
+        &mut inventory,
+        DefaultItemInstance::new(Arc::new(TORCH.clone()), 30),
+    );
+    let diff = before.diff(&Snapshot::take(&inventory));
+
+    assert_eq!(diff.slots.len(), 2);
+    assert_eq!(diff.slots[0].slot, 1);
+    assert_eq!(diff.slots[0].before.as_ref().unwrap().quant(), 90);
+    assert_eq!(diff.slots[0].after.as_ref().unwrap().quant(), 100);
+    assert_eq!(diff.slots[1].slot, 3);
+    assert!(diff.slots[1].before.is_none());
+    assert_eq!(diff.slots[1].after.as_ref().unwrap().quant(), 20);
+    assert_eq!(
+        diff.items,
+        vec![(
+            TORCH.id(),
+            QuantDelta {
+                before: 190,
+                after: 220
+            }
+        )]
+    );
+}
+
+#[test]
+fn remove_from_inventory_changes() {
+    let mut inventory = vec![
+        DefaultSlot::new(TORCH_INST.clone()),
+        DefaultSlot::new(SWORD_INST.clone()),
+        DefaultSlot::new(JUNK_INST.clone()),
+        DefaultSlot::new(TORCH_INST.clone()),
+    ];
+    let before = Snapshot::take(&inventory);
+    remove_from_inventory(
+        &mut inventory,
+        DefaultItemInstance::new(Arc::new(TORCH.clone()), 30),
+    );
+    remove_from_inventory(
+        &mut inventory,
+        DefaultItemInstance::new(Arc::new(SWORD.clone()), 1),
+    );
+    let diff = before.diff(&Snapshot::take(&inventory));
+
+    assert_eq!(
+        diff.slots
+            .iter()
+            .map(|change| change.slot)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+    assert_eq!(diff.quant_delta(TORCH.id()).lost(), 30);
+    assert_eq!(diff.quant_delta(SWORD.id()).lost(), 1);
+    assert_eq!(diff.quant_delta(JUNK.id()), QuantDelta::default());
+    assert_eq!(diff.gained().count(), 0);
+}
+
+#[test]
+fn moving_items_is_not_a_quantity_change() {
+    let inventory = vec![DefaultSlot::new(TORCH_INST.clone()), DefaultSlot::new(None)];
+    let moved = vec![DefaultSlot::new(None), DefaultSlot::new(TORCH_INST.clone())];
+    let diff = Snapshot::take(&inventory).diff(&Snapshot::take(&moved));
+    assert_eq!(diff.slots.len(), 2);
+    assert!(diff.items.is_empty());
+    assert!(Snapshot::take(&inventory)
+        .diff(&Snapshot::take(&inventory))
+        .is_empty());
+}
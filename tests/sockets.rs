@@ -0,0 +1,141 @@
+use game_inventory::equipment::Modifier;
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::samples::{DefaultSlot, Gear, ReservableSlot, SocketedItemInstance};
+use game_inventory::sockets::{
+    insert_gem, remove_gem, socketed_modifiers, Removal, SocketError, Socketed,
+};
+use game_inventory::traits::{ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = SocketedItemInstance<Gear<'static>>;
+
+fn gear(
+    name: &'static str,
+    max_quantity: u16,
+    modifiers: Vec<Modifier<&'static str>>,
+) -> Arc<Gear<'static>> {
+    Arc::new(Gear {
+        name,
+        max_quantity,
+        equip_slot: None,
+        two_handed: false,
+        set: None,
+        modifiers,
+    })
+}
+
+fn ruby() -> Arc<Gear<'static>> {
+    gear("ruby", 10, vec![Modifier::Add("fire", 3.0)])
+}
+
+fn sword(sockets: usize) -> Inst {
+    let sword = gear("sword", 1, vec![Modifier::Add("damage", 10.0)]);
+    SocketedItemInstance::with_sockets(sword, sockets)
+}
+
+fn gems(item: Arc<Gear<'static>>, quant: u16) -> Option<Inst> {
+    Some(SocketedItemInstance::new(item, quant))
+}
+
+#[test]
+fn gems_come_off_and_go_back_on_stacks() {
+    let mut inventory = vec![DefaultSlot::new(gems(ruby(), 2)), DefaultSlot::new(None)];
+    let mut sword = sword(3);
+
+    insert_gem(&mut sword, 0, &mut inventory, 0).unwrap();
+    insert_gem(&mut sword, 2, &mut inventory, 0).unwrap();
+    assert!(inventory[0].item_instance().is_none());
+    assert_eq!(sword.sockets()[0].as_ref().unwrap().quant(), 1);
+    assert_eq!(
+        insert_gem(&mut sword, 1, &mut inventory, 0),
+        Err(SocketError::NoGem)
+    );
+    assert_eq!(
+        insert_gem(&mut sword, 2, &mut inventory, 0),
+        Err(SocketError::Occupied)
+    );
+    assert_eq!(
+        insert_gem(&mut sword, 3, &mut inventory, 0),
+        Err(SocketError::NoSuchSocket)
+    );
+
+    remove_gem(&mut sword, 0, &mut inventory, Removal::Return).unwrap();
+    remove_gem(&mut sword, 2, &mut inventory, Removal::Return).unwrap();
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 2);
+    assert!(inventory[1].item_instance().is_none());
+    assert!(sword.sockets().iter().all(Option::is_none));
+    assert_eq!(
+        remove_gem(&mut sword, 1, &mut inventory, Removal::Return),
+        Err(SocketError::EmptySocket)
+    );
+}
+
+#[test]
+fn removed_gems_can_be_destroyed() {
+    let mut inventory = vec![DefaultSlot::new(gems(ruby(), 1))];
+    let mut sword = sword(1);
+    insert_gem(&mut sword, 0, &mut inventory, 0).unwrap();
+
+    // The inventory now has a sword in it, so there is no room for the ruby.
+    inventory[0].set_item_instance(&Some(self::sword(0)));
+    assert_eq!(
+        remove_gem(&mut sword, 0, &mut inventory, Removal::Return),
+        Err(SocketError::NoSpace)
+    );
+    assert!(sword.sockets()[0].is_some());
+    remove_gem(&mut sword, 0, &mut inventory, Removal::Destroy).unwrap();
+    assert!(sword.sockets()[0].is_none());
+    assert_eq!(quant_in_inventory(&inventory, "ruby"), 0);
+}
+
+#[test]
+fn unstackable_runes_move_whole() {
+    let rune = gear("rune", 1, vec![Modifier::Multiply("damage", 1.5)]);
+    let mut inventory = vec![DefaultSlot::new(Some(SocketedItemInstance::new(rune, 1)))];
+    let mut sword = sword(1);
+    insert_gem(&mut sword, 0, &mut inventory, 0).unwrap();
+    assert!(inventory[0].item_instance().is_none());
+    assert_eq!(
+        socketed_modifiers(&sword),
+        vec![
+            Modifier::Add("damage", 10.0),
+            Modifier::Multiply("damage", 1.5)
+        ]
+    );
+    remove_gem(&mut sword, 0, &mut inventory, Removal::Return).unwrap();
+    assert_eq!(inventory[0].item_instance().unwrap().item().name, "rune");
+}
+
+#[test]
+fn modifiers_add_up_through_nested_sockets() {
+    let mut amulet = SocketedItemInstance::with_sockets(gear("amulet", 1, Vec::new()), 1);
+    let mut setting = SocketedItemInstance::with_sockets(gear("setting", 1, Vec::new()), 1);
+    setting.set_socket(0, gems(ruby(), 1));
+    amulet.set_socket(0, Some(setting));
+    assert_eq!(
+        socketed_modifiers(&amulet),
+        vec![Modifier::Add("fire", 3.0)]
+    );
+
+    // Items with a gem in them keep it to themselves instead of stacking.
+    assert!(!amulet.stacks_with(&amulet.clone()));
+    amulet.set_socket(0, None);
+    assert!(amulet.stacks_with(&amulet.clone()));
+}
+
+#[test]
+fn locked_gems_stay_put() {
+    let mut inventory = vec![
+        ReservableSlot::new(gems(ruby(), 3)),
+        ReservableSlot::new(None),
+    ];
+    inventory[0].locked = true;
+    let mut sword = sword(1);
+    assert_eq!(
+        insert_gem(&mut sword, 0, &mut inventory, 0),
+        Err(SocketError::NoGem)
+    );
+    inventory[0].locked = false;
+    insert_gem(&mut sword, 0, &mut inventory, 0).unwrap();
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 2);
+}
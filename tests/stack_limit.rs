@@ -0,0 +1,147 @@
+use game_inventory::helpers::{
+    add_to_inventory, can_add_item_to_inventory, combine_stack_limited, half_stack_split_limited,
+    quant_in_inventory, remove_from_stack_limited, StackLimit,
+};
+use game_inventory::indexed_inventory::IndexedInventory;
+use game_inventory::machine::{Machine, Recipe, RecipeBook};
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, LimitedSlot, JUNK, SWORD, SWORD_INST, TORCH,
+    TORCH_INST,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+fn torches(quant: u16) -> Option<DefaultItemInstance<DefaultItem<'static>>> {
+    Some(DefaultItemInstance::new(Arc::new(TORCH.clone()), quant))
+}
+
+#[test]
+fn limits_are_resolved_against_the_item() {
+    assert_eq!(StackLimit::Item.of(&TORCH), 100);
+    assert_eq!(StackLimit::Cap(20).of(&TORCH), 20);
+    assert_eq!(StackLimit::Cap(500).of(&TORCH), 100);
+    assert_eq!(StackLimit::Override(500).of(&TORCH), 500);
+    let slot = LimitedSlot::with_limit(torches(1), StackLimit::Override(250));
+    assert_eq!(slot.stack_limit(&TORCH), 250);
+    assert_eq!(DefaultSlot::new(torches(1)).stack_limit(&TORCH), 100);
+    // Unstackable items take up a stack of one.
+    assert_eq!(StackLimit::Item.of(&SWORD), 1);
+    assert_eq!(StackLimit::Cap(0).of(&SWORD), 0);
+}
+
+#[test]
+fn slot_helpers_respect_the_limit() {
+    let res = combine_stack_limited((torches(30), torches(10)), 25)
+        .ok()
+        .unwrap();
+    assert_eq!(res.0.unwrap().quant(), 15);
+    assert_eq!(res.1.unwrap().quant(), 25);
+    assert!(combine_stack_limited((torches(30), torches(25)), 25).is_err());
+
+    let res = half_stack_split_limited((torches(40), torches(15)), 25)
+        .ok()
+        .unwrap();
+    assert_eq!(res.0.unwrap().quant(), 30);
+    assert_eq!(res.1.unwrap().quant(), 25);
+    assert!(half_stack_split_limited((torches(40), torches(25)), 25).is_err());
+
+    let res = remove_from_stack_limited((torches(40), torches(24)), 25)
+        .ok()
+        .unwrap();
+    assert_eq!(res.0.unwrap().quant(), 39);
+    assert_eq!(res.1.unwrap().quant(), 25);
+    assert!(remove_from_stack_limited((torches(40), torches(25)), 25).is_err());
+}
+
+#[test]
+fn adding_splits_stacks_over_capped_slots() {
+    let mut inventory = vec![
+        LimitedSlot::with_limit(torches(5), StackLimit::Cap(10)),
+        LimitedSlot::with_limit(SWORD_INST.clone(), StackLimit::Cap(10)),
+        LimitedSlot::with_limit(None, StackLimit::Cap(10)),
+        LimitedSlot::with_limit(None, StackLimit::Item),
+    ];
+    assert!(can_add_item_to_inventory(&inventory, torches(115).unwrap()));
+    assert!(!can_add_item_to_inventory(
+        &inventory,
+        torches(116).unwrap()
+    ));
+
+    assert!(add_to_inventory(&mut inventory, torches(30).unwrap()).is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 10);
+    assert_eq!(inventory[2].item_instance().unwrap().quant(), 10);
+    assert_eq!(inventory[3].item_instance().unwrap().quant(), 15);
+
+    let left = add_to_inventory(&mut inventory, torches(90).unwrap());
+    assert_eq!(left.unwrap().quant(), 5);
+    assert_eq!(quant_in_inventory(&inventory, TORCH.id()), 120);
+}
+
+#[test]
+fn overridden_slots_hold_more_than_the_item_allows() {
+    let mut inventory = vec![LimitedSlot::with_limit(None, StackLimit::Override(1000))];
+    assert!(add_to_inventory(&mut inventory, torches(600).unwrap()).is_none());
+    assert!(add_to_inventory(&mut inventory, torches(400).unwrap()).is_none());
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 1000);
+    assert!(!can_add_item_to_inventory(&inventory, torches(1).unwrap()));
+}
+
+#[test]
+fn indexed_inventories_respect_the_limit() {
+    let mut inventory = IndexedInventory::new(vec![
+        LimitedSlot::with_limit(TORCH_INST.clone(), StackLimit::Cap(30)),
+        LimitedSlot::with_limit(None, StackLimit::Cap(0)),
+        LimitedSlot::with_limit(None, StackLimit::Override(200)),
+    ]);
+    assert!(inventory.can_add_item(&torches(207).unwrap()));
+    assert!(!inventory.can_add_item(&torches(208).unwrap()));
+
+    let left = inventory.add(torches(210).unwrap());
+    assert_eq!(left.unwrap().quant(), 3);
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 30);
+    assert!(inventory[1].item_instance().is_none());
+    assert_eq!(inventory[2].item_instance().unwrap().quant(), 200);
+    assert_eq!(inventory.quant(TORCH.id()), 230);
+}
+
+#[test]
+fn slots_capped_at_zero_take_no_unstackables() {
+    let mut inventory = vec![
+        LimitedSlot::with_limit(None, StackLimit::Cap(0)),
+        LimitedSlot::with_limit(None, StackLimit::Item),
+    ];
+    assert!(add_to_inventory(&mut inventory, SWORD_INST.clone().unwrap()).is_none());
+    assert!(inventory[0].item_instance().is_none());
+    assert!(inventory[1].item_instance().is_some());
+    assert!(!can_add_item_to_inventory(
+        &inventory,
+        SWORD_INST.clone().unwrap()
+    ));
+    assert!(add_to_inventory(&mut inventory, SWORD_INST.clone().unwrap()).is_some());
+
+    let mut indexed =
+        IndexedInventory::new(vec![LimitedSlot::with_limit(None, StackLimit::Cap(0))]);
+    assert!(!indexed.can_add_item(&SWORD_INST.clone().unwrap()));
+    assert!(indexed.add(SWORD_INST.clone().unwrap()).is_some());
+}
+
+#[test]
+fn machines_respect_the_output_limit() {
+    let mut book = RecipeBook::new();
+    book.add_recipe(Recipe {
+        input: JUNK.id(),
+        input_quant: 1,
+        output: torches(10).unwrap(),
+        ticks: 1,
+    });
+    book.add_fuel(TORCH.id(), 100);
+    let mut furnace: Machine<_, _, LimitedSlot<_, _>> = Machine::new();
+    furnace.output = LimitedSlot::with_limit(None, StackLimit::Cap(25));
+    furnace
+        .input
+        .set_item_instance(&Some(DefaultItemInstance::new(Arc::new(JUNK.clone()), 5)));
+    furnace.fuel.set_item_instance(&torches(1));
+    assert_eq!(furnace.tick(&book, 5), 2);
+    assert_eq!(furnace.output.item_instance().unwrap().quant(), 20);
+    assert_eq!(furnace.input.item_instance().unwrap().quant(), 3);
+}
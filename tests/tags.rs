@@ -0,0 +1,69 @@
+use game_inventory::helpers::{
+    inventory_contains_tag, quant_with_tag_in_inventory, remove_tagged_from_inventory,
+};
+use game_inventory::samples::{
+    DefaultItemInstance, DefaultSlot, TaggedItem, BIRCH_PLANK, OAK_LOG, OAK_PLANK, TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<TaggedItem<'static>>;
+
+fn stack(item: &TaggedItem<'static>, quant: u16) -> Option<Inst> {
+    Some(DefaultItemInstance::new(Arc::new(item.clone()), quant))
+}
+
+static AXE: TaggedItem = TaggedItem {
+    name: "axe",
+    max_quantity: 1,
+    tags: &["tool/axe", "wood"],
+};
+
+#[test]
+fn tags_match_their_parents() {
+    assert!(OAK_LOG.has_tag("wood/log"));
+    assert!(OAK_LOG.has_tag("wood"));
+    assert!(OAK_LOG.has_tag("fuel"));
+    assert!(!OAK_LOG.has_tag("wood/plank"));
+    assert!(!OAK_LOG.has_tag("wood/lo"));
+    assert!(!OAK_LOG.has_tag("log"));
+    assert!(!OAK_LOG.has_tag("wood/log/oak"));
+    assert!(!TORCH.has_tag("torch"));
+}
+
+#[test]
+fn unstackable_items_count_as_one() {
+    let mut inventory: Vec<DefaultSlot<_, Inst>> = vec![
+        DefaultSlot::new(stack(&AXE, 0)),
+        DefaultSlot::new(stack(&OAK_PLANK, 4)),
+        DefaultSlot::new(stack(&AXE, 0)),
+    ];
+    assert!(inventory_contains_tag(&inventory, "tool"));
+    assert!(!inventory_contains_tag(&inventory, "fuel"));
+    assert_eq!(quant_with_tag_in_inventory(&inventory, "wood"), 6);
+    assert_eq!(remove_tagged_from_inventory(&mut inventory, "tool", 1), 0);
+    assert!(inventory[0].item_instance().is_none());
+    assert_eq!(quant_with_tag_in_inventory(&inventory, "tool/axe"), 1);
+}
+
+#[test]
+fn only_tagged_items_are_removed() {
+    let mut inventory: Vec<DefaultSlot<_, Inst>> = vec![
+        DefaultSlot::new(stack(&OAK_LOG, 10)),
+        DefaultSlot::new(stack(&OAK_PLANK, 2)),
+        DefaultSlot::new(None),
+        DefaultSlot::new(stack(&BIRCH_PLANK, 2)),
+    ];
+    assert_eq!(
+        remove_tagged_from_inventory(&mut inventory, "wood/plank", 3),
+        0
+    );
+    assert_eq!(inventory[0].item_instance().unwrap().quant(), 10);
+    assert!(inventory[1].item_instance().is_none());
+    assert_eq!(inventory[3].item_instance().unwrap().quant(), 1);
+    assert_eq!(
+        remove_tagged_from_inventory(&mut inventory, "wood/plank", 0),
+        0
+    );
+    assert_eq!(quant_with_tag_in_inventory(&inventory, "wood"), 11);
+}
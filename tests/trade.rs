@@ -0,0 +1,198 @@
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, DurableItemInstance, LimitedSlot, JUNK, SWORD,
+    SWORD_INST, TORCH,
+};
+use game_inventory::slot_management::StackLimit;
+use game_inventory::trade::{Side, TradeError, TradeSession};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Inventory = Vec<DefaultSlot<'static, DefaultItem<'static>, Inst>>;
+
+fn stack(item: &DefaultItem<'static>, quant: u16) -> Inst {
+    DefaultItemInstance::new(Arc::new(item.clone()), quant)
+}
+
+fn inventory(slots: Vec<Option<Inst>>) -> Inventory {
+    slots.into_iter().map(DefaultSlot::new).collect()
+}
+
+fn agree(trade: &mut TradeSession<DefaultItem<'static>, Inst>) {
+    trade.lock(Side::Left);
+    trade.lock(Side::Right);
+    trade.confirm(Side::Left).unwrap();
+    trade.confirm(Side::Right).unwrap();
+}
+
+#[test]
+fn offers_must_be_held() {
+    let alice = inventory(vec![Some(stack(&TORCH, 30)), Some(stack(&TORCH, 10))]);
+    let mut trade = TradeSession::new();
+    trade.offer(Side::Left, &alice, 0, 25).unwrap();
+    assert_eq!(
+        trade.offer(Side::Left, &alice, 0, 20),
+        Err(TradeError::MissingItems(Side::Left))
+    );
+    trade.offer(Side::Left, &alice, 1, 10).unwrap();
+    assert_eq!(
+        trade.offer(Side::Left, &alice, 1, 1),
+        Err(TradeError::MissingItems(Side::Left))
+    );
+    assert_eq!(
+        trade.offer(Side::Left, &alice, 2, 1),
+        Err(TradeError::MissingItems(Side::Left))
+    );
+    assert_eq!(trade.offers(Side::Left).len(), 2);
+}
+
+#[test]
+fn changes_take_back_confirmations() {
+    let alice = inventory(vec![Some(stack(&TORCH, 30))]);
+    let bob = inventory(vec![Some(stack(&JUNK, 30))]);
+    let mut trade = TradeSession::new();
+    trade.offer(Side::Left, &alice, 0, 10).unwrap();
+    trade.lock(Side::Left);
+    assert_eq!(trade.confirm(Side::Left), Err(TradeError::NotLocked));
+    assert_eq!(
+        trade.offer(Side::Left, &alice, 0, 10),
+        Err(TradeError::Locked)
+    );
+
+    trade.offer(Side::Right, &bob, 0, 5).unwrap();
+    trade.lock(Side::Right);
+    trade.confirm(Side::Left).unwrap();
+    trade.confirm(Side::Right).unwrap();
+
+    trade.unlock(Side::Right);
+    assert!(!trade.is_confirmed(Side::Left));
+    assert!(!trade.is_confirmed(Side::Right));
+    assert_eq!(
+        trade
+            .withdraw(Side::Right, 0)
+            .unwrap()
+            .item_instance
+            .quant(),
+        5
+    );
+    assert_eq!(
+        trade.withdraw(Side::Right, 0).err(),
+        Some(TradeError::NoSuchOffer)
+    );
+    assert!(trade.is_locked(Side::Left));
+}
+
+#[test]
+fn nothing_moves_if_a_side_lost_its_items() {
+    let mut alice = inventory(vec![SWORD_INST.clone(), None]);
+    let mut bob = inventory(vec![Some(stack(&TORCH, 50)), None]);
+    let mut trade = TradeSession::new();
+    trade.offer(Side::Left, &alice, 0, 1).unwrap();
+    trade.offer(Side::Right, &bob, 0, 50).unwrap();
+    assert_eq!(
+        trade.execute(&mut alice, &mut bob),
+        Err(TradeError::NotConfirmed)
+    );
+    agree(&mut trade);
+
+    // Bob drops some torches after confirming.
+    bob[0].set_item_instance(&Some(stack(&TORCH, 49)));
+    assert_eq!(
+        trade.execute(&mut alice, &mut bob),
+        Err(TradeError::MissingItems(Side::Right))
+    );
+    assert!(!trade.is_confirmed(Side::Left));
+    assert_eq!(quant_in_inventory(&alice, SWORD.id()), 1);
+    assert_eq!(quant_in_inventory(&bob, TORCH.id()), 49);
+}
+
+#[test]
+fn recipients_need_room() {
+    let mut alice = inventory(vec![Some(stack(&TORCH, 100)), Some(stack(&JUNK, 10))]);
+    let mut bob = inventory(vec![Some(stack(&JUNK, 95)), None]);
+    let mut trade = TradeSession::new();
+    trade.offer(Side::Right, &bob, 0, 95).unwrap();
+    trade.offer(Side::Left, &alice, 0, 60).unwrap();
+    agree(&mut trade);
+
+    // Alice's junk stack only fits 90 more.
+    assert_eq!(
+        trade.execute(&mut alice, &mut bob),
+        Err(TradeError::NoSpace(Side::Left))
+    );
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 100);
+    assert_eq!(quant_in_inventory(&bob, JUNK.id()), 95);
+
+    // Giving the whole torch stack away frees a slot.
+    trade.unlock(Side::Left);
+    trade.offer(Side::Left, &alice, 0, 40).unwrap();
+    agree(&mut trade);
+    trade.execute(&mut alice, &mut bob).unwrap();
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 0);
+    assert_eq!(quant_in_inventory(&alice, JUNK.id()), 105);
+    assert_eq!(quant_in_inventory(&bob, TORCH.id()), 100);
+    assert_eq!(quant_in_inventory(&bob, JUNK.id()), 0);
+    assert!(trade.offers(Side::Left).is_empty());
+    assert!(!trade.is_locked(Side::Right));
+}
+
+#[test]
+fn slot_limits_are_respected() {
+    let mut alice = inventory(vec![Some(stack(&TORCH, 30))]);
+    let mut chest: Vec<LimitedSlot<_, Inst>> = vec![
+        LimitedSlot::with_limit(None, StackLimit::Cap(10)),
+        LimitedSlot::with_limit(None, StackLimit::Cap(10)),
+    ];
+    let mut trade = TradeSession::new();
+    trade.offer(Side::Left, &alice, 0, 30).unwrap();
+    agree(&mut trade);
+    assert_eq!(
+        trade.execute(&mut alice, &mut chest),
+        Err(TradeError::NoSpace(Side::Right))
+    );
+    assert_eq!(quant_in_inventory(&alice, TORCH.id()), 30);
+    assert_eq!(quant_in_inventory(&chest, TORCH.id()), 0);
+}
+
+#[test]
+fn traded_items_are_the_ones_taken() {
+    type Durable = DurableItemInstance<DefaultItem<'static>>;
+    let torches = |quant, condition| {
+        let mut ii: Durable = DurableItemInstance::new(Arc::new(TORCH.clone()), quant);
+        ii.condition = condition;
+        Some(ii)
+    };
+    let mut alice: Vec<DefaultSlot<_, Durable>> =
+        vec![DefaultSlot::new(torches(10, 0.5)), DefaultSlot::new(None)];
+    let mut bob = vec![DefaultSlot::new(torches(4, 1.0)), DefaultSlot::new(None)];
+    let mut trade = TradeSession::new();
+    trade.offer(Side::Left, &alice, 0, 6).unwrap();
+    assert_eq!(trade.offers(Side::Left)[0].item_instance.condition, 0.5);
+
+    // Alice swaps in fresh torches after offering her worn ones.
+    alice[0].set_item_instance(&torches(10, 1.0));
+    trade.lock(Side::Left);
+    trade.lock(Side::Right);
+    trade.confirm(Side::Left).unwrap();
+    trade.confirm(Side::Right).unwrap();
+    assert_eq!(
+        trade.execute(&mut alice, &mut bob),
+        Err(TradeError::MissingItems(Side::Left))
+    );
+
+    alice[0].set_item_instance(&torches(10, 0.5));
+    trade.unlock(Side::Right);
+    trade.offer(Side::Right, &bob, 0, 4).unwrap();
+    trade.lock(Side::Left);
+    trade.lock(Side::Right);
+    trade.confirm(Side::Left).unwrap();
+    trade.confirm(Side::Right).unwrap();
+    trade.execute(&mut alice, &mut bob).unwrap();
+    // The worn torches do not stack onto fresh ones.
+    assert_eq!(alice[0].item_instance().unwrap().quant(), 4);
+    assert_eq!(alice[0].item_instance().unwrap().condition, 0.5);
+    assert_eq!(alice[1].item_instance().unwrap().condition, 1.0);
+    assert_eq!(bob[0].item_instance().unwrap().quant(), 6);
+    assert_eq!(bob[0].item_instance().unwrap().condition, 0.5);
+}
@@ -0,0 +1,182 @@
+use game_inventory::helpers::quant_in_inventory;
+use game_inventory::samples::{
+    DefaultItem, DefaultItemInstance, DefaultSlot, DurableItemInstance, JUNK, SWORD, SWORD_INST,
+    TORCH,
+};
+use game_inventory::traits::{Item, ItemInstance, Slot};
+use game_inventory::transport::{Distribution, Filter, Link, Transfer, Transport};
+use std::sync::Arc;
+
+type Inst = DefaultItemInstance<DefaultItem<'static>>;
+type Inventory = Vec<DefaultSlot<'static, DefaultItem<'static>, Inst>>;
+
+fn stack(item: &DefaultItem<'static>, quant: u16) -> Option<Inst> {
+    Some(DefaultItemInstance::new(Arc::new(item.clone()), quant))
+}
+
+fn inventory(slots: Vec<Option<Inst>>) -> Inventory {
+    slots.into_iter().map(DefaultSlot::new).collect()
+}
+
+fn empty(len: usize) -> Inventory {
+    inventory(vec![None; len])
+}
+
+#[test]
+fn filters_pick_the_items() {
+    let mut inventories = vec![inventory(vec![stack(&JUNK, 5), stack(&TORCH, 5)]), empty(2)];
+    let mut link = Link::new(0, vec![1]);
+    link.filter = Filter::Only(vec![TORCH.id()]);
+    link.amount = 10;
+    let mut transport = Transport::new();
+    transport.add_link(link);
+    transport.tick(&mut inventories);
+    assert_eq!(quant_in_inventory(&inventories[1], TORCH.id()), 5);
+    assert_eq!(quant_in_inventory(&inventories[1], JUNK.id()), 0);
+
+    transport.link_mut(0).unwrap().filter = Filter::Except(vec![TORCH.id()]);
+    transport.tick(&mut inventories);
+    assert_eq!(quant_in_inventory(&inventories[1], JUNK.id()), 5);
+    assert!(transport.tick(&mut inventories).is_empty());
+}
+
+#[test]
+fn priority_fills_targets_in_order() {
+    let mut inventories = vec![
+        inventory(vec![stack(&TORCH, 100)]),
+        inventory(vec![stack(&TORCH, 95)]),
+        empty(1),
+    ];
+    let mut link = Link::new(0, vec![1, 2]);
+    link.amount = 20;
+    let mut transport = Transport::new();
+    transport.add_link(link);
+
+    let transfers = transport.tick(&mut inventories);
+    assert_eq!(
+        transfers,
+        vec![
+            Transfer {
+                link: 0,
+                source: 0,
+                target: 1,
+                id: TORCH.id(),
+                quant: 5
+            },
+            Transfer {
+                link: 0,
+                source: 0,
+                target: 2,
+                id: TORCH.id(),
+                quant: 15
+            },
+        ]
+    );
+    assert_eq!(quant_in_inventory(&inventories[0], TORCH.id()), 80);
+}
+
+#[test]
+fn links_wait_for_their_interval() {
+    let mut inventories = vec![inventory(vec![stack(&TORCH, 10)]), empty(1)];
+    let mut link = Link::new(0, vec![1]);
+    link.interval = 4;
+    let mut transport = Transport::new();
+    transport.add_link(link);
+    for _ in 0..9 {
+        transport.tick(&mut inventories);
+    }
+    // Moves on ticks 1, 5 and 9.
+    assert_eq!(quant_in_inventory(&inventories[1], TORCH.id()), 3);
+}
+
+#[test]
+fn unstackable_items_keep_their_instance() {
+    let sword = |quant| stack(&SWORD, quant);
+    let mut inventories = vec![
+        inventory(vec![SWORD_INST.clone(), sword(7), None]),
+        empty(1),
+        empty(1),
+    ];
+    let mut link = Link::new(0, vec![0, 1, 2]);
+    link.distribution = Distribution::RoundRobin;
+    link.amount = 5;
+    let mut transport = Transport::new();
+    transport.add_link(link);
+
+    let transfers = transport.tick(&mut inventories);
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].target, 1);
+    assert_eq!(transfers[0].quant, 1);
+    transport.tick(&mut inventories);
+    assert_eq!(inventories[2][0].item_instance().unwrap().quant(), 7);
+    assert!(transport.tick(&mut inventories).is_empty());
+    assert_eq!(quant_in_inventory(&inventories[0], SWORD.id()), 0);
+}
+
+#[test]
+fn items_leave_the_slot_they_were_taken_from() {
+    let torches = |quant, condition| {
+        let mut torches = DurableItemInstance::new(Arc::new(TORCH.clone()), quant);
+        torches.condition = condition;
+        Some(torches)
+    };
+    let mut inventories = vec![
+        vec![
+            DefaultSlot::new(torches(10, 0.5)),
+            DefaultSlot::new(torches(10, 1.0)),
+        ],
+        vec![DefaultSlot::new(torches(90, 1.0))],
+    ];
+    let mut link = Link::new(0, vec![1]);
+    link.amount = 5;
+    let mut transport = Transport::new();
+    transport.add_link(link);
+
+    // The worn torches do not stack with the target's, so only the new ones move.
+    assert_eq!(transport.tick(&mut inventories).len(), 1);
+    let worn = inventories[0][0].item_instance().unwrap();
+    assert_eq!((worn.quant(), worn.condition), (10, 0.5));
+    assert_eq!(inventories[0][1].item_instance().unwrap().quant(), 5);
+    assert_eq!(inventories[1][0].item_instance().unwrap().quant(), 95);
+}
+
+#[test]
+fn chains_replay_identically() {
+    let run = || {
+        let mut inventories = vec![
+            inventory(vec![stack(&TORCH, 40), stack(&JUNK, 40)]),
+            empty(2),
+            empty(2),
+            empty(1),
+        ];
+        let mut transport = Transport::new();
+        let mut splitter = Link::new(0, vec![1, 2]);
+        splitter.distribution = Distribution::RoundRobin;
+        splitter.amount = 7;
+        transport.add_link(splitter);
+        let mut belt = Link::new(1, vec![3]);
+        belt.amount = 3;
+        belt.interval = 2;
+        transport.add_link(belt);
+        transport.add_link(Link::new(2, vec![3]));
+
+        let mut log = vec![];
+        for _ in 0..30 {
+            log.extend(transport.tick(&mut inventories));
+        }
+        (log, inventories)
+    };
+    let (log, inventories) = run();
+    let (again, _) = run();
+    assert_eq!(log, again);
+
+    // Items moved by the splitter are carried along the belt in the same tick.
+    assert_eq!(log[1].source, 1);
+    let total: u128 = inventories
+        .iter()
+        .map(|inventory| {
+            quant_in_inventory(inventory, TORCH.id()) + quant_in_inventory(inventory, JUNK.id())
+        })
+        .sum();
+    assert_eq!(total, 80);
+}